@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "audit_event"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key))]
+    pub id: i64,
+    pub actor_id: String,
+    pub action: String,
+    pub target_ids: serde_json::Value,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
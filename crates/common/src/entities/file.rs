@@ -21,11 +21,28 @@ pub struct Model {
     pub file_type: String,
     pub path: String,
     pub is_directory: bool,
+    pub item_count: i64,
 }
 
 #[cfg(feature = "ssr")]
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
 
 #[cfg(feature = "ssr")]
 impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
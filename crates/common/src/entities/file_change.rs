@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// Written by the `record_file_change` trigger, never by the services themselves. `sequence`
+/// is filled in by the `sequence_file_change` trigger as the writing transaction commits, so
+/// it is set on every row another transaction can see.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "file_change"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key))]
+    pub id: i64,
+    pub owner_id: String,
+    pub file_id: String,
+    pub kind: String,
+    /// The row after the change, or as it was right before it was deleted.
+    pub entry: serde_json::Value,
+    pub created_at: DateTime<FixedOffset>,
+    /// Numbers an owner's changes in commit order, the cursor clients resume from.
+    pub sequence: Option<i64>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
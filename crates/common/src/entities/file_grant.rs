@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// Access another user has to an entry and everything below it. `role` is `viewer` or
+/// `editor`, see `GrantRole`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "file_grant"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub id: String,
+    pub file_id: String,
+    pub owner_id: String,
+    pub grantee_id: String,
+    pub role: String,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::FileId",
+        to = "super::file::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    File,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Owner,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::GranteeId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Grantee,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+/// The user a grant is for. `Related` to the user entity is left out since there are two
+/// ways to get there.
+#[cfg(feature = "ssr")]
+pub struct GranteeLink;
+
+#[cfg(feature = "ssr")]
+impl Linked for GranteeLink {
+    type FromEntity = Entity;
+    type ToEntity = super::user::Entity;
+
+    fn link(&self) -> Vec<RelationDef> {
+        vec![Relation::Grantee.def()]
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// An upload-only link into one of the owner's folders. `allowed_types` is a JSON array of
+/// MIME types such as `image/*` or extensions such as `.pdf`, `null` for anything.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "file_request"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub token: String,
+    pub owner_id: String,
+    pub folder_id: String,
+    pub title: Option<String>,
+    pub max_file_size: Option<i64>,
+    pub max_files: Option<i32>,
+    pub allowed_types: Option<serde_json::Value>,
+    pub upload_count: i32,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::FolderId",
+        to = "super::file::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    File,
+    #[sea_orm(has_many = "super::file_request_upload::Entity")]
+    FileRequestUpload,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::file_request_upload::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FileRequestUpload.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
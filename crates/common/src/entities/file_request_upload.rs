@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// Who sent a file through a file request, as they described themselves. `token` is cleared
+/// when the request is deleted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "file_request_upload"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub file_id: String,
+    pub token: Option<String>,
+    pub owner_id: String,
+    pub uploader_name: String,
+    pub uploader_email: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::FileId",
+        to = "super::file::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    File,
+    #[sea_orm(
+        belongs_to = "super::file_request::Entity",
+        from = "Column::Token",
+        to = "super::file_request::Column::Token",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    FileRequest,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::file_request::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FileRequest.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
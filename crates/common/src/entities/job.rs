@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "job"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub id: String,
+    pub owner_id: String,
+    pub submitted_by: String,
+    pub kind: String,
+    pub status: String,
+    pub payload: serde_json::Value,
+    pub total_items: i64,
+    pub processed_items: i64,
+    pub errors: serde_json::Value,
+    pub result: Option<serde_json::Value>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+    pub completed_at: Option<DateTime<FixedOffset>>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// A top-level entry a copy job has already copied, and the id of its copy. A job that is
+/// run again after a restart skips these instead of copying them a second time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "job_copy"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub job_id: String,
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub source_id: String,
+    pub new_id: String,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::job::Entity",
+        from = "Column::JobId",
+        to = "super::job::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Job,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::job::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Job.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
@@ -5,3 +5,16 @@ pub mod refresh_token;
 pub mod user;
 pub mod auth_session;
 pub mod passkey;
+pub mod job;
+pub mod storage_outbox;
+pub mod audit_event;
+pub mod webhook;
+pub mod webhook_delivery;
+pub mod file_change;
+pub mod share;
+pub mod file_grant;
+pub mod file_request;
+pub mod file_request_upload;
+pub mod share_access;
+pub mod share_item;
+pub mod job_copy;
@@ -8,7 +8,34 @@ pub use super::user::Entity as User;
 pub use super::auth_session::Entity as AuthSession;
 #[cfg(feature = "ssr")]
 pub use super::passkey::Entity as Passkey;
+#[cfg(feature = "ssr")]
+pub use super::job::Entity as Job;
+#[cfg(feature = "ssr")]
+pub use super::job_copy::Entity as JobCopy;
+#[cfg(feature = "ssr")]
+pub use super::storage_outbox::Entity as StorageOutbox;
+#[cfg(feature = "ssr")]
+pub use super::audit_event::Entity as AuditEvent;
+#[cfg(feature = "ssr")]
+pub use super::webhook::Entity as Webhook;
+#[cfg(feature = "ssr")]
+pub use super::webhook_delivery::Entity as WebhookDelivery;
+#[cfg(feature = "ssr")]
+pub use super::file_change::Entity as FileChange;
+#[cfg(feature = "ssr")]
+pub use super::share::Entity as Share;
+#[cfg(feature = "ssr")]
+pub use super::file_grant::Entity as FileGrant;
+#[cfg(feature = "ssr")]
+pub use super::file_request::Entity as FileRequest;
+#[cfg(feature = "ssr")]
+pub use super::file_request_upload::Entity as FileRequestUpload;
+#[cfg(feature = "ssr")]
+pub use super::share_access::Entity as ShareAccess;
+#[cfg(feature = "ssr")]
+pub use super::share_item::Entity as ShareItem;
 
 pub use super::file::Model as FileModel;
 pub use super::refresh_token::Model as RefreshTokenModel;
-pub use super::user::Model as UserModel;
\ No newline at end of file
+pub use super::user::Model as UserModel;
+pub use super::job::Model as JobModel;
\ No newline at end of file
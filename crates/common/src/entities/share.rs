@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// A share link. The edge keeps a copy of each in its KV for lookups, this table is the record
+/// the owner manages and the only place downloads are counted, which the KV can't do atomically.
+///
+/// Collections have no `file_id`, their files are in `share_item` and `title` names them.
+///
+/// Revoking or rotating a link sets `revoked_at` rather than deleting the row, so the edge can
+/// tell a dead token from one it cached before this table existed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "share"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key, auto_increment = false))]
+    pub token: String,
+    pub owner_id: String,
+    pub file_id: Option<String>,
+    pub title: Option<String>,
+    pub password_hash: Option<String>,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub max_downloads: Option<i32>,
+    pub download_count: i32,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+    pub revoked_at: Option<DateTime<FixedOffset>>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::file::Entity",
+        from = "Column::FileId",
+        to = "super::file::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    File,
+    #[sea_orm(has_many = "super::share_item::Entity")]
+    ShareItem,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::share_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ShareItem.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
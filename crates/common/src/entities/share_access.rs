@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+
+#[cfg(feature = "ssr")]
+use sea_orm::entity::prelude::*;
+
+/// One visit to a share link. `outcome` is `ok` or the reason the visitor was turned away,
+/// `country`, `asn` and `as_organization` come from Cloudflare.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ssr", derive(DeriveEntityModel))]
+#[cfg_attr(feature = "ssr", sea_orm(table_name = "share_access"))]
+pub struct Model {
+    #[cfg_attr(feature = "ssr", sea_orm(primary_key))]
+    pub id: i64,
+    pub token: String,
+    pub owner_id: String,
+    pub file_id: Option<String>,
+    pub action: String,
+    pub outcome: String,
+    pub country: Option<String>,
+    pub asn: Option<i64>,
+    pub as_organization: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ActiveModelBehavior for ActiveModel {}
@@ -33,6 +33,14 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::refresh_token::Entity")]
     RefreshToken,
+    #[sea_orm(has_many = "super::file::Entity")]
+    File,
+    #[sea_orm(has_many = "super::job::Entity")]
+    Job,
+    #[sea_orm(has_many = "super::webhook::Entity")]
+    Webhook,
+    #[sea_orm(has_many = "super::share::Entity")]
+    Share,
 }
 
 #[cfg(feature = "ssr")]
@@ -42,5 +50,33 @@ impl Related<super::refresh_token::Entity> for Entity {
     }
 }
 
+#[cfg(feature = "ssr")]
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::job::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Job.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Related<super::share::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Share.def()
+    }
+}
+
 #[cfg(feature = "ssr")]
 impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct AuditListRequest {
+    pub action: Option<String>,
+    pub target_id: Option<String>,
+    #[ts(type = "string | null")]
+    pub from: Option<DateTime<FixedOffset>>,
+    #[ts(type = "string | null")]
+    pub to: Option<DateTime<FixedOffset>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct AuditEventElement {
+    pub id: i64,
+    pub action: String,
+    pub target_ids: Vec<String>,
+    #[ts(type = "unknown")]
+    pub before: Option<serde_json::Value>,
+    #[ts(type = "unknown")]
+    pub after: Option<serde_json::Value>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct AuditListResponse {
+    pub events: Vec<AuditEventElement>,
+    pub has_more: bool,
+}
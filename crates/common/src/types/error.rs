@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+/// Header carrying the id of a request. The edge picks it and passes it on, so both services
+/// log a request under the same id and the client can quote it.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// What went wrong, stable for clients to switch on. New codes may be added, existing ones keep
+/// their meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    Gone,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    RangeNotSatisfiable,
+    RateLimited,
+    Internal,
+    Upstream,
+    Unavailable,
+}
+
+impl ErrorCode {
+    /// The code for a bare status, for errors that come without one.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            409 => ErrorCode::Conflict,
+            410 => ErrorCode::Gone,
+            413 => ErrorCode::PayloadTooLarge,
+            415 => ErrorCode::UnsupportedMediaType,
+            416 => ErrorCode::RangeNotSatisfiable,
+            429 => ErrorCode::RateLimited,
+            502 | 504 => ErrorCode::Upstream,
+            503 => ErrorCode::Unavailable,
+            400..=499 => ErrorCode::BadRequest,
+            _ => ErrorCode::Internal,
+        }
+    }
+
+    pub fn status(self) -> u16 {
+        match self {
+            ErrorCode::BadRequest => 400,
+            ErrorCode::Unauthorized => 401,
+            ErrorCode::Forbidden => 403,
+            ErrorCode::NotFound => 404,
+            ErrorCode::Conflict => 409,
+            ErrorCode::Gone => 410,
+            ErrorCode::PayloadTooLarge => 413,
+            ErrorCode::UnsupportedMediaType => 415,
+            ErrorCode::RangeNotSatisfiable => 416,
+            ErrorCode::RateLimited => 429,
+            ErrorCode::Internal => 500,
+            ErrorCode::Upstream => 502,
+            ErrorCode::Unavailable => 503,
+        }
+    }
+
+    /// Said when there is nothing more specific to say, and always for server errors, whose
+    /// details stay in the logs.
+    pub fn default_message(self) -> &'static str {
+        match self {
+            ErrorCode::BadRequest => "The request is invalid",
+            ErrorCode::Unauthorized => "Sign in to continue",
+            ErrorCode::Forbidden => "You don't have access to this",
+            ErrorCode::NotFound => "Not found",
+            ErrorCode::Conflict => "This conflicts with the current state",
+            ErrorCode::Gone => "This is no longer available",
+            ErrorCode::PayloadTooLarge => "This is too large",
+            ErrorCode::UnsupportedMediaType => "This type isn't supported",
+            ErrorCode::RangeNotSatisfiable => "The requested range isn't available",
+            ErrorCode::RateLimited => "Too many requests, try again later",
+            ErrorCode::Internal => "Something went wrong",
+            ErrorCode::Upstream => "A service we rely on failed",
+            ErrorCode::Unavailable => "Temporarily unavailable, try again later",
+        }
+    }
+}
+
+/// Body of every error response from either service. Errors that carry details of their own,
+/// such as `SharePasswordResponse`, keep their shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Filled in on the way out, see `REQUEST_ID_HEADER`.
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// An error with the code's default message.
+    pub fn from_code(code: ErrorCode) -> Self {
+        ApiError::new(code, code.default_message())
+    }
+
+    pub fn status(&self) -> u16 {
+        self.code.status()
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use crate::types::file::list::ListFileElement;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ChangesRequest {
+    /// The `cursor` of the previous response, left out to replay every change from the start.
+    pub cursor: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Moved,
+    Deleted,
+}
+
+impl ChangeKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(ChangeKind::Created),
+            "updated" => Some(ChangeKind::Updated),
+            "moved" => Some(ChangeKind::Moved),
+            "deleted" => Some(ChangeKind::Deleted),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ChangeElement {
+    pub sequence: i64,
+    pub kind: ChangeKind,
+    /// The entry after the change. For deletions this is the tombstone, the entry as it was
+    /// just before it went away.
+    pub entry: ListFileElement,
+    #[ts(type = "string")]
+    pub changed_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ChangesResponse {
+    pub changes: Vec<ChangeElement>,
+    /// Pass this back to continue after the last change returned.
+    pub cursor: i64,
+    pub has_more: bool,
+}
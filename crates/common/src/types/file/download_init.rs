@@ -6,6 +6,10 @@ use serde::{Deserialize, Serialize};
 pub struct InitDownloadRequest {
     pub file_id: String,
     pub file_name: String,
+    /// Asks for a URL that opens in the browser rather than saving, for types it can show.
+    #[serde(default)]
+    #[ts(optional)]
+    pub inline: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
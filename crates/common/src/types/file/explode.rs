@@ -13,6 +13,7 @@ pub struct ExplodedItem {
     pub file_name: String,
     pub virtual_path: String,
     pub file_size: i64,
+    pub file_type: String,
     #[ts(type = "string")]
     pub created_at: DateTime<FixedOffset>,
 }
@@ -26,6 +27,8 @@ pub struct PresignedExplodedItem {
     pub virtual_path: String,
     pub presign_url: String,
     pub size: i64,
+    #[serde(default)]
+    pub file_type: String,
     #[ts(type = "string")]
     pub created_at: DateTime<FixedOffset>,
 }
@@ -35,6 +38,29 @@ pub struct PresignedExplodedItem {
 #[ts(export)]
 pub struct ZipRequest {
     pub item_ids: Vec<String>,
+    /// Deflates the entries whose type compresses well. Off by default, stored entries stream
+    /// faster and most large files are compressed already.
+    #[serde(default)]
+    #[ts(optional)]
+    pub compress: Option<bool>,
+    /// Zip when left out.
+    #[serde(default)]
+    #[ts(optional)]
+    pub archive_format: Option<ArchiveFormat>,
+}
+
+/// Container for a download of several files. Tarballs keep POSIX paths and times the way
+/// Linux tools expect, `compress` doesn't apply to them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    #[default]
+    Zip,
+    Tar,
+    #[serde(rename = "tar.gz")]
+    TarGz,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -1,6 +1,46 @@
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
+use crate::types::file::share::SharePermission;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileShare {
-    pub file_id: String
+    /// Empty for collections.
+    pub file_id: String,
+    /// Links created before expiry existed have none and never expire.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    /// PBKDF2 hash of the link's password, see `share_password` in the edge worker.
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    /// Set when the authentication service holds a download counter for the token.
+    #[serde(default)]
+    pub max_downloads: Option<u32>,
+    /// A link to several files, listed by the authentication service.
+    #[serde(default)]
+    pub collection: bool,
+    /// Read from a signed token rather than the share table, there is no record to count
+    /// downloads against.
+    #[serde(default)]
+    pub signed: bool,
+    /// The link shows the file but won't hand it out.
+    #[serde(default)]
+    pub view_only: bool,
+}
+
+/// What a signed share token carries, see `share_token` in the edge worker. The short names
+/// keep the token short enough for a URL.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedShareClaims {
+    #[serde(rename = "f")]
+    pub file_id: String,
+    #[serde(rename = "o")]
+    pub owner_id: String,
+    /// Unix seconds, `None` for a link that doesn't expire.
+    #[serde(rename = "e", default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(rename = "p")]
+    pub permissions: Vec<SharePermission>,
+    /// Random id the revocation list refers to.
+    #[serde(rename = "j")]
+    pub id: String,
 }
@@ -0,0 +1,151 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use crate::types::file::upload_complete::Part;
+
+/// Creates an upload-only link into `folder_id`. Every limit is optional.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct CreateFileRequestRequest {
+    pub folder_id: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub title: Option<String>,
+    /// Largest single file accepted, in bytes.
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_file_size: Option<u64>,
+    /// Files accepted before the link stops taking uploads.
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_files: Option<u32>,
+    /// MIME types such as `image/*` or `application/pdf`, or extensions such as `.pdf`.
+    #[serde(default)]
+    #[ts(optional)]
+    pub allowed_types: Option<Vec<String>>,
+    /// Seconds until the link stops working. Takes precedence over `expires_at`.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expires_in: Option<u64>,
+    #[serde(default)]
+    #[ts(optional, as = "Option<String>")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestElement {
+    pub token: String,
+    pub folder_id: String,
+    pub folder_name: String,
+    pub title: Option<String>,
+    pub max_file_size: Option<u64>,
+    pub max_files: Option<u32>,
+    pub allowed_types: Option<Vec<String>>,
+    pub upload_count: u32,
+    #[ts(type = "string | null")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListFileRequestsResponse {
+    pub requests: Vec<FileRequestElement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestTokenRequest {
+    pub token: String,
+}
+
+/// What an anonymous visitor of a file request link gets to see.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestInfoResponse {
+    pub title: Option<String>,
+    pub folder_name: String,
+    pub owner: String,
+    pub max_file_size: Option<u64>,
+    pub uploads_remaining: Option<u32>,
+    pub allowed_types: Option<Vec<String>>,
+    #[ts(type = "string | null")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+/// Starts an anonymous upload through a file request link.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestUploadRequest {
+    pub token: String,
+    pub filename: String,
+    pub size: u64,
+    pub content_type: String,
+    pub part_count: u64,
+    pub uploader_name: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub uploader_email: Option<String>,
+}
+
+/// The edge's call into the authentication service for `FileRequestUploadRequest`, with the
+/// id it picked for the file.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestUploadInternalRequest {
+    pub token: String,
+    pub file_id: String,
+    pub filename: String,
+    pub size: u64,
+    pub content_type: String,
+    pub uploader_name: String,
+    pub uploader_email: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestCompleteRequest {
+    pub token: String,
+    pub file_id: String,
+    pub upload_id: String,
+    pub parts: Vec<Part>,
+}
+
+/// Body of the 4xx answers to anonymous uploads. `error` is `expired`, `full`, `too_large` or
+/// `type_not_allowed`.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestRejectedResponse {
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestUploadElement {
+    pub file_id: String,
+    pub file_name: String,
+    pub file_size: i64,
+    pub upload_completed: bool,
+    pub uploader_name: String,
+    pub uploader_email: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct FileRequestUploadsResponse {
+    pub uploads: Vec<FileRequestUploadElement>,
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use crate::types::file::list::ListFileElement;
+
+/// What a grant lets another user do with an entry and everything below it. Editors can
+/// rename, move, delete and upload, viewers can only list and download.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub enum GrantRole {
+    Viewer,
+    Editor,
+}
+
+impl GrantRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrantRole::Viewer => "viewer",
+            GrantRole::Editor => "editor",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "viewer" => Some(GrantRole::Viewer),
+            "editor" => Some(GrantRole::Editor),
+            _ => None,
+        }
+    }
+}
+
+/// Grants `role` on the entry to the Ledger user with this email, replacing any role they
+/// already had on it.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct CreateGrantRequest {
+    pub file_id: String,
+    pub email: String,
+    pub role: GrantRole,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListGrantsRequest {
+    pub file_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeleteGrantRequest {
+    pub file_id: String,
+    pub grantee_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct GrantElement {
+    pub grantee_id: String,
+    pub username: String,
+    pub email: String,
+    pub role: GrantRole,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListGrantsResponse {
+    pub grants: Vec<GrantElement>,
+}
+
+/// An entry another user has granted the caller access to.
+#[derive(Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedWithMeElement {
+    pub entry: ListFileElement,
+    pub owner_id: String,
+    pub owner: String,
+    pub role: GrantRole,
+    #[ts(type = "string")]
+    pub shared_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedWithMeResponse {
+    pub files: Vec<SharedWithMeElement>,
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use crate::types::file::copy::CopyFilesRequest;
+use crate::types::file::delete::DeleteFilesRequest;
+use crate::types::file::directory_delete::DeleteDirectoryRequest;
+use crate::types::file::r#move::MoveFilesRequest;
+
+/// A bulk operation that can be handed to the job workers instead of running inside the request.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub enum JobOperation {
+    Delete(DeleteFilesRequest),
+    DeleteDirectory(DeleteDirectoryRequest),
+    Copy(CopyFilesRequest),
+    Move(MoveFilesRequest),
+}
+
+impl JobOperation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobOperation::Delete(_) => "delete",
+            JobOperation::DeleteDirectory(_) => "delete_directory",
+            JobOperation::Copy(_) => "copy",
+            JobOperation::Move(_) => "move",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<JobStatus> {
+        match value {
+            "queued" => Some(JobStatus::Queued),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "failed" => Some(JobStatus::Failed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct JobRequest {
+    pub job_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct JobResponse {
+    pub id: String,
+    pub kind: String,
+    pub status: JobStatus,
+    pub total_items: i64,
+    pub processed_items: i64,
+    pub errors: Vec<String>,
+    #[ts(type = "unknown")]
+    pub result: Option<serde_json::Value>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+    #[ts(type = "string")]
+    pub updated_at: DateTime<FixedOffset>,
+    #[ts(type = "string | null")]
+    pub completed_at: Option<DateTime<FixedOffset>>,
+}
@@ -24,7 +24,8 @@ pub struct ListFileElement {
     pub upload_completed: bool,
     pub file_type: String,
     pub path: String,
-    pub is_directory: bool
+    pub is_directory: bool,
+    pub item_count: i64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -19,4 +19,8 @@ pub struct MetadataResponse {
     #[ts(type = "string")]
     pub created_at: DateTime<FixedOffset>,
     pub owner_id: String,
+    #[serde(default)]
+    pub item_count: i64,
+    #[serde(default)]
+    pub is_directory: bool,
 }
\ No newline at end of file
@@ -11,4 +11,13 @@ pub mod directory;
 pub mod directory_delete;
 pub mod explode;
 pub mod share;
-pub mod file_claims;
\ No newline at end of file
+pub mod file_claims;
+pub mod resolve;
+pub mod job;
+pub mod changes;
+
+pub mod share_folder;
+pub mod grant;
+pub mod file_request;
+pub mod share_access;
+pub mod share_collection;
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[derive(ts_rs::TS)]
 #[ts(export)]
 pub struct MoveFilesRequest {
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use crate::types::file::list::Breadcrumb;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ResolveRequest {
+    pub path: Option<String>,
+    pub file_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ResolveResponse {
+    pub file_id: String,
+    pub path: String,
+    pub is_directory: bool,
+    pub breadcrumbs: Vec<Breadcrumb>,
+    /// The owner, whose storage prefix holds the file. Differs from the caller for entries
+    /// shared with them.
+    #[serde(default)]
+    pub owner_id: String,
+    /// Empty for directories.
+    #[serde(default)]
+    pub file_type: String,
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,13 +10,67 @@ pub struct ShareRequest {
     pub file_type: String,
     pub file_size: u64,
     pub created_at: String,
+    /// Seconds until the link stops working. Takes precedence over `expires_at`.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expires_in: Option<u64>,
+    #[serde(default)]
+    #[ts(optional, as = "Option<String>")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    /// Issue a new token even if the file already has one. The old link stops working.
+    #[serde(default)]
+    #[ts(optional)]
+    pub rotate: Option<bool>,
+    /// Require this password before the file can be downloaded. An empty string removes it.
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+    /// Stop working after this many downloads, 1 for a one-time link. 0 removes the limit.
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_downloads: Option<u32>,
+    /// Issue a self-contained signed token instead of a stored one. It is checked at the edge
+    /// without a lookup, can be revoked, but can't carry a password or a download limit.
+    #[serde(default)]
+    #[ts(optional)]
+    pub signed: Option<bool>,
+    /// What a signed token allows, both by default.
+    #[serde(default)]
+    #[ts(optional)]
+    pub permissions: Option<Vec<SharePermission>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum SharePermission {
+    /// See the details and browse a shared folder.
+    View,
+    /// Fetch files, one at a time or zipped.
+    Download,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[derive(ts_rs::TS)]
 #[ts(export)]
 pub struct ShareResponse {
-    pub token: String
+    pub token: String,
+    #[ts(type = "string | null")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub password_protected: bool,
+    pub max_downloads: Option<u32>,
+}
+
+/// Body of the 410 returned for a link that existed but ran out, as opposed to the 404 for a
+/// token that was never issued or has since been rotated away.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareExpiredResponse {
+    pub error: String,
+    #[ts(type = "string")]
+    pub expired_at: DateTime<FixedOffset>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,6 +78,31 @@ pub struct ShareResponse {
 #[ts(export)]
 pub struct ShareDownloadRequest {
     pub token: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+    /// Only the details are wanted, as for rendering the share page. Nothing is counted, and
+    /// links with a download limit also skip presigning and leave `presigned_url` empty.
+    #[serde(default)]
+    #[ts(optional)]
+    pub metadata_only: Option<bool>,
+    /// The URL opens in the browser instead of saving, for types it can show. Still counted as
+    /// a download.
+    #[serde(default)]
+    #[ts(optional)]
+    pub inline: Option<bool>,
+}
+
+/// Body of the 401 and 429 for password protected links. `error` is `password_required` when
+/// none was sent, `invalid_password` for a wrong one and `too_many_attempts` once the token is
+/// locked for `retry_after` seconds.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharePasswordResponse {
+    pub error: String,
+    pub attempts_remaining: Option<u32>,
+    pub retry_after: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,6 +114,117 @@ pub struct ShareDownloadResponse {
     pub file_name: String,
     pub file_size: u64,
     pub created_at: String,
-    pub owner: String
+    pub owner: String,
+    /// Downloads left after this one, for links with a limit.
+    pub downloads_remaining: Option<u32>,
+    /// Set for shared folders, which are browsed and zipped instead of downloaded directly.
+    #[serde(default)]
+    pub is_directory: bool,
+    /// Set for collections. `file_name` is the title and `file_size` the total, the files are
+    /// listed through `/download/share/items`.
+    #[serde(default)]
+    pub is_collection: bool,
+}
+
+/// Body of the 410 the authentication service answers for a link that was revoked or rotated
+/// away, for the edge to refuse a copy it still has cached.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareRevokedResponse {
+    pub error: String,
+    #[ts(type = "string")]
+    pub revoked_at: DateTime<FixedOffset>,
+}
+
+/// Body of the 410 for a link whose download limit has been used up.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareExhaustedResponse {
+    pub error: String,
+    pub max_downloads: u32,
+}
+
+/// Written by the edge whenever it issues or changes a link, with the settings already merged.
+/// `replaces` is the token a rotation retires.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareSaveRequest {
+    pub token: String,
+    pub file_id: String,
+    pub password_hash: Option<String>,
+    #[ts(type = "string | null")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub max_downloads: Option<u32>,
+    pub replaces: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareTokenRequest {
+    pub token: String,
+}
+
+/// A download was counted. `remaining` is left out for links without a limit.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareConsumeResponse {
+    pub remaining: Option<u32>,
+}
+
+/// One of the caller's links that hasn't expired.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareElement {
+    pub token: String,
+    /// `None` for collections, which have `item_count` instead and their title as `file_name`.
+    pub file_id: Option<String>,
+    pub file_name: String,
+    pub is_directory: bool,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+    #[ts(type = "string | null")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub password_protected: bool,
+    pub max_downloads: Option<u32>,
+    pub download_count: u32,
+    pub item_count: Option<u32>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListSharesResponse {
+    pub shares: Vec<ShareElement>,
+}
+
+/// Revokes one link, or every link of the caller when `token` is left out.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareRevokeRequest {
+    #[serde(default)]
+    #[ts(optional)]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct RevokedShare {
+    pub token: String,
+    /// `None` for collections.
+    pub file_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareRevokeResponse {
+    pub revoked: Vec<RevokedShare>,
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// Sent by the edge after each visit to a share link. `action` is `view`, `download`, `browse`
+/// or `zip`, `outcome` is `ok` or the error the visitor got back, such as `expired` or
+/// `invalid_password`.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareAccessRecordRequest {
+    pub token: String,
+    /// The entry fetched from a shared folder, `None` for the shared entry itself.
+    pub file_id: Option<String>,
+    pub action: String,
+    pub outcome: String,
+    pub country: Option<String>,
+    pub asn: Option<u32>,
+    pub as_organization: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareAccessRequest {
+    pub token: String,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareAccessElement {
+    pub id: i64,
+    pub file_id: Option<String>,
+    pub action: String,
+    pub outcome: String,
+    pub country: Option<String>,
+    pub asn: Option<u32>,
+    pub as_organization: Option<String>,
+    pub user_agent: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareAccessCount {
+    pub country: Option<String>,
+    pub count: u64,
+}
+
+/// Totals over the whole history of a link, not just the page of entries returned with it.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareAccessSummary {
+    pub visits: u64,
+    /// Downloads and zips handed out, the number to look at for "did they get it".
+    pub downloads: u64,
+    /// Visits turned away, for a wrong password, an expired link and the like.
+    pub denied: u64,
+    #[ts(type = "string | null")]
+    pub first_download_at: Option<DateTime<FixedOffset>>,
+    #[ts(type = "string | null")]
+    pub last_download_at: Option<DateTime<FixedOffset>>,
+    pub countries: Vec<ShareAccessCount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareAccessResponse {
+    pub summary: ShareAccessSummary,
+    pub entries: Vec<ShareAccessElement>,
+    pub has_more: bool,
+}
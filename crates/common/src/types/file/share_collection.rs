@@ -0,0 +1,93 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// One link for several files, possibly from different folders. The settings work as for
+/// `ShareRequest`, except that every call issues a new token.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareCollectionRequest {
+    pub file_ids: Vec<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub title: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub expires_in: Option<u64>,
+    #[serde(default)]
+    #[ts(optional, as = "Option<String>")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_downloads: Option<u32>,
+}
+
+/// Written by the edge when it issues a collection, with the password already hashed.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareCollectionSaveRequest {
+    pub token: String,
+    pub file_ids: Vec<String>,
+    pub title: Option<String>,
+    pub password_hash: Option<String>,
+    #[ts(type = "string | null")]
+    pub expires_at: Option<DateTime<FixedOffset>>,
+    pub max_downloads: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareItemsRequest {
+    pub token: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedItem {
+    pub id: String,
+    pub file_name: String,
+    pub file_size: u64,
+    pub file_type: String,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+/// The files of a collection that still exist, in the order they were picked.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedItemsResponse {
+    pub owner_id: String,
+    pub title: String,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+    pub items: Vec<SharedItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareItemsResponse {
+    pub title: String,
+    pub owner: String,
+    pub total_size: u64,
+    pub items: Vec<SharedItem>,
+}
+
+/// Zips files of a collection, all of them when `item_ids` is empty.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedCollectionZipRequest {
+    pub token: String,
+    pub item_ids: Vec<String>,
+}
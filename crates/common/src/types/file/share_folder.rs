@@ -0,0 +1,86 @@
+use crate::types::file::explode::ArchiveFormat;
+use serde::{Deserialize, Serialize};
+
+/// Lists a directory of a shared folder, the root itself when `directory_id` is left out.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareBrowseRequest {
+    pub token: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub directory_id: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub offset: Option<u32>,
+}
+
+/// Downloads a single file from inside a shared folder.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareFileRequest {
+    pub token: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+    pub file_id: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub inline: Option<bool>,
+}
+
+/// Zips entries of a shared folder, the whole folder when `item_ids` is left out.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ShareZipRequest {
+    pub token: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub password: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub item_ids: Option<Vec<String>>,
+    /// As for `ZipRequest`.
+    #[serde(default)]
+    #[ts(optional)]
+    pub compress: Option<bool>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub archive_format: Option<ArchiveFormat>,
+}
+
+/// The edge's calls into the authentication service once it has checked the token. Every
+/// requested id is confined to the subtree under `root_id`.
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedBrowseRequest {
+    pub root_id: String,
+    pub directory_id: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedEntryRequest {
+    pub root_id: String,
+    pub file_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct SharedExplodeRequest {
+    pub root_id: String,
+    pub item_ids: Vec<String>,
+}
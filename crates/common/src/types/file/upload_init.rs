@@ -37,4 +37,7 @@ pub struct InitUploadInternalRequest {
 #[ts(export)]
 pub struct InitUploadInternalResponse {
     pub upload_id: String,
+    /// Owner of the target folder, the parts go under their storage prefix.
+    #[serde(default)]
+    pub owner_id: String,
 }
\ No newline at end of file
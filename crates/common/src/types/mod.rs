@@ -1,3 +1,7 @@
 pub mod file;
 pub mod authentication;
 pub mod user;
+pub mod audit;
+pub mod webhook;
+pub mod error;
+pub mod outbox;
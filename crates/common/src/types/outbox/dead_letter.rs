@@ -0,0 +1,51 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeadLetterListRequest {
+    pub owner_id: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+/// A storage operation that ran out of attempts.
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeadLetterElement {
+    pub id: String,
+    pub owner_id: String,
+    pub operation: String,
+    #[ts(type = "unknown")]
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+    #[ts(type = "string")]
+    pub updated_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeadLetterListResponse {
+    pub entries: Vec<DeadLetterElement>,
+    pub has_more: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeadLetterRetryRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeadLetterRetryResponse {
+    pub retried: u64,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use crate::types::webhook::event::WebhookEvent;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    /// Only fire for entries inside this directory, at any depth.
+    pub folder_id: Option<String>,
+}
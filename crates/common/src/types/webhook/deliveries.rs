@@ -0,0 +1,35 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListDeliveriesRequest {
+    pub webhook_id: String,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct DeliveryElement {
+    pub id: String,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+    #[ts(type = "string | null")]
+    pub delivered_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListDeliveriesResponse {
+    pub deliveries: Vec<DeliveryElement>,
+    pub has_more: bool,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub enum WebhookEvent {
+    UploadCompleted,
+    Deleted,
+    Moved,
+    Shared,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::UploadCompleted => "upload_completed",
+            WebhookEvent::Deleted => "deleted",
+            WebhookEvent::Moved => "moved",
+            WebhookEvent::Shared => "shared",
+        }
+    }
+}
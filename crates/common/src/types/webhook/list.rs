@@ -0,0 +1,32 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use crate::types::webhook::event::WebhookEvent;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct WebhookRequest {
+    pub webhook_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct WebhookElement {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub folder_id: Option<String>,
+    pub enabled: bool,
+    pub consecutive_failures: i32,
+    pub disabled_reason: Option<String>,
+    #[ts(type = "string")]
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[derive(ts_rs::TS)]
+#[ts(export)]
+pub struct ListWebhooksResponse {
+    pub webhooks: Vec<WebhookElement>,
+}
@@ -0,0 +1,4 @@
+pub mod event;
+pub mod create;
+pub mod list;
+pub mod deliveries;
@@ -14,4 +14,5 @@ pub trait StorageBackend {
     async fn move_many(&self, moves: Vec<(&str, &str)>) -> Result<()>;
     async fn copy_object(&self, src: &str, dest: &str) -> Result<()>;
     async fn list_objects(&self, prefix: &str) -> Result<Vec<String>>;
+    async fn object_size(&self, path: &str) -> Result<i64>;
 }
\ No newline at end of file
@@ -63,7 +63,7 @@ impl StorageBackend for S3ScopedStorage {
             .client
             .complete_multipart_upload()
             .bucket(&self.bucket)
-            .key(self.scoped_path(&path))
+            .key(self.scoped_path(path))
             .upload_id(upload_id)
             .multipart_upload(completion_data)
             .send()
@@ -94,7 +94,7 @@ impl StorageBackend for S3ScopedStorage {
             })
             .collect::<Vec<_>>();
 
-        self.client
+        let output = self.client
             .delete_objects()
             .bucket(&self.bucket)
             .delete(
@@ -106,6 +106,16 @@ impl StorageBackend for S3ScopedStorage {
             .send()
             .await?;
 
+        // The batch call succeeds as a whole even when individual keys fail.
+        if let Some(error) = output.errors().first() {
+            anyhow::bail!(
+                "Failed to delete {} of the objects, first error on {}: {}",
+                output.errors().len(),
+                error.key().unwrap_or_default(),
+                error.message().unwrap_or_default()
+            );
+        }
+
         Ok(())
     }
 
@@ -161,4 +171,17 @@ impl StorageBackend for S3ScopedStorage {
 
         Ok(keys)
     }
+
+    async fn object_size(&self, path: &str) -> anyhow::Result<i64> {
+        let res = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.scoped_path(path))
+            .send()
+            .await?;
+
+        res.content_length()
+            .ok_or_else(|| anyhow::anyhow!("S3 returned no content length for {}", path))
+    }
 }
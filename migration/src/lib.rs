@@ -10,6 +10,25 @@ impl MigratorTrait for Migrator {
             Box::new(m20260321_142905_create_user::Migration),
             Box::new(m20260321_142910_create_refresh_token::Migration),
             Box::new(m20260402_110621_create_passkey::Migration),
+            Box::new(m20260415_090000_create_search_indexes::Migration),
+            Box::new(m20260416_101500_add_file_foreign_keys::Migration),
+            Box::new(m20260418_143000_add_directory_totals::Migration),
+            Box::new(m20260420_110000_create_job::Migration),
+            Box::new(m20260422_090000_create_storage_outbox::Migration),
+            Box::new(m20260424_100000_create_audit_event::Migration),
+            Box::new(m20260427_150000_create_webhooks::Migration),
+            Box::new(m20260429_090000_create_file_change::Migration),
+            Box::new(m20260501_120000_notify_file_change::Migration),
+            Box::new(m20260503_100000_create_share_limit::Migration),
+            Box::new(m20260505_090000_create_share::Migration),
+            Box::new(m20260507_090000_create_file_grant::Migration),
+            Box::new(m20260509_090000_create_file_request::Migration),
+            Box::new(m20260511_090000_create_share_access::Migration),
+            Box::new(m20260513_090000_create_share_item::Migration),
+            Box::new(m20260515_090000_create_job_copy::Migration),
+            Box::new(m20260517_090000_sequence_file_change::Migration),
+            Box::new(m20260519_090000_add_share_revoked_at::Migration),
+            Box::new(m20260521_090000_add_job_submitted_by::Migration),
         ]
     }
 
@@ -22,3 +41,57 @@ mod m20260321_142900_create_table;
 mod m20260321_142905_create_user;
 mod m20260321_142910_create_refresh_token;
 mod m20260402_110621_create_passkey;
+mod m20260415_090000_create_search_indexes;
+mod m20260416_101500_add_file_foreign_keys;
+mod m20260418_143000_add_directory_totals;
+mod m20260420_110000_create_job;
+mod m20260422_090000_create_storage_outbox;
+mod m20260424_100000_create_audit_event;
+mod m20260427_150000_create_webhooks;
+mod m20260429_090000_create_file_change;
+mod m20260501_120000_notify_file_change;
+mod m20260503_100000_create_share_limit;
+mod m20260505_090000_create_share;
+mod m20260507_090000_create_file_grant;
+mod m20260509_090000_create_file_request;
+mod m20260511_090000_create_share_access;
+mod m20260513_090000_create_share_item;
+mod m20260515_090000_create_job_copy;
+mod m20260517_090000_sequence_file_change;
+mod m20260519_090000_add_share_revoked_at;
+mod m20260521_090000_add_job_submitted_by;
+
+/// Postgres extensions the file queries rely on (`%` and `similarity()` come from pg_trgm).
+pub const REQUIRED_EXTENSIONS: [&str; 1] = ["pg_trgm"];
+
+/// Fails with the list of required extensions the server doesn't ship. Run before migrating:
+/// the migrations create the extensions, which only works when their packages are installed,
+/// and this names what is missing rather than failing halfway through a migration.
+pub async fn verify_extensions<C: ConnectionTrait>(connection: &C) -> Result<(), DbErr> {
+    let rows = connection
+        .query_all_raw(sea_orm::Statement::from_string(
+            connection.get_database_backend(),
+            "SELECT name FROM pg_available_extensions",
+        ))
+        .await?;
+
+    let installed = rows
+        .iter()
+        .filter_map(|row| row.try_get::<String>("", "name").ok())
+        .collect::<Vec<_>>();
+
+    let missing = REQUIRED_EXTENSIONS
+        .iter()
+        .filter(|extension| !installed.iter().any(|name| name == *extension))
+        .copied()
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(DbErr::Custom(format!(
+        "Missing required Postgres extensions: {}. Install them on the database server, e.g. the postgresql-contrib package.",
+        missing.join(", ")
+    )))
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let connection = manager.get_connection();
+
+        connection
+            .execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await
+            .map_err(|err| {
+                DbErr::Migration(format!(
+                    "The pg_trgm extension is not installed and could not be created ({}). \
+                     Ask a database superuser to run `CREATE EXTENSION pg_trgm;` and restart.",
+                    err
+                ))
+            })?;
+
+        // sea-query has no way to express operator classes, so the trigram index is raw SQL.
+        connection
+            .execute_unprepared(
+                r#"CREATE INDEX IF NOT EXISTS "idx-file-file-name-trgm" ON "file" USING GIN ("file_name" gin_trgm_ops)"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-file-file-name-trgm")
+                    .table(File::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The extension is left in place, other database objects may depend on it.
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+}
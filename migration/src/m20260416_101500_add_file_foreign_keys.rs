@@ -0,0 +1,148 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let connection = manager.get_connection();
+
+        // Rows whose owner is gone can never be listed again; they move to `file_orphan` rather
+        // than being deleted, since they are the only record of the storage objects behind
+        // them. Rows pointing at a parent that no longer exists, including one that was just
+        // quarantined, are then moved back to the root so they stay reachable.
+        connection
+            .execute_unprepared(
+                r#"
+                CREATE TABLE IF NOT EXISTS "file_orphan" (LIKE "file" INCLUDING DEFAULTS);
+                ALTER TABLE "file_orphan" ADD COLUMN IF NOT EXISTS "quarantined_at" timestamptz NOT NULL DEFAULT now();
+                "#,
+            )
+            .await?;
+
+        let quarantined = connection
+            .execute_unprepared(
+                r#"
+                WITH "moved" AS (
+                    DELETE FROM "file"
+                    WHERE NOT EXISTS (SELECT 1 FROM "user" u WHERE u."id" = "file"."owner_id")
+                    RETURNING *
+                )
+                INSERT INTO "file_orphan" (
+                    "id", "file_name", "owner_id", "file_size", "created_at", "upload_completed",
+                    "file_type", "path", "is_directory", "quarantined_at"
+                )
+                SELECT
+                    "id", "file_name", "owner_id", "file_size", "created_at", "upload_completed",
+                    "file_type", "path", "is_directory", now()
+                FROM "moved"
+                "#,
+            )
+            .await?
+            .rows_affected();
+
+        let reattached = connection
+            .execute_unprepared(
+                r#"
+                UPDATE "file" SET "path" = ''
+                WHERE "path" <> ''
+                  AND NOT EXISTS (SELECT 1 FROM "file" p WHERE p."id" = "file"."path")
+                "#,
+            )
+            .await?
+            .rows_affected();
+
+        if reattached > 0 {
+            log::warn!("Reattached {} file entries with a missing parent to the root", reattached);
+        }
+
+        if quarantined > 0 {
+            log::warn!(
+                "Moved {} file entries without an owner to file_orphan; their storage objects are still in the bucket",
+                quarantined
+            );
+        }
+
+        // Root entries use an empty `path`, which can't take part in a foreign key, so the
+        // parent reference is mirrored into a nullable generated column.
+        connection
+            .execute_unprepared(
+                r#"ALTER TABLE "file" ADD COLUMN "parent_id" varchar GENERATED ALWAYS AS (NULLIF("path", '')) STORED"#,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-parent-id")
+                    .table(File::Table)
+                    .col(File::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // NO ACTION rather than RESTRICT: the check runs at the end of the statement, so a
+        // directory and its whole subtree can still be removed by a single DELETE.
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-file-parent_id")
+                    .from(File::Table, File::ParentId)
+                    .to(File::Table, File::Id)
+                    .on_delete(ForeignKeyAction::NoAction)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-file-owner_id")
+                    .from(File::Table, File::OwnerId)
+                    .to(User::Table, User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // `file_orphan` stays, it may be the only record of objects still in storage.
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk-file-owner_id")
+                    .table(File::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk-file-parent_id")
+                    .table(File::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .drop_column(File::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+    OwnerId,
+    ParentId,
+}
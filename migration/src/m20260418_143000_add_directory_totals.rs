@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .add_column(
+                        ColumnDef::new(File::ItemCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Directories are created with a size of 0, backfill them with the recursive totals of
+        // their subtree. Only completed uploads count, matching what upload completion adds.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                WITH RECURSIVE tree AS (
+                    SELECT id AS root_id, id FROM "file" WHERE is_directory = true
+                    UNION ALL
+                    SELECT t.root_id, f.id FROM "file" f
+                    INNER JOIN tree t ON f.path = t.id
+                )
+                UPDATE "file" d
+                SET file_size = totals.total_size, item_count = totals.total_items
+                FROM (
+                    SELECT
+                        t.root_id,
+                        COALESCE(SUM(f.file_size) FILTER (WHERE f.is_directory = false AND f.upload_completed = true), 0) AS total_size,
+                        COUNT(*) FILTER (WHERE f.is_directory = true OR f.upload_completed = true) AS total_items
+                    FROM tree t
+                    INNER JOIN "file" f ON f.id = t.id
+                    WHERE t.id <> t.root_id
+                    GROUP BY t.root_id
+                ) totals
+                WHERE d.id = totals.root_id
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(r#"UPDATE "file" SET file_size = 0 WHERE is_directory = true"#)
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .drop_column(File::ItemCount)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    ItemCount,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Job::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Job::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(Job::OwnerId).string().not_null())
+                    .col(ColumnDef::new(Job::Kind).string().not_null())
+                    .col(ColumnDef::new(Job::Status).string().not_null())
+                    .col(ColumnDef::new(Job::Payload).json().not_null())
+                    .col(ColumnDef::new(Job::TotalItems).big_integer().not_null().default(0))
+                    .col(ColumnDef::new(Job::ProcessedItems).big_integer().not_null().default(0))
+                    .col(ColumnDef::new(Job::Errors).json().not_null())
+                    .col(ColumnDef::new(Job::Result).json())
+                    .col(ColumnDef::new(Job::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Job::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Job::CompletedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-job-owner_id")
+                            .from(Job::Table, Job::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-job-status-created-at")
+                    .table(Job::Table)
+                    .col(Job::Status)
+                    .col(Job::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-job-owner-id")
+                    .table(Job::Table)
+                    .col(Job::OwnerId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Job::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Job {
+    Table,
+    Id,
+    OwnerId,
+    Kind,
+    Status,
+    Payload,
+    TotalItems,
+    ProcessedItems,
+    Errors,
+    Result,
+    CreatedAt,
+    UpdatedAt,
+    CompletedAt,
+}
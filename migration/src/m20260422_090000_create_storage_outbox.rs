@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StorageOutbox::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(StorageOutbox::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(StorageOutbox::OwnerId).string().not_null())
+                    .col(ColumnDef::new(StorageOutbox::Operation).string().not_null())
+                    .col(ColumnDef::new(StorageOutbox::Payload).json().not_null())
+                    .col(ColumnDef::new(StorageOutbox::Status).string().not_null())
+                    .col(ColumnDef::new(StorageOutbox::Attempts).integer().not_null().default(0))
+                    .col(ColumnDef::new(StorageOutbox::LastError).text())
+                    .col(ColumnDef::new(StorageOutbox::NextAttemptAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(StorageOutbox::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(StorageOutbox::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-storage-outbox-status-next-attempt-at")
+                    .table(StorageOutbox::Table)
+                    .col(StorageOutbox::Status)
+                    .col(StorageOutbox::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StorageOutbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StorageOutbox {
+    Table,
+    Id,
+    OwnerId,
+    Operation,
+    Payload,
+    Status,
+    Attempts,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+    UpdatedAt,
+}
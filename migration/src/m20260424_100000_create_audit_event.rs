@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditEvent::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::ActorId).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Action).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::TargetIds).json_binary().not_null())
+                    .col(ColumnDef::new(AuditEvent::Before).json_binary())
+                    .col(ColumnDef::new(AuditEvent::After).json_binary())
+                    .col(ColumnDef::new(AuditEvent::Ip).string())
+                    .col(ColumnDef::new(AuditEvent::UserAgent).text())
+                    .col(ColumnDef::new(AuditEvent::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-audit-event-actor-id-created-at")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::ActorId)
+                    .col(AuditEvent::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        let connection = manager.get_connection();
+
+        connection
+            .execute_unprepared(
+                r#"CREATE INDEX IF NOT EXISTS "idx-audit-event-target-ids" ON audit_event USING GIN (target_ids)"#,
+            )
+            .await?;
+
+        // Events are never rewritten or removed, not even by the service writing them.
+        connection
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION audit_event_append_only() RETURNS trigger AS $$
+                BEGIN
+                    RAISE EXCEPTION 'audit_event is append-only';
+                END;
+                $$ LANGUAGE plpgsql;
+
+                CREATE TRIGGER audit_event_append_only
+                BEFORE UPDATE OR DELETE ON audit_event
+                FOR EACH ROW EXECUTE FUNCTION audit_event_append_only();
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvent::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION IF EXISTS audit_event_append_only()")
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEvent {
+    Table,
+    Id,
+    ActorId,
+    Action,
+    TargetIds,
+    Before,
+    After,
+    Ip,
+    UserAgent,
+    CreatedAt,
+}
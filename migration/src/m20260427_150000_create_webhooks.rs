@@ -0,0 +1,138 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhook::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Webhook::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(Webhook::OwnerId).string().not_null())
+                    .col(ColumnDef::new(Webhook::Url).text().not_null())
+                    .col(ColumnDef::new(Webhook::Secret).string().not_null())
+                    .col(ColumnDef::new(Webhook::Events).json().not_null())
+                    .col(ColumnDef::new(Webhook::FolderId).string())
+                    .col(ColumnDef::new(Webhook::Enabled).boolean().not_null().default(true))
+                    .col(ColumnDef::new(Webhook::ConsecutiveFailures).integer().not_null().default(0))
+                    .col(ColumnDef::new(Webhook::DisabledReason).text())
+                    .col(ColumnDef::new(Webhook::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Webhook::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-webhook-owner_id")
+                            .from(Webhook::Table, Webhook::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-webhook-owner-id")
+                    .table(Webhook::Table)
+                    .col(Webhook::OwnerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(WebhookDelivery::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(WebhookDelivery::WebhookId).string().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Event).string().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Payload).json().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Status).string().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::Attempts).integer().not_null().default(0))
+                    .col(ColumnDef::new(WebhookDelivery::ResponseStatus).integer())
+                    .col(ColumnDef::new(WebhookDelivery::LastError).text())
+                    .col(ColumnDef::new(WebhookDelivery::NextAttemptAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::DeliveredAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-webhook-delivery-webhook_id")
+                            .from(WebhookDelivery::Table, WebhookDelivery::WebhookId)
+                            .to(Webhook::Table, Webhook::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-webhook-delivery-status-next-attempt-at")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::Status)
+                    .col(WebhookDelivery::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-webhook-delivery-webhook-id-created-at")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::WebhookId)
+                    .col(WebhookDelivery::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Webhook::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Webhook {
+    Table,
+    Id,
+    OwnerId,
+    Url,
+    Secret,
+    Events,
+    FolderId,
+    Enabled,
+    ConsecutiveFailures,
+    DisabledReason,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WebhookDelivery {
+    Table,
+    Id,
+    WebhookId,
+    Event,
+    Payload,
+    Status,
+    Attempts,
+    ResponseStatus,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+    DeliveredAt,
+}
@@ -0,0 +1,139 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FileChange::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(FileChange::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(FileChange::OwnerId).string().not_null())
+                    .col(ColumnDef::new(FileChange::FileId).string().not_null())
+                    .col(ColumnDef::new(FileChange::Kind).string().not_null())
+                    .col(ColumnDef::new(FileChange::Entry).json_binary().not_null())
+                    .col(ColumnDef::new(FileChange::CreatedAt).timestamp_with_time_zone().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-change-owner-id-id")
+                    .table(FileChange::Table)
+                    .col(FileChange::OwnerId)
+                    .col(FileChange::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        let connection = manager.get_connection();
+
+        // Every write to `file` is logged from a trigger so no code path can forget to. The
+        // per-owner advisory lock is held until commit, which keeps an owner's sequence numbers
+        // in commit order and means a reader can never see a later change before an earlier one.
+        // Directory rows whose totals were the only thing to change aren't logged, those move
+        // with every upload below them.
+        connection
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION record_file_change() RETURNS trigger AS $$
+                DECLARE
+                    entry file%ROWTYPE;
+                    change_kind text;
+                BEGIN
+                    IF TG_OP = 'INSERT' THEN
+                        entry := NEW;
+                        change_kind := 'created';
+                    ELSIF TG_OP = 'DELETE' THEN
+                        entry := OLD;
+                        change_kind := 'deleted';
+                    ELSIF OLD.path IS DISTINCT FROM NEW.path THEN
+                        entry := NEW;
+                        change_kind := 'moved';
+                    ELSIF (OLD.file_name, OLD.file_type, OLD.upload_completed, OLD.is_directory)
+                        IS NOT DISTINCT FROM (NEW.file_name, NEW.file_type, NEW.upload_completed, NEW.is_directory)
+                        AND (NEW.is_directory OR OLD.file_size = NEW.file_size) THEN
+                        RETURN NULL;
+                    ELSE
+                        entry := NEW;
+                        change_kind := 'updated';
+                    END IF;
+
+                    PERFORM pg_advisory_xact_lock(hashtext('file_change'), hashtext(entry.owner_id));
+
+                    INSERT INTO file_change (owner_id, file_id, kind, entry, created_at)
+                    VALUES (entry.owner_id, entry.id, change_kind, to_jsonb(entry), now());
+
+                    RETURN NULL;
+                END;
+                $$ LANGUAGE plpgsql;
+
+                CREATE TRIGGER record_file_change
+                AFTER INSERT OR UPDATE OR DELETE ON file
+                FOR EACH ROW EXECUTE FUNCTION record_file_change();
+                "#,
+            )
+            .await?;
+
+        // Files from before the log existed start out as created, parents ahead of children so
+        // a client replaying from the beginning always has somewhere to put each entry.
+        connection
+            .execute_unprepared(
+                r#"
+                WITH RECURSIVE tree AS (
+                    SELECT id, 0 AS depth FROM file WHERE path = ''
+                    UNION ALL
+                    SELECT f.id, t.depth + 1 FROM file f
+                    INNER JOIN tree t ON f.path = t.id
+                )
+                INSERT INTO file_change (owner_id, file_id, kind, entry, created_at)
+                SELECT f.owner_id, f.id, 'created', to_jsonb(f), now()
+                FROM tree t
+                INNER JOIN file f ON f.id = t.id
+                ORDER BY t.depth, f.created_at;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let connection = manager.get_connection();
+
+        connection
+            .execute_unprepared("DROP TRIGGER IF EXISTS record_file_change ON file")
+            .await?;
+
+        connection
+            .execute_unprepared("DROP FUNCTION IF EXISTS record_file_change()")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(FileChange::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FileChange {
+    Table,
+    Id,
+    OwnerId,
+    FileId,
+    Kind,
+    Entry,
+    CreatedAt,
+}
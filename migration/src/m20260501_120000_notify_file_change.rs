@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Notifications go out on commit and Postgres folds duplicates within a transaction,
+        // so a bulk operation wakes each listener once per owner rather than once per row.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION notify_file_change() RETURNS trigger AS $$
+                BEGIN
+                    PERFORM pg_notify('file_change', NEW.owner_id);
+                    RETURN NULL;
+                END;
+                $$ LANGUAGE plpgsql;
+
+                CREATE TRIGGER notify_file_change
+                AFTER INSERT ON file_change
+                FOR EACH ROW EXECUTE FUNCTION notify_file_change();
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let connection = manager.get_connection();
+
+        connection
+            .execute_unprepared("DROP TRIGGER IF EXISTS notify_file_change ON file_change")
+            .await?;
+
+        connection
+            .execute_unprepared("DROP FUNCTION IF EXISTS notify_file_change()")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShareLimit::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ShareLimit::Token).string().not_null().primary_key())
+                    .col(ColumnDef::new(ShareLimit::OwnerId).string().not_null())
+                    .col(ColumnDef::new(ShareLimit::FileId).string().not_null())
+                    .col(ColumnDef::new(ShareLimit::MaxDownloads).integer().not_null())
+                    .col(ColumnDef::new(ShareLimit::DownloadCount).integer().not_null().default(0))
+                    .col(ColumnDef::new(ShareLimit::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(ShareLimit::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-limit-owner_id")
+                            .from(ShareLimit::Table, ShareLimit::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-limit-file_id")
+                            .from(ShareLimit::Table, ShareLimit::FileId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-share-limit-file-id")
+                    .table(ShareLimit::Table)
+                    .col(ShareLimit::FileId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ShareLimit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ShareLimit {
+    Table,
+    Token,
+    OwnerId,
+    FileId,
+    MaxDownloads,
+    DownloadCount,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
@@ -0,0 +1,161 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+/// Share links move from the edge KV into Postgres, which becomes the source of truth. The
+/// download counters in `share_limit` are folded into the new table.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Share::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Share::Token).string().not_null().primary_key())
+                    .col(ColumnDef::new(Share::OwnerId).string().not_null())
+                    .col(ColumnDef::new(Share::FileId).string().not_null())
+                    .col(ColumnDef::new(Share::PasswordHash).string().null())
+                    .col(ColumnDef::new(Share::ExpiresAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Share::MaxDownloads).integer().null())
+                    .col(ColumnDef::new(Share::DownloadCount).integer().not_null().default(0))
+                    .col(ColumnDef::new(Share::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(Share::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-owner_id")
+                            .from(Share::Table, Share::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-file_id")
+                            .from(Share::Table, Share::FileId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-share-owner-file")
+                    .table(Share::Table)
+                    .col(Share::OwnerId)
+                    .col(Share::FileId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO share (token, owner_id, file_id, max_downloads, download_count, created_at, updated_at)
+                SELECT token, owner_id, file_id, max_downloads, download_count, created_at, updated_at
+                FROM share_limit
+                ON CONFLICT (token) DO NOTHING;
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ShareLimit::Table).to_owned())
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShareLimit::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ShareLimit::Token).string().not_null().primary_key())
+                    .col(ColumnDef::new(ShareLimit::OwnerId).string().not_null())
+                    .col(ColumnDef::new(ShareLimit::FileId).string().not_null())
+                    .col(ColumnDef::new(ShareLimit::MaxDownloads).integer().not_null())
+                    .col(ColumnDef::new(ShareLimit::DownloadCount).integer().not_null().default(0))
+                    .col(ColumnDef::new(ShareLimit::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(ShareLimit::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-limit-owner_id")
+                            .from(ShareLimit::Table, ShareLimit::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-limit-file_id")
+                            .from(ShareLimit::Table, ShareLimit::FileId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-share-limit-file-id")
+                    .table(ShareLimit::Table)
+                    .col(ShareLimit::FileId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO share_limit (token, owner_id, file_id, max_downloads, download_count, created_at, updated_at)
+                SELECT token, owner_id, file_id, max_downloads, download_count, created_at, updated_at
+                FROM share
+                WHERE max_downloads IS NOT NULL;
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Share::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Share {
+    Table,
+    Token,
+    OwnerId,
+    FileId,
+    PasswordHash,
+    ExpiresAt,
+    MaxDownloads,
+    DownloadCount,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ShareLimit {
+    Table,
+    Token,
+    OwnerId,
+    FileId,
+    MaxDownloads,
+    DownloadCount,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FileGrant::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(FileGrant::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(FileGrant::FileId).string().not_null())
+                    .col(ColumnDef::new(FileGrant::OwnerId).string().not_null())
+                    .col(ColumnDef::new(FileGrant::GranteeId).string().not_null())
+                    .col(ColumnDef::new(FileGrant::Role).string().not_null())
+                    .col(ColumnDef::new(FileGrant::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(FileGrant::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-grant-file_id")
+                            .from(FileGrant::Table, FileGrant::FileId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-grant-owner_id")
+                            .from(FileGrant::Table, FileGrant::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-grant-grantee_id")
+                            .from(FileGrant::Table, FileGrant::GranteeId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-grant-file-grantee")
+                    .table(FileGrant::Table)
+                    .col(FileGrant::FileId)
+                    .col(FileGrant::GranteeId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-grant-grantee-id")
+                    .table(FileGrant::Table)
+                    .col(FileGrant::GranteeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FileGrant::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FileGrant {
+    Table,
+    Id,
+    FileId,
+    OwnerId,
+    GranteeId,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
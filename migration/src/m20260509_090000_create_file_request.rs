@@ -0,0 +1,144 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FileRequest::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(FileRequest::Token).string().not_null().primary_key())
+                    .col(ColumnDef::new(FileRequest::OwnerId).string().not_null())
+                    .col(ColumnDef::new(FileRequest::FolderId).string().not_null())
+                    .col(ColumnDef::new(FileRequest::Title).string().null())
+                    .col(ColumnDef::new(FileRequest::MaxFileSize).big_integer().null())
+                    .col(ColumnDef::new(FileRequest::MaxFiles).integer().null())
+                    .col(ColumnDef::new(FileRequest::AllowedTypes).json().null())
+                    .col(ColumnDef::new(FileRequest::UploadCount).integer().not_null().default(0))
+                    .col(ColumnDef::new(FileRequest::ExpiresAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(FileRequest::CreatedAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(FileRequest::UpdatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-request-owner_id")
+                            .from(FileRequest::Table, FileRequest::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-request-folder_id")
+                            .from(FileRequest::Table, FileRequest::FolderId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-request-owner-id")
+                    .table(FileRequest::Table)
+                    .col(FileRequest::OwnerId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Kept when the request itself is deleted, so the note on who sent a file stays.
+        manager
+            .create_table(
+                Table::create()
+                    .table(FileRequestUpload::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(FileRequestUpload::FileId).string().not_null().primary_key())
+                    .col(ColumnDef::new(FileRequestUpload::Token).string().null())
+                    .col(ColumnDef::new(FileRequestUpload::OwnerId).string().not_null())
+                    .col(ColumnDef::new(FileRequestUpload::UploaderName).string().not_null())
+                    .col(ColumnDef::new(FileRequestUpload::UploaderEmail).string().null())
+                    .col(ColumnDef::new(FileRequestUpload::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-request-upload-file_id")
+                            .from(FileRequestUpload::Table, FileRequestUpload::FileId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-request-upload-token")
+                            .from(FileRequestUpload::Table, FileRequestUpload::Token)
+                            .to(FileRequest::Table, FileRequest::Token)
+                            .on_delete(ForeignKeyAction::SetNull)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-file-request-upload-owner_id")
+                            .from(FileRequestUpload::Table, FileRequestUpload::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-request-upload-token")
+                    .table(FileRequestUpload::Table)
+                    .col(FileRequestUpload::Token)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FileRequestUpload::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(FileRequest::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FileRequest {
+    Table,
+    Token,
+    OwnerId,
+    FolderId,
+    Title,
+    MaxFileSize,
+    MaxFiles,
+    AllowedTypes,
+    UploadCount,
+    ExpiresAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum FileRequestUpload {
+    Table,
+    FileId,
+    Token,
+    OwnerId,
+    UploaderName,
+    UploaderEmail,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+use crate::m20260321_142905_create_user::User;
+
+/// Every visit to a share link, kept for the owner. The token isn't a foreign key, the history
+/// of a revoked link stays readable.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShareAccess::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ShareAccess::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ShareAccess::Token).string().not_null())
+                    .col(ColumnDef::new(ShareAccess::OwnerId).string().not_null())
+                    .col(ColumnDef::new(ShareAccess::FileId).string().null())
+                    .col(ColumnDef::new(ShareAccess::Action).string().not_null())
+                    .col(ColumnDef::new(ShareAccess::Outcome).string().not_null())
+                    .col(ColumnDef::new(ShareAccess::Country).string().null())
+                    .col(ColumnDef::new(ShareAccess::Asn).big_integer().null())
+                    .col(ColumnDef::new(ShareAccess::AsOrganization).string().null())
+                    .col(ColumnDef::new(ShareAccess::UserAgent).text().null())
+                    .col(ColumnDef::new(ShareAccess::CreatedAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-access-owner_id")
+                            .from(ShareAccess::Table, ShareAccess::OwnerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-share-access-token-created-at")
+                    .table(ShareAccess::Table)
+                    .col(ShareAccess::Token)
+                    .col(ShareAccess::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ShareAccess::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ShareAccess {
+    Table,
+    Id,
+    Token,
+    OwnerId,
+    FileId,
+    Action,
+    Outcome,
+    Country,
+    Asn,
+    AsOrganization,
+    UserAgent,
+    CreatedAt,
+}
@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+/// Collections: one link for several files picked from anywhere in the owner's tree. A
+/// collection is a share without a `file_id`, its files are listed in `share_item`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Share::Table)
+                    .modify_column(ColumnDef::new(Share::FileId).string().null())
+                    .add_column(ColumnDef::new(Share::Title).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShareItem::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ShareItem::Token).string().not_null())
+                    .col(ColumnDef::new(ShareItem::FileId).string().not_null())
+                    .col(ColumnDef::new(ShareItem::Position).integer().not_null())
+                    .primary_key(Index::create().col(ShareItem::Token).col(ShareItem::FileId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-item-token")
+                            .from(ShareItem::Table, ShareItem::Token)
+                            .to(Share::Table, Share::Token)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-share-item-file_id")
+                            .from(ShareItem::Table, ShareItem::FileId)
+                            .to(File::Table, File::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ShareItem::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DELETE FROM share WHERE file_id IS NULL")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Share::Table)
+                    .modify_column(ColumnDef::new(Share::FileId).string().not_null())
+                    .drop_column(Share::Title)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Share {
+    Table,
+    Token,
+    FileId,
+    Title,
+}
+
+#[derive(DeriveIden)]
+enum ShareItem {
+    Table,
+    Token,
+    FileId,
+    Position,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+/// What a copy job has copied so far, so running it again after a restart doesn't copy
+/// the same entries twice.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobCopy::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(JobCopy::JobId).string().not_null())
+                    .col(ColumnDef::new(JobCopy::SourceId).string().not_null())
+                    .col(ColumnDef::new(JobCopy::NewId).string().not_null())
+                    .primary_key(Index::create().col(JobCopy::JobId).col(JobCopy::SourceId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-job-copy-job_id")
+                            .from(JobCopy::Table, JobCopy::JobId)
+                            .to(Job::Table, Job::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobCopy::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JobCopy {
+    Table,
+    JobId,
+    SourceId,
+    NewId,
+}
+
+#[derive(DeriveIden)]
+enum Job {
+    Table,
+    Id,
+}
@@ -0,0 +1,222 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FileChangeCounter::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(FileChangeCounter::OwnerId).string().not_null().primary_key())
+                    .col(ColumnDef::new(FileChangeCounter::LastSequence).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FileChange::Table)
+                    .add_column(ColumnDef::new(FileChange::Sequence).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let connection = manager.get_connection();
+
+        // Cursors handed out so far are row ids, numbering carries on from them.
+        connection
+            .execute_unprepared(
+                r#"
+                UPDATE file_change SET sequence = id;
+
+                INSERT INTO file_change_counter (owner_id, last_sequence)
+                SELECT owner_id, max(id) FROM file_change GROUP BY owner_id;
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-file-change-owner-id-id")
+                    .table(FileChange::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-change-owner-id-sequence")
+                    .table(FileChange::Table)
+                    .col(FileChange::OwnerId)
+                    .col(FileChange::Sequence)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // The row trigger used to take a per-owner advisory lock held until commit, which kept
+        // sequence numbers in commit order but serialized every write of an owner for the
+        // length of its transaction. Changes are now numbered by a deferred trigger that runs
+        // as the transaction commits, so the owner's counter row is only locked for the moment
+        // between numbering and commit. Readers only see numbered rows, and an owner's numbers
+        // still follow commit order.
+        connection
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION record_file_change() RETURNS trigger AS $$
+                DECLARE
+                    entry file%ROWTYPE;
+                    change_kind text;
+                BEGIN
+                    IF TG_OP = 'INSERT' THEN
+                        entry := NEW;
+                        change_kind := 'created';
+                    ELSIF TG_OP = 'DELETE' THEN
+                        entry := OLD;
+                        change_kind := 'deleted';
+                    ELSIF OLD.path IS DISTINCT FROM NEW.path THEN
+                        entry := NEW;
+                        change_kind := 'moved';
+                    ELSIF (OLD.file_name, OLD.file_type, OLD.upload_completed, OLD.is_directory)
+                        IS NOT DISTINCT FROM (NEW.file_name, NEW.file_type, NEW.upload_completed, NEW.is_directory)
+                        AND (NEW.is_directory OR OLD.file_size = NEW.file_size) THEN
+                        RETURN NULL;
+                    ELSE
+                        entry := NEW;
+                        change_kind := 'updated';
+                    END IF;
+
+                    INSERT INTO file_change (owner_id, file_id, kind, entry, created_at)
+                    VALUES (entry.owner_id, entry.id, change_kind, to_jsonb(entry), now());
+
+                    RETURN NULL;
+                END;
+                $$ LANGUAGE plpgsql;
+
+                CREATE OR REPLACE FUNCTION sequence_file_change() RETURNS trigger AS $$
+                DECLARE
+                    next_sequence bigint;
+                BEGIN
+                    INSERT INTO file_change_counter (owner_id, last_sequence)
+                    VALUES (NEW.owner_id, 1)
+                    ON CONFLICT (owner_id) DO UPDATE
+                    SET last_sequence = file_change_counter.last_sequence + 1
+                    RETURNING last_sequence INTO next_sequence;
+
+                    UPDATE file_change SET sequence = next_sequence WHERE id = NEW.id;
+
+                    RETURN NULL;
+                END;
+                $$ LANGUAGE plpgsql;
+
+                CREATE CONSTRAINT TRIGGER sequence_file_change
+                AFTER INSERT ON file_change
+                DEFERRABLE INITIALLY DEFERRED
+                FOR EACH ROW EXECUTE FUNCTION sequence_file_change();
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let connection = manager.get_connection();
+
+        connection
+            .execute_unprepared(
+                r#"
+                DROP TRIGGER IF EXISTS sequence_file_change ON file_change;
+                DROP FUNCTION IF EXISTS sequence_file_change();
+
+                CREATE OR REPLACE FUNCTION record_file_change() RETURNS trigger AS $$
+                DECLARE
+                    entry file%ROWTYPE;
+                    change_kind text;
+                BEGIN
+                    IF TG_OP = 'INSERT' THEN
+                        entry := NEW;
+                        change_kind := 'created';
+                    ELSIF TG_OP = 'DELETE' THEN
+                        entry := OLD;
+                        change_kind := 'deleted';
+                    ELSIF OLD.path IS DISTINCT FROM NEW.path THEN
+                        entry := NEW;
+                        change_kind := 'moved';
+                    ELSIF (OLD.file_name, OLD.file_type, OLD.upload_completed, OLD.is_directory)
+                        IS NOT DISTINCT FROM (NEW.file_name, NEW.file_type, NEW.upload_completed, NEW.is_directory)
+                        AND (NEW.is_directory OR OLD.file_size = NEW.file_size) THEN
+                        RETURN NULL;
+                    ELSE
+                        entry := NEW;
+                        change_kind := 'updated';
+                    END IF;
+
+                    PERFORM pg_advisory_xact_lock(hashtext('file_change'), hashtext(entry.owner_id));
+
+                    INSERT INTO file_change (owner_id, file_id, kind, entry, created_at)
+                    VALUES (entry.owner_id, entry.id, change_kind, to_jsonb(entry), now());
+
+                    RETURN NULL;
+                END;
+                $$ LANGUAGE plpgsql;
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-file-change-owner-id-sequence")
+                    .table(FileChange::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-file-change-owner-id-id")
+                    .table(FileChange::Table)
+                    .col(FileChange::OwnerId)
+                    .col(FileChange::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(FileChange::Table)
+                    .drop_column(FileChange::Sequence)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(FileChangeCounter::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FileChange {
+    Table,
+    Id,
+    OwnerId,
+    Sequence,
+}
+
+#[derive(DeriveIden)]
+enum FileChangeCounter {
+    Table,
+    OwnerId,
+    LastSequence,
+}
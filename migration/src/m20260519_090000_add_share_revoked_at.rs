@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Revoked and rotated links stay on record so a copy the edge still holds is refused
+        // rather than mistaken for a token from before the share table.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Share::Table)
+                    .add_column(ColumnDef::new(Share::RevokedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DELETE FROM share WHERE revoked_at IS NOT NULL")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Share::Table)
+                    .drop_column(Share::RevokedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Share {
+    Table,
+    RevokedAt,
+}
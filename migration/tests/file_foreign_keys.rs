@@ -0,0 +1,79 @@
+//! Runs the migrations against the database in `DATABASE_URL`, which it wipes first:
+//! `DATABASE_URL=postgres://… cargo test -p migration -- --ignored`
+
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, Statement};
+
+/// The migrations before `m20260416_101500_add_file_foreign_keys`.
+const BEFORE_FOREIGN_KEYS: u32 = 5;
+
+async fn scratch_database() -> DatabaseConnection {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must point at a scratch database");
+    let database = Database::connect(url).await.unwrap();
+
+    database
+        .execute_unprepared("DROP SCHEMA public CASCADE; CREATE SCHEMA public;")
+        .await
+        .unwrap();
+
+    database
+}
+
+async fn ids_and_paths(database: &DatabaseConnection, table: &str) -> Vec<(String, String)> {
+    let sql = format!(r#"SELECT "id", "path" FROM "{}" ORDER BY "id""#, table);
+
+    database
+        .query_all_raw(Statement::from_string(database.get_database_backend(), sql))
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| (row.try_get("", "id").unwrap(), row.try_get("", "path").unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+#[ignore = "needs a scratch Postgres database in DATABASE_URL"]
+async fn children_of_quarantined_parents_are_reattached() {
+    let database = scratch_database().await;
+    Migrator::up(&database, Some(BEFORE_FOREIGN_KEYS)).await.unwrap();
+
+    // `ghost` has no user row. Their directory holds one of their own files and one of alice's.
+    database
+        .execute_unprepared(
+            r#"
+            INSERT INTO "user" ("id", "email", "username", "created_at")
+            VALUES ('alice', 'alice@example.com', 'alice', now());
+
+            INSERT INTO "file" ("id", "file_name", "owner_id", "file_size", "created_at", "upload_completed", "file_type", "path", "is_directory")
+            VALUES
+                ('ghost-dir', 'Ghost', 'ghost', 0, now(), true, '', '', true),
+                ('ghost-file', 'a.txt', 'ghost', 1, now(), true, 'text/plain', 'ghost-dir', false),
+                ('adopted', 'b.txt', 'alice', 1, now(), true, 'text/plain', 'ghost-dir', false),
+                ('stray', 'c.txt', 'alice', 1, now(), true, 'text/plain', 'deleted-dir', false),
+                ('home', 'Home', 'alice', 0, now(), true, '', '', true),
+                ('nested', 'd.txt', 'alice', 1, now(), true, 'text/plain', 'home', false);
+            "#,
+        )
+        .await
+        .unwrap();
+
+    Migrator::up(&database, Some(1)).await.unwrap();
+
+    assert_eq!(
+        ids_and_paths(&database, "file").await,
+        [
+            ("adopted".to_string(), String::new()),
+            ("home".to_string(), String::new()),
+            ("nested".to_string(), "home".to_string()),
+            ("stray".to_string(), String::new()),
+        ]
+    );
+
+    assert_eq!(
+        ids_and_paths(&database, "file_orphan").await,
+        [
+            ("ghost-dir".to_string(), String::new()),
+            ("ghost-file".to_string(), "ghost-dir".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,145 @@
+use actix_web::HttpResponse;
+use common::entities::prelude::File;
+use common::types::file::grant::GrantRole;
+use common::types::file::list::Breadcrumb;
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait, Statement, Value};
+
+/// How far a user may go with an entry. Owners can do anything, including managing grants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl From<GrantRole> for Role {
+    fn from(role: GrantRole) -> Self {
+        match role {
+            GrantRole::Viewer => Role::Viewer,
+            GrantRole::Editor => Role::Editor,
+        }
+    }
+}
+
+/// A user's access to an entry, either from owning it or from a grant on it or on a folder
+/// above it.
+#[derive(Debug, Clone)]
+pub struct Access {
+    /// Whose tree the entry is in. Queries and storage keys use this, not the caller.
+    pub owner_id: String,
+    pub role: Role,
+    /// The topmost granted entry on the way up, where a grantee's view of the tree starts.
+    /// `None` for the owner.
+    pub root_id: Option<String>,
+}
+
+impl Access {
+    pub fn allows(&self, role: Role) -> bool {
+        self.role >= role
+    }
+
+    /// Drops the breadcrumbs above the granted root, a grantee never learns what sits there.
+    pub fn visible_breadcrumbs(&self, breadcrumbs: Vec<Breadcrumb>) -> Vec<Breadcrumb> {
+        match &self.root_id {
+            Some(root_id) => breadcrumbs
+                .into_iter()
+                .skip_while(|crumb| &crumb.id != root_id)
+                .collect(),
+            None => breadcrumbs,
+        }
+    }
+}
+
+/// The user's access to an entry, `None` if they have none. An empty id is the user's own
+/// root.
+pub async fn access<C: ConnectionTrait>(
+    connection: &C,
+    user_id: &str,
+    file_id: &str,
+) -> Result<Option<Access>, DbErr> {
+    if file_id.is_empty() {
+        return Ok(Some(Access {
+            owner_id: user_id.to_string(),
+            role: Role::Owner,
+            root_id: None,
+        }));
+    }
+
+    let Some(entry) = File::find_by_id(file_id).one(connection).await? else {
+        return Ok(None);
+    };
+
+    if entry.owner_id == user_id {
+        return Ok(Some(Access {
+            owner_id: entry.owner_id,
+            role: Role::Owner,
+            root_id: None,
+        }));
+    }
+
+    // Grants are inherited, so every grant on the way up counts and the strongest wins.
+    // language=PostgreSQL
+    let sql = r#"
+        WITH RECURSIVE ancestors AS (
+            SELECT id, path, 0 AS depth FROM file WHERE id = $1 AND owner_id = $3
+            UNION ALL
+            SELECT f.id, f.path, a.depth + 1 FROM file f
+            INNER JOIN ancestors a ON f.id = a.path
+            WHERE f.owner_id = $3
+        )
+        SELECT g.file_id, g.role FROM ancestors a
+        INNER JOIN file_grant g ON g.file_id = a.id
+        WHERE g.grantee_id = $2
+        ORDER BY a.depth DESC;
+    "#;
+
+    let rows = connection
+        .query_all_raw(Statement::from_sql_and_values(
+            connection.get_database_backend(),
+            sql,
+            [
+                Value::from(file_id),
+                Value::from(user_id),
+                Value::from(entry.owner_id.clone()),
+            ],
+        ))
+        .await?;
+
+    let grants = rows
+        .iter()
+        .filter_map(|row| {
+            let file_id = row.try_get::<String>("", "file_id").ok()?;
+            let role = GrantRole::parse(&row.try_get::<String>("", "role").ok()?)?;
+            Some((file_id, Role::from(role)))
+        })
+        .collect::<Vec<_>>();
+
+    let Some(role) = grants.iter().map(|(_, role)| *role).max() else {
+        return Ok(None);
+    };
+
+    Ok(Some(Access {
+        owner_id: entry.owner_id,
+        role,
+        root_id: grants.into_iter().next().map(|(file_id, _)| file_id),
+    }))
+}
+
+/// `access` for route handlers. Answers 404 when the entry is out of the user's reach, so its
+/// existence isn't given away, and 403 when they can see it but not do this.
+pub async fn authorize<C: ConnectionTrait>(
+    connection: &C,
+    user_id: &str,
+    file_id: &str,
+    role: Role,
+) -> Result<Access, HttpResponse> {
+    match access(connection, user_id, file_id).await {
+        Ok(Some(access)) if access.allows(role) => Ok(access),
+        Ok(Some(_)) => Err(HttpResponse::Forbidden().finish()),
+        Ok(None) => Err(HttpResponse::NotFound().finish()),
+        Err(err) => {
+            log::error!("Failed to check access to {}: {:?}", file_id, err);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
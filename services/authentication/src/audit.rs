@@ -0,0 +1,73 @@
+use crate::middleware::middleware::ClientContext;
+use common::entities::audit_event;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr, NotSet, Set};
+use serde_json::Value;
+
+/// A single entry for the audit log. Routes that already run a transaction record it in
+/// there, so the event and the change it describes commit together.
+pub struct AuditEvent {
+    action: &'static str,
+    target_ids: Vec<String>,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl AuditEvent {
+    pub fn new(action: &'static str, target_ids: Vec<String>) -> Self {
+        AuditEvent {
+            action,
+            target_ids,
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn before(mut self, before: Value) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    pub fn after(mut self, after: Value) -> Self {
+        self.after = Some(after);
+        self
+    }
+}
+
+pub async fn record<C: ConnectionTrait>(
+    connection: &C,
+    actor_id: &str,
+    client: &ClientContext,
+    event: AuditEvent,
+) -> Result<(), DbErr> {
+    audit_event::ActiveModel {
+        id: NotSet,
+        actor_id: Set(actor_id.to_string()),
+        action: Set(event.action.to_string()),
+        target_ids: Set(Value::from(event.target_ids)),
+        before: Set(event.before),
+        after: Set(event.after),
+        ip: Set(client.ip.clone()),
+        user_agent: Set(client.user_agent.clone()),
+        created_at: Set(DateTimeWithTimeZone::from(chrono::Utc::now())),
+    }
+    .insert(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// For routes without a transaction of their own. The change has already happened, so a
+/// failure here is logged rather than turned into an error response.
+pub async fn record_logged<C: ConnectionTrait>(
+    connection: &C,
+    actor_id: &str,
+    client: &ClientContext,
+    event: AuditEvent,
+) {
+    let action = event.action;
+
+    if let Err(err) = record(connection, actor_id, client, event).await {
+        log::error!("Failed to record the {} audit event for {}: {:?}", action, actor_id, err);
+    }
+}
@@ -0,0 +1,122 @@
+use common::entities::prelude::FileChange;
+use common::entities::{file, file_change};
+use common::types::file::changes::{ChangeElement, ChangeKind};
+use common::types::file::list::ListFileElement;
+use sea_orm::sqlx::postgres::PgListener;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const CHANNEL: &str = "file_change";
+
+/// Fans the `file_change` notifications from Postgres out to every open event stream in this
+/// process. Carries the owner id only, streams read the changes themselves.
+#[derive(Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<String>,
+}
+
+impl ChangeFeed {
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.sender.subscribe()
+    }
+}
+
+/// Holds a single LISTEN connection for the whole process and reconnects when it drops.
+/// Streams fall back to polling on their heartbeat, so notifications lost in between only
+/// delay a change rather than lose it.
+pub fn spawn_listener(database: &DatabaseConnection) -> ChangeFeed {
+    let (sender, _) = broadcast::channel(1024);
+    let feed = ChangeFeed { sender: sender.clone() };
+    let pool = database.get_postgres_connection_pool().clone();
+
+    tokio::spawn(async move {
+        loop {
+            let mut listener = match PgListener::connect_with(&pool).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    log::error!("Failed to open the change listener: {:?}", err);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            if let Err(err) = listener.listen(CHANNEL).await {
+                log::error!("Failed to listen for file changes: {:?}", err);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+
+            loop {
+                match listener.recv().await {
+                    // Nobody subscribed is not an error.
+                    Ok(notification) => {
+                        let _ = sender.send(notification.payload().to_string());
+                    }
+                    Err(err) => {
+                        log::error!("Change listener dropped: {:?}", err);
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    feed
+}
+
+/// Up to `limit` of the owner's changes after `cursor`, oldest first, along with the cursor to
+/// continue from and whether more are waiting.
+pub async fn changes_after(
+    database: &DatabaseConnection,
+    owner_id: &str,
+    cursor: i64,
+    limit: u64,
+) -> Result<(Vec<ChangeElement>, i64, bool), DbErr> {
+    let mut rows = FileChange::find()
+        .filter(file_change::Column::OwnerId.eq(owner_id))
+        .filter(file_change::Column::Sequence.gt(cursor))
+        .order_by_asc(file_change::Column::Sequence)
+        .limit(limit + 1)
+        .all(database)
+        .await?;
+
+    let has_more = rows.len() as u64 > limit;
+    if has_more { rows.pop(); }
+
+    let cursor = rows.last().and_then(|row| row.sequence).unwrap_or(cursor);
+
+    let changes = rows
+        .into_iter()
+        .filter_map(|row| {
+            let sequence = row.sequence?;
+            let kind = ChangeKind::parse(&row.kind)?;
+            let entry = match serde_json::from_value::<file::Model>(row.entry) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    log::error!("Unreadable file change {}: {:?}", row.id, err);
+                    return None;
+                }
+            };
+
+            Some(ChangeElement {
+                sequence,
+                kind,
+                entry: ListFileElement {
+                    id: entry.id,
+                    file_name: entry.file_name,
+                    file_size: entry.file_size,
+                    created_at: entry.created_at,
+                    upload_completed: entry.upload_completed,
+                    file_type: entry.file_type,
+                    path: entry.path,
+                    is_directory: entry.is_directory,
+                    item_count: entry.item_count,
+                },
+                changed_at: row.created_at,
+            })
+        })
+        .collect();
+
+    Ok((changes, cursor, has_more))
+}
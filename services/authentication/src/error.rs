@@ -0,0 +1,118 @@
+use actix_web::body::{to_bytes, BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse};
+use common::types::error::{ApiError, ErrorCode, REQUEST_ID_HEADER};
+use log::error;
+use serde_json::Value;
+
+/// An error response in the shared `ApiError` envelope.
+pub fn error_response(code: ErrorCode, message: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(StatusCode::from_u16(code.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR))
+        .json(ApiError::new(code, message))
+}
+
+/// Logs what failed and answers a plain 500, so database and library errors stay out of
+/// responses.
+pub fn internal_error(context: &str, err: impl std::fmt::Debug) -> HttpResponse {
+    error!("{}: {:?}", context, err);
+    error_response(ErrorCode::Internal, ErrorCode::Internal.default_message())
+}
+
+/// Puts every error response into the `ApiError` envelope and tags all responses with the
+/// request's id, the one the edge passed on or a fresh one. Handlers answering with an empty
+/// body or plain text don't need to know about it: plain text becomes the message of a client
+/// error. Server errors only ever carry the default message, whatever they said is logged
+/// under the request id instead. JSON objects of another shape pass through untouched.
+pub async fn error_envelope(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| valid_request_id(id))
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let http_request = req.request().clone();
+    let method = req.method().clone();
+    let path = req.path().to_string();
+
+    let response = match next.call(req).await {
+        Ok(response) => response.map_into_boxed_body(),
+        Err(err) => ServiceResponse::new(http_request, err.error_response()),
+    };
+
+    let (request, mut response) = response.into_parts();
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static("x-request-id"), value);
+    }
+
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return Ok(ServiceResponse::new(request, response));
+    }
+
+    let is_text = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/plain"));
+
+    let (mut response, body) = response.into_parts();
+    let body = to_bytes(body).await.unwrap_or_default();
+
+    if status.is_server_error() && !body.is_empty() {
+        error!(
+            "{} {} failed with {} ({}): {}",
+            method,
+            path,
+            status.as_u16(),
+            request_id,
+            String::from_utf8_lossy(&body)
+        );
+    }
+
+    let envelope = match serde_json::from_slice::<Value>(&body) {
+        Ok(Value::Object(fields)) => serde_json::from_value::<ApiError>(Value::Object(fields)).ok(),
+        Ok(Value::String(message)) => Some(wrap(status, Some(message))),
+        _ if is_text => Some(wrap(status, Some(String::from_utf8_lossy(&body).into_owned()))),
+        _ => Some(wrap(status, None)),
+    };
+
+    let body = match envelope {
+        Some(mut api_error) => {
+            api_error.request_id = Some(request_id);
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            response.headers_mut().remove(header::CONTENT_LENGTH);
+            serde_json::to_vec(&api_error)?.into()
+        }
+        None => body,
+    };
+
+    Ok(ServiceResponse::new(request, response.set_body(BoxBody::new(body))))
+}
+
+/// An envelope for a bare error, keeping a client error's own words.
+fn wrap(status: StatusCode, message: Option<String>) -> ApiError {
+    let code = ErrorCode::from_status(status.as_u16());
+
+    match message {
+        Some(message) if status.is_client_error() && !message.trim().is_empty() => ApiError::new(code, message),
+        _ => ApiError::from_code(code),
+    }
+}
+
+/// Ids come from the edge, anything else is replaced so logs stay readable.
+fn valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
@@ -0,0 +1,99 @@
+pub mod worker;
+
+use common::entities::job;
+use common::entities::prelude::Job;
+use common::types::file::job::{JobOperation, JobResponse, JobStatus};
+use migration::Expr;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Requests touching more entries than this are handed to the job workers and answered
+/// with `202 Accepted` instead of running inline.
+pub const JOB_THRESHOLD: usize = 500;
+
+/// How many entries a worker handles per transaction. Also keeps S3 batch deletes well
+/// under their 1000 key limit.
+pub const JOB_BATCH_SIZE: usize = 250;
+
+#[derive(Clone, Default)]
+pub struct JobQueue {
+    notify: Arc<Notify>,
+}
+
+impl JobQueue {
+    /// Queues an operation on `owner_id`'s tree. The job belongs to `submitted_by`, the only
+    /// one who can follow or cancel it.
+    pub async fn submit(
+        &self,
+        database: &DatabaseConnection,
+        owner_id: &str,
+        submitted_by: &str,
+        operation: &JobOperation,
+    ) -> Result<job::Model, DbErr> {
+        let payload = serde_json::to_value(operation).map_err(|err| DbErr::Custom(err.to_string()))?;
+        let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+        let job = job::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            owner_id: Set(owner_id.to_string()),
+            submitted_by: Set(submitted_by.to_string()),
+            kind: Set(operation.kind().to_string()),
+            status: Set(JobStatus::Queued.as_str().to_string()),
+            payload: Set(payload),
+            total_items: Set(0),
+            processed_items: Set(0),
+            errors: Set(serde_json::json!([])),
+            result: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            completed_at: Set(None),
+        }
+        .insert(database)
+        .await?;
+
+        self.notify.notify_one();
+
+        Ok(job)
+    }
+
+    /// Wakes a worker for a job that was queued some other way than `submit`.
+    pub fn notify(&self) {
+        self.notify.notify_one();
+    }
+
+    pub async fn wait(&self) {
+        self.notify.notified().await
+    }
+}
+
+/// Marks a queued or running job as cancelled. Running jobs notice between batches.
+pub async fn cancel(database: &DatabaseConnection, submitted_by: &str, job_id: &str) -> Result<bool, DbErr> {
+    let result = Job::update_many()
+        .col_expr(job::Column::Status, Expr::value(JobStatus::Cancelled.as_str()))
+        .col_expr(job::Column::UpdatedAt, Expr::current_timestamp())
+        .col_expr(job::Column::CompletedAt, Expr::current_timestamp())
+        .filter(job::Column::Id.eq(job_id))
+        .filter(job::Column::SubmittedBy.eq(submitted_by))
+        .filter(job::Column::Status.is_in([JobStatus::Queued.as_str(), JobStatus::Running.as_str()]))
+        .exec(database)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+pub fn job_response(job: job::Model) -> JobResponse {
+    JobResponse {
+        status: JobStatus::parse(&job.status).unwrap_or(JobStatus::Failed),
+        errors: serde_json::from_value(job.errors).unwrap_or_default(),
+        id: job.id,
+        kind: job.kind,
+        total_items: job.total_items,
+        processed_items: job.processed_items,
+        result: job.result,
+        created_at: job.created_at,
+        updated_at: job.updated_at,
+        completed_at: job.completed_at,
+    }
+}
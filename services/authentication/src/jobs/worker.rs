@@ -0,0 +1,424 @@
+use crate::access::{access, Role};
+use crate::jobs::{JobQueue, JOB_BATCH_SIZE};
+use crate::routes::file::copy::{copy_destination, copy_tree, fetch_tree, top_level};
+use crate::routes::file::delete::delete_entries;
+use crate::routes::file::delete_directory::subtree;
+use crate::routes::file::r#move::move_entries;
+use crate::routes::file::tree::FileTreeExtension;
+use common::entities::job;
+use common::entities::prelude::{Job, JobCopy};
+use common::types::file::job::{JobOperation, JobStatus};
+use migration::{Expr, ExprTrait};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter, Statement};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use storage::s3_manager::S3StorageManager;
+use storage::s3_scoped_storage::S3ScopedStorage;
+
+/// Workers also poll on their own so jobs queued by another instance, or left behind by a
+/// restart, are picked up without a local notification.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a running job says it is still alive, even while a single batch takes long.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often jobs whose process went away are looked for. A job counts as stalled after ten
+/// missed heartbeats.
+const STALE_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn spawn_workers(
+    count: usize,
+    database: DatabaseConnection,
+    storage: Arc<S3StorageManager>,
+    queue: JobQueue,
+) {
+    for _ in 0..count {
+        let database = database.clone();
+        let storage = storage.clone();
+        let queue = queue.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match claim(&database).await {
+                    Ok(Some(job)) => {
+                        execute(&database, &storage, job).await;
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(err) => log::error!("Failed to claim a job: {:?}", err),
+                }
+
+                tokio::select! {
+                    _ = queue.wait() => {}
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(STALE_SWEEP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match requeue_stale(&database).await {
+                Ok(0) => {}
+                Ok(count) => {
+                    log::info!("Requeued {} stalled jobs", count);
+                    queue.notify();
+                }
+                Err(err) => log::error!("Failed to requeue stalled jobs: {:?}", err),
+            }
+        }
+    });
+}
+
+/// Puts jobs that stopped sending heartbeats back in the queue, for when the process running
+/// them went away mid-way. They start over from the first entry: deletes and moves are
+/// idempotent, copies skip what `job_copy` says they already copied.
+pub async fn requeue_stale(database: &DatabaseConnection) -> Result<u64, DbErr> {
+    let result = Job::update_many()
+        .col_expr(job::Column::Status, Expr::value(JobStatus::Queued.as_str()))
+        .col_expr(job::Column::ProcessedItems, Expr::value(0))
+        .col_expr(job::Column::Errors, Expr::value(json!([])))
+        .filter(job::Column::Status.eq(JobStatus::Running.as_str()))
+        .filter(Expr::cust("updated_at < now() - interval '10 minutes'"))
+        .exec(database)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+async fn claim(database: &DatabaseConnection) -> Result<Option<job::Model>, DbErr> {
+    // language=PostgreSQL
+    let sql = r#"
+        UPDATE job SET status = 'running', updated_at = now()
+        WHERE id = (
+            SELECT id FROM job WHERE status = 'queued'
+            ORDER BY created_at
+            FOR UPDATE SKIP LOCKED
+            LIMIT 1
+        )
+        RETURNING *;
+    "#;
+
+    Job::find()
+        .from_raw_sql(Statement::from_string(DbBackend::Postgres, sql))
+        .one(database)
+        .await
+}
+
+struct Progress<'a> {
+    database: &'a DatabaseConnection,
+    job_id: String,
+    owner_id: String,
+    submitted_by: String,
+    errors: Vec<String>,
+}
+
+impl Progress<'_> {
+    /// Records the total and reports whether the job is still wanted.
+    async fn start(&self, total: usize) -> Result<bool, DbErr> {
+        self.update(Job::update_many().col_expr(job::Column::TotalItems, Expr::value(total as i64)))
+            .await
+    }
+
+    /// Adds to the processed counter and reports whether the job is still wanted.
+    async fn advance(&self, processed: usize) -> Result<bool, DbErr> {
+        self.update(Job::update_many().col_expr(
+            job::Column::ProcessedItems,
+            Expr::col(job::Column::ProcessedItems).add(processed as i64),
+        ))
+        .await
+    }
+
+    /// Whether the job may change an entry: it has to sit in the owner's tree and whoever
+    /// submitted the job needs at least editor access to it.
+    async fn may_edit(&self, file_id: &str) -> Result<bool, DbErr> {
+        Ok(access(self.database, &self.submitted_by, file_id)
+            .await?
+            .is_some_and(|access| access.owner_id == self.owner_id && access.allows(Role::Editor)))
+    }
+
+    fn error(&mut self, message: String) {
+        log::warn!("Job {}: {}", self.job_id, message);
+        self.errors.push(message);
+    }
+
+    async fn finish(&self, result: Value) -> Result<(), DbErr> {
+        let status = if self.errors.is_empty() {
+            JobStatus::Completed
+        } else {
+            JobStatus::Failed
+        };
+
+        self.update(
+            Job::update_many()
+                .col_expr(job::Column::Status, Expr::value(status.as_str()))
+                .col_expr(job::Column::Result, Expr::value(result))
+                .col_expr(job::Column::CompletedAt, Expr::current_timestamp()),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Every write is guarded on the job still running, so a cancellation is never overwritten
+    /// and shows up here as zero affected rows.
+    async fn update(&self, update: sea_orm::UpdateMany<Job>) -> Result<bool, DbErr> {
+        let result = update
+            .col_expr(job::Column::Errors, Expr::value(json!(self.errors)))
+            .col_expr(job::Column::UpdatedAt, Expr::current_timestamp())
+            .filter(job::Column::Id.eq(self.job_id.clone()))
+            .filter(job::Column::Status.eq(JobStatus::Running.as_str()))
+            .exec(self.database)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+async fn execute(database: &DatabaseConnection, storage_manager: &S3StorageManager, job: job::Model) {
+    let mut progress = Progress {
+        database,
+        job_id: job.id.clone(),
+        owner_id: job.owner_id.clone(),
+        submitted_by: job.submitted_by.clone(),
+        errors: vec![],
+    };
+
+    let storage = S3ScopedStorage {
+        user_id: job.owner_id.clone(),
+        bucket: storage_manager.bucket.clone(),
+        client: storage_manager.client.clone(),
+    };
+
+    let heartbeat = tokio::spawn(heartbeat(database.clone(), job.id.clone()));
+
+    let outcome = match serde_json::from_value::<JobOperation>(job.payload) {
+        Ok(JobOperation::Delete(request)) => delete(&mut progress, request.file_ids).await,
+        Ok(JobOperation::DeleteDirectory(request)) => {
+            delete_directory(&mut progress, request.directory_id).await
+        }
+        Ok(JobOperation::Copy(request)) => {
+            copy(&mut progress, &storage, request.file_ids, request.destination_path).await
+        }
+        Ok(JobOperation::Move(request)) => {
+            r#move(&mut progress, request.file_ids, request.destination_path).await
+        }
+        Err(err) => {
+            progress.error(format!("Unreadable job payload: {}", err));
+            Ok(Value::Null)
+        }
+    };
+
+    let result = match outcome {
+        Ok(result) => result,
+        Err(err) => {
+            progress.error(format!("Job stopped: {}", err));
+            Value::Null
+        }
+    };
+
+    heartbeat.abort();
+
+    if let Err(err) = progress.finish(result).await {
+        log::error!("Failed to record the outcome of job {}: {:?}", job.id, err);
+    }
+}
+
+/// Keeps a running job's `updated_at` fresh so the stale sweep leaves it alone.
+async fn heartbeat(database: DatabaseConnection, job_id: String) {
+    let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let touched = Job::update_many()
+            .col_expr(job::Column::UpdatedAt, Expr::current_timestamp())
+            .filter(job::Column::Id.eq(job_id.clone()))
+            .filter(job::Column::Status.eq(JobStatus::Running.as_str()))
+            .exec(&database)
+            .await;
+
+        match touched {
+            Ok(result) if result.rows_affected == 0 => return,
+            Ok(_) => {}
+            Err(err) => log::warn!("Failed to record a heartbeat for job {}: {:?}", job_id, err),
+        }
+    }
+}
+
+async fn delete(
+    progress: &mut Progress<'_>,
+    file_ids: Vec<String>,
+) -> Result<Value, DbErr> {
+    let mut deleted = 0;
+
+    if !progress.start(file_ids.len()).await? {
+        return Ok(Value::Null);
+    }
+
+    for batch in file_ids.chunks(JOB_BATCH_SIZE) {
+        match delete_entries(progress.database, &progress.owner_id, batch).await {
+            Ok(rows) => deleted += rows.len(),
+            Err(err) => progress.error(format!("Failed to delete {} entries: {}", batch.len(), err)),
+        }
+
+        if !progress.advance(batch.len()).await? {
+            break;
+        }
+    }
+
+    Ok(json!({ "deleted": deleted }))
+}
+
+async fn delete_directory(
+    progress: &mut Progress<'_>,
+    directory_id: String,
+) -> Result<Value, DbErr> {
+    let entries = subtree(progress.database, &progress.owner_id, &directory_id).await?;
+    let mut deleted = 0;
+
+    if entries.is_empty() {
+        progress.error(format!("Directory {} not found", directory_id));
+        return Ok(Value::Null);
+    }
+
+    if !progress.start(entries.len()).await? {
+        return Ok(Value::Null);
+    }
+
+    // Deepest first, so a batch never removes a directory that still has children.
+    for batch in entries.chunks(JOB_BATCH_SIZE) {
+        let ids = batch.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>();
+
+        match delete_entries(progress.database, &progress.owner_id, &ids).await {
+            Ok(rows) => deleted += rows.len(),
+            Err(err) => progress.error(format!("Failed to delete {} entries: {}", ids.len(), err)),
+        }
+
+        if !progress.advance(batch.len()).await? {
+            break;
+        }
+    }
+
+    Ok(json!({ "deleted": deleted }))
+}
+
+async fn copy(
+    progress: &mut Progress<'_>,
+    storage: &S3ScopedStorage,
+    file_ids: Vec<String>,
+    destination: String,
+) -> Result<Value, DbErr> {
+    if !copy_destination(progress.database, &progress.owner_id, &destination).await? {
+        progress.error(format!("Destination {} not found or not a directory", destination));
+        return Ok(Value::Null);
+    }
+
+    let files = fetch_tree(progress.database, &progress.owner_id, &file_ids).await?;
+    let mut copied = Vec::new();
+
+    if !progress.start(files.len()).await? {
+        return Ok(Value::Null);
+    }
+
+    // One top-level entry at a time, each with its own transaction, so progress and
+    // cancellation have somewhere to land.
+    for root in top_level(&files, &file_ids) {
+        let tree = fetch_tree(progress.database, &progress.owner_id, std::slice::from_ref(&root)).await?;
+
+        // Copied before the job was interrupted and requeued.
+        let done = JobCopy::find_by_id((progress.job_id.clone(), root.clone()))
+            .one(progress.database)
+            .await?;
+
+        if let Some(done) = done {
+            copied.push(done.new_id);
+            if !progress.advance(tree.len()).await? {
+                break;
+            }
+            continue;
+        }
+
+        match copy_tree(
+            progress.database,
+            storage,
+            &progress.owner_id,
+            std::slice::from_ref(&root),
+            &destination,
+            &tree,
+            Some(&progress.job_id),
+        )
+        .await
+        {
+            Ok(ids) => copied.extend(ids),
+            Err(err) => progress.error(format!("Failed to copy {}: {:?}", root, err)),
+        }
+
+        if !progress.advance(tree.len()).await? {
+            break;
+        }
+    }
+
+    Ok(json!({ "file_ids": copied }))
+}
+
+async fn r#move(
+    progress: &mut Progress<'_>,
+    references: Vec<String>,
+    destination: String,
+) -> Result<Value, DbErr> {
+    let owner_id = progress.owner_id.clone();
+
+    let Some(destination) = progress.database.resolve_reference(&owner_id, &destination).await? else {
+        progress.error(format!("Destination {} not found", destination));
+        return Ok(Value::Null);
+    };
+
+    // Jobs from `/job/submit` arrive unchecked, and access may have changed since queueing,
+    // so everything the inline route checks is checked again here.
+    if !progress.may_edit(&destination).await?
+        || !copy_destination(progress.database, &owner_id, &destination).await?
+    {
+        progress.error(format!("Destination {} not found or not a directory", destination));
+        return Ok(Value::Null);
+    }
+
+    let mut file_ids = Vec::with_capacity(references.len());
+    for reference in references {
+        match progress.database.resolve_reference(&owner_id, &reference).await? {
+            Some(id) if !id.is_empty() && progress.may_edit(&id).await? => file_ids.push(id),
+            _ => progress.error(format!("{} not found", reference)),
+        }
+    }
+
+    let ancestors = progress.database.ancestor_ids(&owner_id, &destination).await?;
+    if file_ids.iter().any(|id| ancestors.contains(id)) {
+        progress.error("Cannot move a directory into itself".to_string());
+        return Ok(Value::Null);
+    }
+
+    if !progress.start(file_ids.len()).await? {
+        return Ok(Value::Null);
+    }
+
+    let mut moved = 0;
+
+    for batch in file_ids.chunks(JOB_BATCH_SIZE) {
+        match move_entries(progress.database, &owner_id, batch, &destination).await {
+            Ok(_) => moved += batch.len(),
+            Err(err) => progress.error(format!("Failed to move {} entries: {}", batch.len(), err)),
+        }
+
+        if !progress.advance(batch.len()).await? {
+            break;
+        }
+    }
+
+    Ok(json!({ "moved": moved }))
+}
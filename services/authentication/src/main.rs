@@ -1,15 +1,24 @@
 pub mod routes;
 pub mod middleware;
-
+pub mod jobs;
+pub mod outbox;
+pub mod audit;
+pub mod webhooks;
+pub mod changes;
+pub mod access;
+pub mod error;
+
+use actix_web::middleware::from_fn;
 use actix_web::{web, App, HttpServer};
 use sea_orm::{ConnectOptions, Database};
 use std::env;
 use std::time::Duration;
 use env_logger::Env;
-use log::info;
+use log::{error, info};
 use reqwest::Url;
 use webauthn_rs::WebauthnBuilder;
 use migration::{Migrator, MigratorTrait};
+use jobs::JobQueue;
 use storage::s3_manager::S3StorageManager;
 
 pub struct ProviderConfiguration {
@@ -84,10 +93,32 @@ async fn main() -> std::io::Result<()> {
         .test_before_acquire(false);
 
     let database_client = Database::connect(opt).await.unwrap();
+
+    if let Err(err) = migration::verify_extensions(&database_client).await {
+        error!("{}", err);
+        return Err(std::io::Error::other(err.to_string()));
+    }
+
     Migrator::up(&database_client, None).await.unwrap();
 
+    let job_workers = env::var("JOB_WORKERS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(4);
+
     let s3_data = web::Data::new(s3_manager);
     let db_data = web::Data::new(database_client);
+    let job_queue = web::Data::new(JobQueue::default());
+
+    jobs::worker::spawn_workers(
+        job_workers,
+        db_data.get_ref().clone(),
+        s3_data.clone().into_inner(),
+        job_queue.get_ref().clone(),
+    );
+    outbox::dispatcher::spawn_dispatcher(db_data.get_ref().clone(), s3_data.clone().into_inner());
+    webhooks::dispatcher::spawn_dispatcher(db_data.get_ref().clone());
+    let change_feed = web::Data::new(changes::spawn_listener(db_data.get_ref()));
     let provider_data = web::Data::new(provider_configuration);
     let webauth = web::Data::new(builder.build().expect("Failed to build WebAuthn instance"));
 
@@ -99,6 +130,9 @@ async fn main() -> std::io::Result<()> {
             .app_data(db_data.clone())
             .app_data(provider_data.clone())
             .app_data(webauth.clone())
+            .app_data(job_queue.clone())
+            .app_data(change_feed.clone())
+            .wrap(from_fn(error::error_envelope))
             .configure(routes::routes)
             .configure(routes::user::routes)
     })
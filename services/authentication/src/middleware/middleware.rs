@@ -58,6 +58,43 @@ impl FromRequest for AuthenticatedUser {
     }
 }
 
+/// Where a request came from, for the audit log. Requests proxied by the edge carry the
+/// caller's details in `X-Client-IP` and `X-Client-User-Agent`, which are only trusted
+/// alongside a valid origin secret. Anything else is recorded by its peer address alone, as
+/// its headers are whatever the caller chose to send.
+#[derive(Debug, Clone, Default)]
+pub struct ClientContext {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl FromRequest for ClientContext {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+
+        let from_edge = req
+            .app_data::<Data<ProviderConfiguration>>()
+            .is_some_and(|config| header("X-Origin-Secret").as_deref() == Some(config.origin_secret.as_str()));
+
+        let forwarded = |name: &str| if from_edge { header(name) } else { None };
+
+        ready(Ok(ClientContext {
+            ip: forwarded("X-Client-IP")
+                .or_else(|| forwarded("CF-Connecting-IP"))
+                .or_else(|| req.peer_addr().map(|address| address.ip().to_string())),
+            user_agent: forwarded("X-Client-User-Agent").or_else(|| forwarded("User-Agent")),
+        }))
+    }
+}
+
 pub async fn reject_bypassed_traffic(
     req: ServiceRequest,
     next: Next<impl MessageBody>,
@@ -68,12 +105,11 @@ pub async fn reject_bypassed_traffic(
         .origin_secret
         .clone();
 
-    if let Some(header_value) = req.headers().get("X-Origin-Secret") {
-        if let Ok(secret_str) = header_value.to_str() {
-            if secret_str == origin_secret {
-                return next.call(req).await;
-            }
-        }
+    if let Some(header_value) = req.headers().get("X-Origin-Secret")
+        && let Ok(secret_str) = header_value.to_str()
+        && secret_str == origin_secret
+    {
+        return next.call(req).await;
     }
 
     Err(actix_web::error::ErrorForbidden(
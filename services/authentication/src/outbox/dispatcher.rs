@@ -0,0 +1,123 @@
+use crate::outbox::{StorageOperation, STATUS_DEAD};
+use common::entities::prelude::StorageOutbox;
+use common::entities::storage_outbox;
+use migration::Expr;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter, Statement};
+use std::sync::Arc;
+use std::time::Duration;
+use storage::s3_manager::S3StorageManager;
+use storage::s3_scoped_storage::S3ScopedStorage;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Attempts before an entry is dead-lettered, about six hours of retries with the backoff below.
+pub const MAX_ATTEMPTS: i32 = 12;
+
+pub fn spawn_dispatcher(database: DatabaseConnection, storage: Arc<S3StorageManager>) {
+    tokio::spawn(async move {
+        loop {
+            match claim(&database).await {
+                Ok(entries) if !entries.is_empty() => {
+                    for entry in entries {
+                        dispatch(&database, &storage, entry).await;
+                    }
+                    continue;
+                }
+                Ok(_) => {}
+                Err(err) => log::error!("Failed to claim outbox entries: {:?}", err),
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Takes a lease on due entries by pushing their next attempt out. If the process dies
+/// mid-dispatch the entries simply become due again once the lease runs out.
+async fn claim(database: &DatabaseConnection) -> Result<Vec<storage_outbox::Model>, DbErr> {
+    // language=PostgreSQL
+    let sql = r#"
+        UPDATE storage_outbox
+        SET attempts = attempts + 1,
+            next_attempt_at = now() + interval '5 minutes',
+            updated_at = now()
+        WHERE id IN (
+            SELECT id FROM storage_outbox
+            WHERE status = 'pending' AND next_attempt_at <= now()
+            ORDER BY next_attempt_at
+            LIMIT 20
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *;
+    "#;
+
+    StorageOutbox::find()
+        .from_raw_sql(Statement::from_string(DbBackend::Postgres, sql))
+        .all(database)
+        .await
+}
+
+fn backoff(attempts: i32) -> chrono::Duration {
+    chrono::Duration::seconds((15_i64 << attempts.clamp(0, 8)).min(3600))
+}
+
+async fn dispatch(
+    database: &DatabaseConnection,
+    storage_manager: &S3StorageManager,
+    entry: storage_outbox::Model,
+) {
+    let storage = S3ScopedStorage {
+        user_id: entry.owner_id.clone(),
+        bucket: storage_manager.bucket.clone(),
+        client: storage_manager.client.clone(),
+    };
+
+    let outcome = match serde_json::from_value::<StorageOperation>(entry.payload.clone()) {
+        Ok(operation) => operation.apply(&storage).await.map_err(|err| err.to_string()),
+        Err(err) => Err(format!("Unreadable payload: {}", err)),
+    };
+
+    let result = match outcome {
+        Ok(()) => StorageOutbox::delete_by_id(entry.id.clone())
+            .exec(database)
+            .await
+            .map(|_| ()),
+        Err(error) if entry.attempts >= MAX_ATTEMPTS => {
+            log::error!(
+                "Storage operation {} ({}) dead-lettered after {} attempts: {}",
+                entry.id, entry.operation, entry.attempts, error
+            );
+
+            StorageOutbox::update_many()
+                .col_expr(storage_outbox::Column::Status, Expr::value(STATUS_DEAD))
+                .col_expr(storage_outbox::Column::LastError, Expr::value(error))
+                .col_expr(storage_outbox::Column::UpdatedAt, Expr::current_timestamp())
+                .filter(storage_outbox::Column::Id.eq(entry.id.clone()))
+                .exec(database)
+                .await
+                .map(|_| ())
+        }
+        Err(error) => {
+            log::warn!(
+                "Storage operation {} ({}) failed on attempt {}: {}",
+                entry.id, entry.operation, entry.attempts, error
+            );
+
+            let next_attempt_at = DateTimeWithTimeZone::from(chrono::Utc::now() + backoff(entry.attempts));
+
+            StorageOutbox::update_many()
+                .col_expr(storage_outbox::Column::NextAttemptAt, Expr::value(next_attempt_at))
+                .col_expr(storage_outbox::Column::LastError, Expr::value(error))
+                .col_expr(storage_outbox::Column::UpdatedAt, Expr::current_timestamp())
+                .filter(storage_outbox::Column::Id.eq(entry.id.clone()))
+                .exec(database)
+                .await
+                .map(|_| ())
+        }
+    };
+
+    if let Err(err) = result {
+        log::error!("Failed to record the outcome of storage operation {}: {:?}", entry.id, err);
+    }
+}
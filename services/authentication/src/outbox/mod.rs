@@ -0,0 +1,78 @@
+pub mod dispatcher;
+
+use common::entities::storage_outbox;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr, Set};
+use serde::{Deserialize, Serialize};
+use storage::StorageBackend;
+
+pub const STATUS_PENDING: &str = "pending";
+
+/// Entries that ran out of attempts. They stay in the table with their last error until an
+/// operator looks at them through `/internal/outbox/dead` and retries them with
+/// `/internal/outbox/retry`.
+pub const STATUS_DEAD: &str = "dead";
+
+/// Maximum keys in a single S3 batch delete.
+const DELETE_BATCH_SIZE: usize = 1000;
+
+/// Storage work that has to follow a database change. It is written in the same transaction
+/// as the change and applied by the dispatcher once that transaction has committed.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum StorageOperation {
+    DeleteMany { paths: Vec<String> },
+}
+
+impl StorageOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageOperation::DeleteMany { .. } => "delete_many",
+        }
+    }
+
+    pub async fn apply<S: StorageBackend + Sync>(&self, storage: &S) -> anyhow::Result<()> {
+        match self {
+            StorageOperation::DeleteMany { paths } => storage.delete_many(paths.clone()).await,
+        }
+    }
+}
+
+pub async fn enqueue<C: ConnectionTrait>(
+    connection: &C,
+    owner_id: &str,
+    operation: StorageOperation,
+) -> Result<(), DbErr> {
+    let payload = serde_json::to_value(&operation).map_err(|err| DbErr::Custom(err.to_string()))?;
+    let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+    storage_outbox::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        owner_id: Set(owner_id.to_string()),
+        operation: Set(operation.name().to_string()),
+        payload: Set(payload),
+        status: Set(STATUS_PENDING.to_string()),
+        attempts: Set(0),
+        last_error: Set(None),
+        next_attempt_at: Set(now),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Queues the removal of the stored objects, split into batches S3 accepts.
+pub async fn enqueue_delete<C: ConnectionTrait>(
+    connection: &C,
+    owner_id: &str,
+    paths: Vec<String>,
+) -> Result<(), DbErr> {
+    for batch in paths.chunks(DELETE_BATCH_SIZE) {
+        enqueue(connection, owner_id, StorageOperation::DeleteMany { paths: batch.to_vec() }).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::audit_event;
+use common::entities::prelude::AuditEvent;
+use common::types::audit::list::{AuditEventElement, AuditListRequest, AuditListResponse};
+use sea_orm::prelude::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Value};
+
+#[post("list")]
+pub async fn list(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<AuditListRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let limit = payload.limit.unwrap_or(50).min(200) as u64;
+    let offset = payload.offset.unwrap_or(0) as u64;
+
+    let mut query = AuditEvent::find()
+        .filter(audit_event::Column::ActorId.eq(authenticated_user.id.clone()));
+
+    if let Some(action) = &payload.action {
+        query = query.filter(audit_event::Column::Action.eq(action.clone()));
+    }
+
+    if let Some(target_id) = &payload.target_id {
+        query = query.filter(Expr::cust_with_values(
+            "target_ids @> jsonb_build_array($1::text)",
+            [Value::from(target_id.clone())],
+        ));
+    }
+
+    if let Some(from) = payload.from {
+        query = query.filter(audit_event::Column::CreatedAt.gte(from));
+    }
+
+    if let Some(to) = payload.to {
+        query = query.filter(audit_event::Column::CreatedAt.lt(to));
+    }
+
+    let mut events = match query
+        .order_by_desc(audit_event::Column::Id)
+        .limit(limit + 1)
+        .offset(offset)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(events) => events,
+        Err(err) => {
+            log::error!("Error fetching audit events: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let has_more = events.len() as u64 > limit;
+    if has_more { events.pop(); }
+
+    HttpResponse::Ok().json(AuditListResponse {
+        events: events
+            .into_iter()
+            .map(|event| AuditEventElement {
+                id: event.id,
+                action: event.action,
+                target_ids: serde_json::from_value(event.target_ids).unwrap_or_default(),
+                before: event.before,
+                after: event.after,
+                ip: event.ip,
+                user_agent: event.user_agent,
+                created_at: event.created_at,
+            })
+            .collect(),
+        has_more,
+    })
+}
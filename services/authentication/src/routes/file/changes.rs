@@ -0,0 +1,29 @@
+use crate::changes::changes_after;
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::types::file::changes::{ChangesRequest, ChangesResponse};
+use sea_orm::DatabaseConnection;
+
+/// Everything that happened to the caller's files after `cursor`, oldest first. Clients mirror
+/// the tree by applying the changes in order and storing the returned cursor.
+#[post("changes")]
+pub async fn changes(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ChangesRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let limit = payload.limit.unwrap_or(500).clamp(1, 1000) as u64;
+    let cursor = payload.cursor.unwrap_or(0);
+
+    match changes_after(&database, &authenticated_user.id, cursor, limit).await {
+        Ok((changes, cursor, has_more)) => HttpResponse::Ok().json(ChangesResponse {
+            changes,
+            cursor,
+            has_more,
+        }),
+        Err(err) => {
+            log::error!("Failed to fetch file changes: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -1,62 +1,329 @@
 use actix_web::{post, web, HttpResponse, Responder};
-use common::entities::file;
-use common::entities::prelude::File;
+use common::entities::{file, job_copy};
+use common::entities::prelude::{File, JobCopy};
 use futures::stream;
 use futures::StreamExt;
-use sea_orm::QueryFilter;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait};
-use common::types::file::copy::CopyFilesRequest;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter, Set, Statement, TransactionTrait};
+use common::types::file::copy::{CopyFilesRequest, CopyFilesResponse};
+use common::types::file::job::JobOperation;
+use serde_json::json;
+use std::collections::HashMap;
 use storage::s3_manager::S3StorageManager;
 use storage::s3_scoped_storage::S3ScopedStorage;
 use storage::StorageBackend;
-use crate::middleware::middleware::AuthenticatedUser;
+use crate::jobs::{job_response, JobQueue, JOB_THRESHOLD};
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::error::internal_error;
+use crate::outbox::enqueue_delete;
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::routes::file::tree::{contribution, FileTreeExtension};
 
 #[post("copy")]
 pub async fn copy(
     database: web::Data<DatabaseConnection>,
     s3storage_manager: web::Data<S3StorageManager>,
+    queue: web::Data<JobQueue>,
     payload: web::Json<CopyFilesRequest>,
-    authenticated_user: AuthenticatedUser
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> impl Responder {
-    let files = match File::find()
-        .filter(file::Column::Id.is_in(payload.file_ids.clone()))
-        .all(database.get_ref())
-        .await
-    {
+    match authorize(database.get_ref(), &authenticated_user.id, &payload.destination_path, Role::Editor).await {
+        Ok(access) if access.owner_id == authenticated_user.id => {}
+        Ok(_) => return HttpResponse::BadRequest().body("Entries can't be copied into another user's files"),
+        Err(response) => return response,
+    }
+
+    match copy_destination(database.get_ref(), &authenticated_user.id, &payload.destination_path).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().body("The destination is not a directory"),
+        Err(err) => return internal_error("Failed to check the copy destination", err),
+    }
+
+    let files = match fetch_tree(&database, &authenticated_user.id, &payload.file_ids).await {
         Ok(files) => files,
-        Err(err) => {
-            return HttpResponse::InternalServerError()
-                .body(format!("Failed to fetch the files: {:?}", err));
-        }
+        Err(err) => return internal_error("Failed to fetch the files", err),
     };
 
+    if files.len() > JOB_THRESHOLD {
+        let operation = JobOperation::Copy(payload.clone());
+        return match queue.submit(&database, &authenticated_user.id, &authenticated_user.id, &operation).await {
+            Ok(job) => {
+                record_logged(
+                    database.get_ref(),
+                    &authenticated_user.id,
+                    &client,
+                    AuditEvent::new("file.copy", payload.file_ids.clone()).after(json!({
+                        "destination": payload.destination_path,
+                        "job_id": job.id,
+                    })),
+                )
+                .await;
+
+                HttpResponse::Accepted().json(job_response(job))
+            }
+            Err(err) => internal_error("Failed to queue the copy", err),
+        };
+    }
+
     let s3_manager = S3ScopedStorage {
         user_id: authenticated_user.id.clone(),
         bucket: s3storage_manager.bucket.clone(),
         client: s3storage_manager.client.clone(),
     };
 
-    let results: Vec<_> = stream::iter(files)
-        .map(|file| {
-            let s3_manager = s3_manager.clone();
-            let payload = payload.clone();
-            
+    match copy_tree(
+        &database,
+        &s3_manager,
+        &authenticated_user.id,
+        &payload.file_ids,
+        &payload.destination_path,
+        &files,
+        None,
+    )
+    .await
+    {
+        Ok(file_ids) if file_ids.is_empty() => HttpResponse::NotFound().finish(),
+        Ok(file_ids) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("file.copy", payload.file_ids.clone()).after(json!({
+                    "destination": payload.destination_path,
+                    "file_ids": file_ids,
+                })),
+            )
+            .await;
+
+            HttpResponse::Ok().json(CopyFilesResponse { file_ids })
+        }
+        Err(err) => internal_error("Failed to copy the files", err),
+    }
+}
+
+/// Whether copies can go to `destination`: the owner's root or one of their directories.
+/// Copies are written under the owner's storage prefix, so another user's folder is never a
+/// destination, not even with an editor grant.
+pub async fn copy_destination<C: ConnectionTrait>(
+    connection: &C,
+    owner_id: &str,
+    destination: &str,
+) -> Result<bool, DbErr> {
+    if destination.is_empty() {
+        return Ok(true);
+    }
+
+    let directory = File::find_by_id(destination)
+        .filter(file::Column::OwnerId.eq(owner_id))
+        .filter(file::Column::IsDirectory.eq(true))
+        .one(connection)
+        .await?;
+
+    Ok(directory.is_some())
+}
+
+/// The selected entries together with everything below them, parents before children.
+pub async fn fetch_tree(
+    database: &DatabaseConnection,
+    owner_id: &str,
+    file_ids: &[String],
+) -> Result<Vec<file::Model>, DbErr> {
+    // language=PostgreSQL
+    let query = r#"
+        WITH RECURSIVE tree AS (
+            SELECT id, 0 AS depth FROM "file" WHERE id = ANY($1) AND owner_id = $2
+            UNION ALL
+            SELECT f.id, t.depth + 1 FROM "file" f
+            INNER JOIN tree t ON f.path = t.id
+            WHERE f.owner_id = $2
+        )
+        SELECT f.id, f.file_name, f.owner_id, f.file_size, f.created_at, f.upload_completed,
+               f.file_type, f.path, f.is_directory, f.item_count
+        FROM tree t
+        INNER JOIN "file" f ON f.id = t.id
+        ORDER BY t.depth;
+    "#;
+
+    File::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            query,
+            [file_ids.to_vec().into(), owner_id.into()],
+        ))
+        .all(database)
+        .await
+}
+
+/// The selected entries that don't already sit inside another selected directory. Nested
+/// selections are copied along with that directory instead of a second time.
+pub fn top_level(files: &[file::Model], selected: &[String]) -> Vec<String> {
+    let parents: HashMap<&str, &str> = files
+        .iter()
+        .map(|file| (file.id.as_str(), file.path.as_str()))
+        .collect();
+
+    let is_nested = |id: &str| {
+        let mut current = parents.get(id).copied();
+        while let Some(parent) = current {
+            if selected.iter().any(|selected| selected == parent) {
+                return true;
+            }
+            current = parents.get(parent).copied();
+        }
+        false
+    };
+
+    selected
+        .iter()
+        .filter(|id| parents.contains_key(id.as_str()) && !is_nested(id))
+        .cloned()
+        .collect()
+}
+
+/// Copies a tree fetched by [`fetch_tree`] into `destination` and returns the new ids of the
+/// top-level copies. Objects are copied before any rows are written; when a copy or the rows
+/// fail, the objects already copied are queued for removal. The caller checks `destination`
+/// with [`copy_destination`]. Copy jobs pass their id to have the top-level copies recorded in
+/// `job_copy` along with the rows.
+pub async fn copy_tree(
+    database: &DatabaseConnection,
+    storage: &S3ScopedStorage,
+    owner_id: &str,
+    selected: &[String],
+    destination: &str,
+    files: &[file::Model],
+    job_id: Option<&str>,
+) -> anyhow::Result<Vec<String>> {
+    let top_level = top_level(files, selected);
+
+    // Rows are ordered parents first, so every child can look up its parent's new id.
+    let mut new_ids: HashMap<String, String> = HashMap::new();
+    let mut copies = Vec::new();
+    let mut roots = Vec::new();
+
+    for file in files.iter().filter(|f| f.is_directory || f.upload_completed) {
+        if new_ids.contains_key(&file.id) {
+            continue;
+        }
+
+        let is_root = top_level.contains(&file.id);
+
+        let path = if is_root {
+            destination.to_string()
+        } else {
+            match new_ids.get(&file.path) {
+                Some(parent) => parent.clone(),
+                None => continue,
+            }
+        };
+
+        let new_id = uuid::Uuid::new_v4().to_string();
+        new_ids.insert(file.id.clone(), new_id.clone());
+
+        if is_root {
+            roots.push((contribution(file), file.id.clone(), new_id.clone()));
+        }
+
+        copies.push((file, new_id, path));
+    }
+
+    if copies.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let objects = copies
+        .iter()
+        .filter(|(file, _, _)| !file.is_directory)
+        .map(|(file, new_id, _)| (file.id.clone(), new_id.clone()))
+        .collect::<Vec<_>>();
+
+    let results: Vec<_> = stream::iter(objects)
+        .map(|(old_id, new_id)| {
+            let storage = storage.clone();
+
             async move {
-                s3_manager.copy_object(&file.path, &payload.destination_path).await
+                storage.copy_object(&old_id, &new_id).await.map(|_| new_id)
             }
         })
         .buffer_unordered(10)
         .collect()
         .await;
 
-    for file in results {
-        match file {
-            Ok(_) => {}
-            Err(err) => {
-                return HttpResponse::InternalServerError().body(format!("Failed to copy one or more files: {:?}", err));
-            }
+    let mut copied = Vec::with_capacity(results.len());
+    let mut failure = None;
+
+    for result in results {
+        match result {
+            Ok(new_id) => copied.push(new_id),
+            Err(err) => failure = failure.or(Some(err)),
         }
     }
-    
-    HttpResponse::Ok().finish()
+
+    if let Some(err) = failure {
+        discard_copies(database, owner_id, copied).await;
+        return Err(err);
+    }
+
+    let inserts = copies
+        .iter()
+        .map(|(file, new_id, path)| file::ActiveModel {
+            id: Set(new_id.clone()),
+            file_name: Set(file.file_name.clone()),
+            owner_id: Set(owner_id.to_string()),
+            file_size: Set(file.file_size),
+            created_at: Set(DateTimeWithTimeZone::from(chrono::Utc::now())),
+            upload_completed: Set(file.upload_completed),
+            file_type: Set(file.file_type.clone()),
+            path: Set(path.clone()),
+            is_directory: Set(file.is_directory),
+            item_count: Set(file.item_count),
+        })
+        .collect::<Vec<_>>();
+
+    let (size, count) = roots
+        .iter()
+        .fold((0, 0), |(size, count), ((s, c), _, _)| (size + s, count + c));
+
+    let written: Result<(), DbErr> = async {
+        let transaction = database.begin().await?;
+
+        File::insert_many(inserts).exec(&transaction).await?;
+        transaction
+            .adjust_ancestors(owner_id, destination, size, count)
+            .await?;
+
+        if let Some(job_id) = job_id {
+            JobCopy::insert_many(roots.iter().map(|(_, source_id, new_id)| job_copy::ActiveModel {
+                job_id: Set(job_id.to_string()),
+                source_id: Set(source_id.clone()),
+                new_id: Set(new_id.clone()),
+            }))
+            .exec(&transaction)
+            .await?;
+        }
+
+        transaction.commit().await
+    }
+    .await;
+
+    if let Err(err) = written {
+        discard_copies(database, owner_id, copied).await;
+        return Err(err.into());
+    }
+
+    Ok(roots.into_iter().map(|(_, _, new_id)| new_id).collect())
+}
+
+/// Queues the removal of objects copied for rows that were never written.
+async fn discard_copies(database: &DatabaseConnection, owner_id: &str, copied: Vec<String>) {
+    if copied.is_empty() {
+        return;
+    }
+
+    let count = copied.len();
+    if let Err(err) = enqueue_delete(database, owner_id, copied).await {
+        log::error!("Failed to queue the removal of {} unused copies for {}: {:?}", count, owner_id, err);
+    }
 }
@@ -3,54 +3,146 @@ use common::entities::file;
 use common::entities::prelude::File;
 use sea_orm::ColumnTrait;
 use sea_orm::QueryFilter;
-use sea_orm::{DatabaseConnection, EntityTrait};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, TransactionTrait};
+use serde_json::{json, Value};
 use common::types::file::delete::DeleteFilesRequest;
-use storage::s3_manager::S3StorageManager;
-use storage::s3_scoped_storage::S3ScopedStorage;
-use storage::StorageBackend;
-use crate::middleware::middleware::AuthenticatedUser;
+use common::types::file::job::JobOperation;
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::jobs::{job_response, JobQueue, JOB_THRESHOLD};
+use crate::outbox::enqueue_delete;
+use crate::routes::file::tree::{contribution, FileTreeExtension};
+use crate::webhooks;
+use common::types::webhook::event::WebhookEvent;
 
 #[delete("delete")]
 pub async fn delete(
     database: web::Data<DatabaseConnection>,
-    s3_manager: web::Data<S3StorageManager>,
+    queue: web::Data<JobQueue>,
     payload: web::Json<DeleteFilesRequest>,
-    authenticated_user: AuthenticatedUser
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> impl Responder {
     let file_ids = payload.into_inner().file_ids;
 
-    let delete_result = File::delete_many()
-        .filter(file::Column::Id.is_in(file_ids.clone()))
-        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
-        .exec(database.get_ref())
-        .await;
+    let mut owner_id = authenticated_user.id.clone();
+    for (index, file_id) in file_ids.iter().enumerate() {
+        match authorize(database.get_ref(), &authenticated_user.id, file_id, Role::Editor).await {
+            Ok(access) if index == 0 => owner_id = access.owner_id,
+            Ok(access) if access.owner_id == owner_id => {}
+            Ok(_) => return HttpResponse::BadRequest().body("Entries of different owners can't be deleted together"),
+            Err(response) => return response,
+        }
+    }
+
+    if file_ids.len() > JOB_THRESHOLD {
+        let operation = JobOperation::Delete(DeleteFilesRequest { file_ids: file_ids.clone() });
+        return match queue.submit(&database, &owner_id, &authenticated_user.id, &operation).await {
+            Ok(job) => {
+                record_logged(
+                    database.get_ref(),
+                    &authenticated_user.id,
+                    &client,
+                    AuditEvent::new("file.delete", file_ids).after(json!({ "job_id": job.id })),
+                )
+                .await;
 
-    let result = match delete_result {
-        Ok(res) => res,
+                HttpResponse::Accepted().json(job_response(job))
+            }
+            Err(err) => {
+                log::error!("Failed to queue the deletion: {}", err);
+                HttpResponse::InternalServerError().body("Failed to delete files.")
+            }
+        };
+    }
+
+    let delete_result = delete_entries(&database, &owner_id, &file_ids).await;
+
+    let deleted = match delete_result {
+        Ok(deleted) => deleted,
         Err(err) => {
             log::error!("Database deletion failed: {}", err);
             return HttpResponse::InternalServerError().body("Failed to delete files.");
         }
     };
 
-    if result.rows_affected == 0 {
+    if deleted.is_empty() {
         return HttpResponse::NotFound().finish();
     }
 
-    let storage = S3ScopedStorage {
-        user_id: authenticated_user.id.clone(),
-        bucket: s3_manager.bucket.clone(),
-        client: s3_manager.client.clone(),
-    };
-
-    tokio::spawn(
-        async move {
-            match storage.delete_many(file_ids.clone()).await {
-                Ok(_) => log::info!("Successfully deleted files from S3: {:?}", file_ids),
-                Err(err) => log::error!("Failed to delete files from S3: {}", err),
-            }
-        }
-    );
+    record_logged(
+        database.get_ref(),
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("file.delete", deleted.iter().map(|entry| entry.id.clone()).collect())
+            .before(Value::from_iter(deleted.iter().map(|entry| json!({
+                "id": entry.id,
+                "file_name": entry.file_name,
+                "path": entry.path,
+            })))),
+    )
+    .await;
 
     HttpResponse::Ok().finish()
-}
\ No newline at end of file
+}
+
+/// Removes the rows and takes their totals off every directory above them. Directories
+/// are expected to be passed along with their contents. The stored objects are queued for
+/// removal in the same transaction. Returns the rows as they were before deletion.
+pub async fn delete_entries(
+    database: &DatabaseConnection,
+    owner_id: &str,
+    file_ids: &[String],
+) -> Result<Vec<file::Model>, DbErr> {
+    let transaction = database.begin().await?;
+
+    let deleted = File::find()
+        .filter(file::Column::Id.is_in(file_ids.to_vec()))
+        .filter(file::Column::OwnerId.eq(owner_id))
+        .all(&transaction)
+        .await?;
+
+    if !deleted.is_empty() {
+        let ids = deleted.iter().map(|entry| entry.id.clone()).collect::<Vec<_>>();
+        webhooks::emit(
+            &transaction,
+            owner_id,
+            WebhookEvent::Deleted,
+            &ids,
+            json!({
+                "files": deleted.iter().map(|entry| json!({
+                    "id": entry.id,
+                    "file_name": entry.file_name,
+                    "path": entry.path,
+                    "is_directory": entry.is_directory,
+                })).collect::<Vec<_>>(),
+            }),
+        )
+        .await?;
+    }
+
+    File::delete_many()
+        .filter(file::Column::Id.is_in(file_ids.to_vec()))
+        .filter(file::Column::OwnerId.eq(owner_id))
+        .exec(&transaction)
+        .await?;
+
+    for entry in &deleted {
+        let (size, count) = contribution(entry);
+        transaction
+            .adjust_ancestors(owner_id, &entry.path, -size, -count)
+            .await?;
+    }
+
+    let objects = deleted
+        .iter()
+        .filter(|entry| !entry.is_directory)
+        .map(|entry| entry.id.clone())
+        .collect::<Vec<_>>();
+    enqueue_delete(&transaction, owner_id, objects).await?;
+
+    transaction.commit().await?;
+
+    Ok(deleted)
+}
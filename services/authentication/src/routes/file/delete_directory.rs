@@ -1,40 +1,33 @@
-use crate::middleware::middleware::AuthenticatedUser;
+use crate::access::{authorize, Role};
+use crate::audit::{record, record_logged, AuditEvent};
+use crate::jobs::{job_response, JobQueue, JOB_THRESHOLD};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::outbox::enqueue_delete;
+use crate::routes::file::tree::{contribution, FileTreeExtension};
+use crate::webhooks;
 use actix_web::{delete, web, HttpResponse, Responder};
 use common::entities::file;
 use common::entities::prelude::File;
 use common::types::file::directory_delete::DeleteDirectoryRequest;
-use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Statement};
-use storage::s3_manager::S3StorageManager;
-use storage::s3_scoped_storage::S3ScopedStorage;
-use storage::StorageBackend;
+use common::types::file::job::JobOperation;
+use common::types::webhook::event::WebhookEvent;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Statement, TransactionTrait};
+use serde_json::json;
 
 #[delete("delete")]
 pub async fn delete(
     database: web::Data<DatabaseConnection>,
-    s3_manager: web::Data<S3StorageManager>,
+    queue: web::Data<JobQueue>,
     payload: web::Json<DeleteDirectoryRequest>,
     authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> impl Responder {
-    let backend = database.get_database_backend();
-    let sql = r#"
-        WITH RECURSIVE subordinates AS (
-            SELECT id, is_directory FROM file WHERE id = $1 AND owner_id = $2
-            UNION ALL
-            SELECT f.id, f.is_directory FROM file f
-            INNER JOIN subordinates s ON f.path = s.id
-            WHERE f.owner_id = $2
-        )
-        SELECT id, is_directory FROM subordinates;
-    "#;
+    let owner_id = match authorize(database.get_ref(), &authenticated_user.id, &payload.directory_id, Role::Editor).await {
+        Ok(access) => access.owner_id,
+        Err(response) => return response,
+    };
 
-    let rows = match database
-        .query_all_raw(Statement::from_sql_and_values(
-            backend,
-            sql,
-            [payload.directory_id.clone().into(), authenticated_user.id.clone().into()],
-        ))
-        .await
-    {
+    let rows = match subtree(&database, &owner_id, &payload.directory_id).await {
         Ok(rows) => rows,
         Err(e) => {
             log::error!("Recursive fetch error: {:?}", e);
@@ -42,48 +35,145 @@ pub async fn delete(
         }
     };
 
+    if rows.is_empty() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    if rows.len() > JOB_THRESHOLD {
+        let directory_id = payload.directory_id.clone();
+        let operation = JobOperation::DeleteDirectory(payload.into_inner());
+        return match queue.submit(&database, &owner_id, &authenticated_user.id, &operation).await {
+            Ok(job) => {
+                record_logged(
+                    database.get_ref(),
+                    &authenticated_user.id,
+                    &client,
+                    AuditEvent::new("directory.delete", vec![directory_id])
+                        .after(json!({ "job_id": job.id })),
+                )
+                .await;
+
+                HttpResponse::Accepted().json(job_response(job))
+            }
+            Err(e) => {
+                log::error!("Failed to queue the directory deletion: {:?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        };
+    }
+
     let mut all_ids = Vec::new();
     let mut file_ids_for_s3 = Vec::new();
 
-    for row in rows {
-        let id = row.try_get::<String>("", "id").unwrap_or_default();
-        let is_dir = row.try_get::<bool>("", "is_directory").unwrap_or_default();
-
+    for (id, is_dir) in rows {
         all_ids.push(id.clone());
         if !is_dir {
             file_ids_for_s3.push(id);
         }
     }
 
-    if all_ids.is_empty() {
-        return HttpResponse::NotFound().finish();
-    }
+    let delete_result: Result<(), DbErr> = async {
+        let transaction = database.begin().await?;
 
-    let storage = S3ScopedStorage {
-        user_id: authenticated_user.id.clone(),
-        bucket: s3_manager.bucket.clone(),
-        client: s3_manager.client.clone(),
-    };
+        let directory = File::find()
+            .filter(file::Column::Id.eq(payload.directory_id.clone()))
+            .filter(file::Column::OwnerId.eq(owner_id.clone()))
+            .one(&transaction)
+            .await?;
 
-    if !file_ids_for_s3.is_empty() {
-        tokio::spawn(
-            async move {
-                if let Err(e) = storage.delete_many(file_ids_for_s3.clone()).await {
-                    log::error!("S3 delete error: {:?}", e);
-                }
-            }
-        );
+        if let Some(directory) = &directory {
+            webhooks::emit(
+                &transaction,
+                &owner_id,
+                WebhookEvent::Deleted,
+                &all_ids,
+                json!({
+                    "files": [{
+                        "id": directory.id,
+                        "file_name": directory.file_name,
+                        "path": directory.path,
+                        "is_directory": true,
+                    }],
+                    "file_ids": all_ids,
+                }),
+            )
+            .await?;
+        }
+
+        File::delete_many()
+            .filter(file::Column::Id.is_in(all_ids))
+            .exec(&transaction)
+            .await?;
+
+        if let Some(directory) = directory {
+            let (size, count) = contribution(&directory);
+            transaction
+                .adjust_ancestors(&owner_id, &directory.path, -size, -count)
+                .await?;
+
+            record(
+                &transaction,
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("directory.delete", vec![directory.id.clone()]).before(json!({
+                    "file_name": directory.file_name,
+                    "path": directory.path,
+                    "file_size": directory.file_size,
+                    "item_count": directory.item_count,
+                })),
+            )
+            .await?;
+        }
+
+        enqueue_delete(&transaction, &owner_id, file_ids_for_s3).await?;
+
+        transaction.commit().await
     }
+    .await;
 
-    match File::delete_many()
-        .filter(file::Column::Id.is_in(all_ids))
-        .exec(database.get_ref())
-        .await
-    {
+    match delete_result {
         Ok(_) => HttpResponse::Ok().finish(),
         Err(e) => {
             log::error!("Database delete error: {:?}", e);
             HttpResponse::InternalServerError().finish()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Every entry below and including the directory as `(id, is_directory)`, deepest first so
+/// the list can be deleted in batches without a parent outliving its children.
+pub async fn subtree(
+    database: &DatabaseConnection,
+    owner_id: &str,
+    directory_id: &str,
+) -> Result<Vec<(String, bool)>, DbErr> {
+    // language=PostgreSQL
+    let sql = r#"
+        WITH RECURSIVE subordinates AS (
+            SELECT id, is_directory, 0 AS depth FROM file WHERE id = $1 AND owner_id = $2
+            UNION ALL
+            SELECT f.id, f.is_directory, s.depth + 1 FROM file f
+            INNER JOIN subordinates s ON f.path = s.id
+            WHERE f.owner_id = $2
+        )
+        SELECT id, is_directory FROM subordinates ORDER BY depth DESC;
+    "#;
+
+    let rows = database
+        .query_all_raw(Statement::from_sql_and_values(
+            database.get_database_backend(),
+            sql,
+            [directory_id.into(), owner_id.into()],
+        ))
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.try_get::<String>("", "id").unwrap_or_default(),
+                row.try_get::<bool>("", "is_directory").unwrap_or_default(),
+            )
+        })
+        .collect())
+}
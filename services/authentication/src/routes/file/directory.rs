@@ -1,10 +1,14 @@
-use crate::middleware::middleware::AuthenticatedUser;
+use crate::audit::{record, AuditEvent};
+use crate::error::internal_error;
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::routes::file::tree::FileTreeExtension;
 use actix_web::{post, web, HttpResponse};
 use common::entities::file;
 use common::entities::prelude::File;
 use common::types::file::directory::{DirectoryRequest, DirectoryResponse};
 use sea_orm::prelude::DateTimeWithTimeZone;
-use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, Set, TransactionTrait};
+use serde_json::json;
 use storage::s3_manager::S3StorageManager;
 
 #[post("create")]
@@ -13,14 +17,20 @@ pub async fn directory(
     _s3_client: web::Data<S3StorageManager>,
     payload: web::Json<DirectoryRequest>,
     authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> HttpResponse {
-    let directories = payload.name.split("/").collect::<Vec<&str>>();
+    let directories = payload
+        .name
+        .split("/")
+        .filter(|dir| !dir.is_empty())
+        .collect::<Vec<&str>>();
 
     let mut inserts = Vec::new();
+    let mut created_ids = Vec::new();
     let mut current_id = String::from("");
     let mut base_path = payload.path.clone();
 
-    if directories.len() == 0 {
+    if directories.is_empty() {
         return HttpResponse::BadRequest().finish();
     }
 
@@ -32,36 +42,56 @@ pub async fn directory(
             .body("Directory names must be 255 characters or less".to_string());
     }
 
-    directories.iter().for_each(|dir| {
-        if !dir.is_empty() {
-            let id = uuid::Uuid::new_v4().to_string();
-
-            let insert = file::ActiveModel {
-                id: Set(id.clone()),
-                file_name: Set(dir.to_string()),
-                owner_id: Set(authenticated_user.id.clone()),
-                created_at: Set(DateTimeWithTimeZone::from(chrono::Utc::now())),
-                upload_completed: Set(true),
-                file_type: Set("directory".to_string()),
-                file_size: Set(0),
-                path: Set(base_path.clone()),
-                is_directory: Set(true),
-            };
-
-            inserts.push(insert);
-
-            base_path = id.clone();
-            current_id = id;
-        }
+    let parent_id = payload.path.clone();
+    let created = directories.len() as i64;
+
+    // Each new directory contains the ones nested below it in the same request.
+    directories.iter().enumerate().for_each(|(index, dir)| {
+        let id = uuid::Uuid::new_v4().to_string();
+
+        let insert = file::ActiveModel {
+            id: Set(id.clone()),
+            file_name: Set(dir.to_string()),
+            owner_id: Set(authenticated_user.id.clone()),
+            created_at: Set(DateTimeWithTimeZone::from(chrono::Utc::now())),
+            upload_completed: Set(true),
+            file_type: Set("directory".to_string()),
+            file_size: Set(0),
+            path: Set(base_path.clone()),
+            is_directory: Set(true),
+            item_count: Set(created - index as i64 - 1),
+        };
+
+        inserts.push(insert);
+        created_ids.push(id.clone());
+
+        base_path = id.clone();
+        current_id = id;
     });
 
-    let insert = File::insert_many(inserts).exec(database.get_ref()).await;
+    let insert: Result<(), DbErr> = async {
+        let transaction = database.begin().await?;
+
+        File::insert_many(inserts).exec(&transaction).await?;
+        transaction
+            .adjust_ancestors(&authenticated_user.id, &parent_id, 0, created)
+            .await?;
+
+        record(
+            &transaction,
+            &authenticated_user.id,
+            &client,
+            AuditEvent::new("directory.create", created_ids)
+                .after(json!({ "name": payload.name, "path": parent_id })),
+        )
+        .await?;
+
+        transaction.commit().await
+    }
+    .await;
 
-    if insert.is_err() {
-        return HttpResponse::InternalServerError().body(format!(
-            "Failed to create file record: {}",
-            insert.err().unwrap()
-        ));
+    if let Err(err) = insert {
+        return internal_error("Failed to create the directory", err);
     }
 
     let response = DirectoryResponse {
@@ -0,0 +1,106 @@
+use crate::changes::{changes_after, ChangeFeed};
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::web::Bytes;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use common::types::file::changes::{ChangeElement, ChangesRequest};
+use futures::stream;
+use sea_orm::DatabaseConnection;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Also how often a quiet stream looks for changes it may have missed a notification for.
+const HEARTBEAT: Duration = Duration::from_secs(25);
+
+const PAGE_SIZE: u64 = 200;
+
+struct EventStream {
+    database: web::Data<DatabaseConnection>,
+    owner_id: String,
+    cursor: i64,
+    receiver: broadcast::Receiver<String>,
+    buffered: VecDeque<Bytes>,
+}
+
+/// Server-Sent Events stream of the caller's file changes. Each event carries its sequence as
+/// the event id, so a reconnecting `EventSource` resumes through `Last-Event-ID` without
+/// missing anything. `?cursor=` does the same for clients that track it themselves.
+#[get("events")]
+pub async fn events(
+    request: HttpRequest,
+    database: web::Data<DatabaseConnection>,
+    feed: web::Data<ChangeFeed>,
+    query: web::Query<ChangesRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> HttpResponse {
+    let last_event_id = request
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i64>().ok());
+
+    let state = EventStream {
+        database,
+        owner_id: authenticated_user.id,
+        cursor: last_event_id.or(query.cursor).unwrap_or(0),
+        // Subscribed before the first read, so nothing committed in between goes unnoticed.
+        receiver: feed.subscribe(),
+        buffered: VecDeque::from([Bytes::from_static(b"retry: 3000\n\n")]),
+    };
+
+    let body = stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(chunk) = state.buffered.pop_front() {
+                return Some((Ok::<_, actix_web::Error>(chunk), state));
+            }
+
+            match changes_after(&state.database, &state.owner_id, state.cursor, PAGE_SIZE).await {
+                Ok((changes, cursor, _)) if !changes.is_empty() => {
+                    state.cursor = cursor;
+                    state.buffered.extend(changes.iter().map(frame));
+                    continue;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    // Ending the stream makes the client reconnect from its last event.
+                    log::error!("Failed to read changes for the event stream: {:?}", err);
+                    return None;
+                }
+            }
+
+            match tokio::time::timeout(HEARTBEAT, changed(&mut state.receiver, &state.owner_id)).await {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(_) => state.buffered.push_back(Bytes::from_static(b": keep-alive\n\n")),
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body)
+}
+
+/// Waits until the owner has something new. A lagging receiver may have skipped the owner's
+/// notification, so that counts as a change too.
+async fn changed(receiver: &mut broadcast::Receiver<String>, owner_id: &str) -> bool {
+    loop {
+        match receiver.recv().await {
+            Ok(owner) if owner == owner_id => return true,
+            Ok(_) => {}
+            Err(RecvError::Lagged(_)) => return true,
+            Err(RecvError::Closed) => return false,
+        }
+    }
+}
+
+fn frame(change: &ChangeElement) -> Bytes {
+    Bytes::from(format!(
+        "id: {}\nevent: change\ndata: {}\n\n",
+        change.sequence,
+        serde_json::to_string(change).unwrap_or_default(),
+    ))
+}
@@ -1,10 +1,11 @@
+use crate::access::{authorize, Role};
+use crate::error::internal_error;
 use crate::middleware::middleware::AuthenticatedUser;
 use actix_web::{HttpResponse, post, web};
 use aws_sdk_s3::presigning::PresigningConfig;
 use common::types::file::explode::{ZipRequest, ExplodeResponse, ExplodedItem, PresignedExplodedItem};
 use sea_orm::{DatabaseConnection, DbBackend, FromQueryResult, Statement};
 use storage::s3_manager::S3StorageManager;
-use storage::s3_scoped_storage::S3ScopedStorage;
 
 #[post("/explode")]
 pub async fn explode(
@@ -13,8 +14,30 @@ pub async fn explode(
     req: web::Json<ZipRequest>,
     authenticated_user: AuthenticatedUser,
 ) -> HttpResponse {
-    let database = database.get_ref();
+    // Shared entries are listed under their owner, whose objects they are.
+    let mut owner_id = authenticated_user.id.clone();
+    for (index, item_id) in req.item_ids.iter().enumerate() {
+        match authorize(database.get_ref(), &authenticated_user.id, item_id, Role::Viewer).await {
+            Ok(access) if index == 0 => owner_id = access.owner_id,
+            Ok(access) if access.owner_id == owner_id => {}
+            Ok(_) => return HttpResponse::BadRequest().body("Entries of different owners can't be zipped together"),
+            Err(response) => return response,
+        }
+    }
+
+    match explode_items(&database, &s3_client, &owner_id, &req.item_ids).await {
+        Ok(explode_response) => HttpResponse::Ok().json(explode_response),
+        Err(err) => internal_error("Failed to explode the entries", err),
+    }
+}
 
+/// Every file below the given entries with a presigned URL and its path relative to them.
+pub async fn explode_items(
+    database: &DatabaseConnection,
+    s3_client: &S3StorageManager,
+    owner_id: &str,
+    item_ids: &[String],
+) -> anyhow::Result<ExplodeResponse> {
     // language=PostgreSQL
     let query = r#"
     WITH RECURSIVE tree AS (
@@ -24,6 +47,7 @@ pub async fn explode(
             is_directory,
             path,
             file_size,
+            file_type,
             created_at,
             file_name::text as virtual_path
         FROM "file"
@@ -37,67 +61,49 @@ pub async fn explode(
             i.is_directory,
             i.path,
             i.file_size,
+            i.file_type,
             i.created_at,
             t.virtual_path || '/' || i.file_name
         FROM "file" i
         INNER JOIN tree t ON i.path = t.id
         WHERE i.owner_id = $2
     )
-    SELECT id, is_directory, file_name, file_size, created_at, virtual_path FROM tree WHERE is_directory = false;
+    SELECT id, is_directory, file_name, file_size, file_type, created_at, virtual_path FROM tree WHERE is_directory = false;
 "#;
 
-    let exploded_items: Vec<ExplodedItem> = match ExplodedItem::find_by_statement(
-        Statement::from_sql_and_values(DbBackend::Postgres, query, [req.item_ids.clone().into(), authenticated_user.id.clone().into()])
+    let exploded_items: Vec<ExplodedItem> = ExplodedItem::find_by_statement(
+        Statement::from_sql_and_values(DbBackend::Postgres, query, [item_ids.to_vec().into(), owner_id.into()])
     )
     .all(database)
-    .await
-    {
-        Ok(items) => items,
-        Err(err) => {
-            return HttpResponse::InternalServerError()
-                .body(format!("Failed to execute query: {}", err.to_string()));
-        }
-    };
-
-    let storage = S3ScopedStorage {
-        user_id: authenticated_user.id.clone(),
-        bucket: s3_client.bucket.clone(),
-        client: s3_client.client.clone(),
-    };
+    .await?;
 
     let mut presigned_urls = Vec::new();
 
     let presign_config = PresigningConfig::builder()
         .expires_in(std::time::Duration::from_mins(30))
-        .build()
-        .unwrap();
+        .build()?;
 
-    for item in exploded_items.clone() {
-        let res = storage
+    for item in exploded_items {
+        let res = s3_client
             .client
             .get_object()
             .bucket(s3_client.bucket.clone())
-            .key(format!("{}/{}", authenticated_user.id, item.id))
+            .key(format!("{}/{}", owner_id, item.id))
             .presigned(presign_config.clone())
-            .await;
-
-        if res.is_err() {
-            return HttpResponse::InternalServerError().finish();
-        }
+            .await?;
 
         presigned_urls.push(PresignedExplodedItem {
-            id: item.id.clone(),
-            file_name: item.file_name.clone(),
-            virtual_path: item.virtual_path.clone(),
-            presign_url: res.unwrap().uri().to_string(),
+            id: item.id,
+            file_name: item.file_name,
+            virtual_path: item.virtual_path,
+            presign_url: res.uri().to_string(),
             size: item.file_size,
-            created_at: item.created_at.clone(),
+            file_type: item.file_type,
+            created_at: item.created_at,
         });
     }
 
-    let explode_response = ExplodeResponse {
+    Ok(ExplodeResponse {
         items: presigned_urls,
-    };
-
-    HttpResponse::Ok().json(explode_response)
+    })
 }
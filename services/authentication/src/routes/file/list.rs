@@ -1,10 +1,12 @@
+use crate::access::{authorize, Role};
 use crate::middleware::middleware::AuthenticatedUser;
+use crate::routes::file::tree::FileTreeExtension;
 use actix_web::{HttpResponse, Responder, post, web};
 use common::entities::file;
 use common::entities::prelude::File;
-use common::types::file::list::{Breadcrumb, ListFileElement, ListFilesRequest, ListFilesResponse};
+use common::types::file::list::{ListFileElement, ListFilesRequest, ListFilesResponse};
 use sea_orm::{ColumnTrait, Order, QueryOrder, QuerySelect, Value, Condition};
-use sea_orm::{ConnectionTrait, QueryFilter, Statement};
+use sea_orm::QueryFilter;
 use sea_orm::{DatabaseConnection, EntityTrait};
 use sea_orm::prelude::Expr;
 
@@ -17,6 +19,32 @@ pub async fn list(
     let limit = payload.limit.unwrap_or(20) as u64;
     let offset = payload.offset.unwrap_or(0) as u64;
 
+    let parent_id = match database
+        .resolve_reference(&authenticated_user.id, &payload.path)
+        .await
+    {
+        Ok(Some(id)) => id,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            log::error!("Error resolving path: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let access = match authorize(database.get_ref(), &authenticated_user.id, &parent_id, Role::Viewer).await {
+        Ok(access) => access,
+        Err(response) => return response,
+    };
+
+    let is_searching = payload.search_query.as_ref().map(|s| !s.is_empty()).unwrap_or(false);
+
+    // Searches only ever cover the caller's own files, a shared folder is browsed instead.
+    let owner_id = if is_searching {
+        authenticated_user.id.clone()
+    } else {
+        access.owner_id.clone()
+    };
+
     let (column, order) = match payload.sort.as_str() {
         "name_asc" => (file::Column::FileName, Order::Asc),
         "name_desc" => (file::Column::FileName, Order::Desc),
@@ -30,9 +58,7 @@ pub async fn list(
     };
 
     let mut query = File::find()
-        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()));
-
-    let is_searching = payload.search_query.as_ref().map(|s| !s.is_empty()).unwrap_or(false);
+        .filter(file::Column::OwnerId.eq(owner_id.clone()));
 
     if is_searching {
         let search = payload.search_query.as_ref().unwrap();
@@ -54,8 +80,7 @@ pub async fn list(
             .order_by(column, order);
     } else {
         query = query
-            .filter(file::Column::Path.eq(payload.path.clone()))
-            .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
+            .filter(file::Column::Path.eq(parent_id.clone()))
             .order_by(file::Column::IsDirectory, Order::Desc)
             .order_by(column, order);
     }
@@ -65,7 +90,7 @@ pub async fn list(
         .offset(offset)
         .all(database.get_ref());
 
-    let path_clone = payload.path.clone();
+    let path_clone = parent_id.clone();
     let database_ref = database.clone();
 
     let breadcrumbs_future = async move {
@@ -73,21 +98,10 @@ pub async fn list(
             return Ok(vec![]);
         }
 
-        let sql = r#"
-            WITH RECURSIVE trail AS (
-                SELECT id, file_name, path FROM file WHERE id = $1 AND owner_id = $2
-                UNION ALL
-                SELECT f.id, f.file_name, f.path FROM file f
-                INNER JOIN trail t ON f.id = t.path
-            )
-            SELECT id, file_name FROM trail;
-        "#;
-
-        database_ref.query_all_raw(Statement::from_sql_and_values(
-            database_ref.get_database_backend(),
-            sql,
-            [Value::from(path_clone), authenticated_user.id.clone().into()],
-        )).await
+        database_ref
+            .breadcrumbs(&owner_id, &path_clone)
+            .await
+            .map(|breadcrumbs| access.visible_breadcrumbs(breadcrumbs))
     };
 
     let (files_result, crumbs_result) = tokio::join!(files_query, breadcrumbs_future);
@@ -114,22 +128,19 @@ pub async fn list(
             path: v.path,
             upload_completed: v.upload_completed,
             is_directory: v.is_directory,
+            item_count: v.item_count,
         })
         .collect();
 
-    let mut breadcrumbs = match crumbs_result {
-        Ok(rows) => rows.into_iter().map(|row| Breadcrumb {
-            id: row.try_get::<String>("", "id").unwrap_or_default(),
-            name: row.try_get::<String>("", "file_name").unwrap_or_default(),
-        }).collect::<Vec<_>>(),
+    let breadcrumbs = match crumbs_result {
+        Ok(breadcrumbs) => breadcrumbs,
         Err(e) => {
             log::error!("Breadcrumb error: {:?}", e);
             vec![]
         }
     };
-    breadcrumbs.reverse();
 
-    if !is_searching && breadcrumbs.is_empty() && !payload.path.is_empty() {
+    if !is_searching && breadcrumbs.is_empty() && !parent_id.is_empty() {
         return HttpResponse::NotFound().finish();
     }
 
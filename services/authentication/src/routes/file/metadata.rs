@@ -1,6 +1,8 @@
+use crate::error::{error_response, internal_error};
 use actix_web::{HttpResponse, post, web};
 use common::entities::file;
 use common::entities::prelude::File;
+use common::types::error::ErrorCode;
 use common::types::file::metadata::{MetadataRequest, MetadataResponse};
 use sea_orm::ColumnTrait;
 use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
@@ -15,30 +17,18 @@ pub async fn metadata(
         .one(database.get_ref())
         .await;
 
-    if file.is_err() {
-        return HttpResponse::InternalServerError().body(format!(
-            "Failed to create file record: {}",
-            file.err().unwrap()
-        ));
-    }
-
-    let file = file.unwrap();
-
-    if file.is_none() {
-        return HttpResponse::NotFound().body(format!("File with ID {} not found", payload.file_id));
-    }
-
     match file {
-        Some(data) => HttpResponse::Ok().json(MetadataResponse {
+        Ok(Some(data)) => HttpResponse::Ok().json(MetadataResponse {
             file_name: data.file_name,
             size: data.file_size as u64,
             content_type: data.file_type,
             path: data.path,
             created_at: data.created_at,
             owner_id: data.owner_id,
+            item_count: data.item_count,
+            is_directory: data.is_directory,
         }),
-        None => {
-            HttpResponse::NotFound().body(format!("File with ID {} not found", payload.file_id))
-        }
+        Ok(None) => error_response(ErrorCode::NotFound, "File not found"),
+        Err(err) => internal_error("Failed to fetch the file metadata", err),
     }
 }
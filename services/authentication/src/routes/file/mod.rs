@@ -7,4 +7,10 @@ pub mod metadata;
 pub mod upload;
 pub mod directory;
 pub mod delete_directory;
-pub mod explode;
\ No newline at end of file
+pub mod explode;
+pub mod tree;
+pub mod resolve;
+pub mod share;
+pub mod changes;
+pub mod events;
+pub mod shared;
@@ -2,33 +2,186 @@ use actix_web::{post, web, HttpResponse, Responder};
 use common::entities::file;
 use common::entities::prelude::File;
 use migration::Expr;
+use serde_json::{json, Map, Value};
 use sea_orm::QueryFilter;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, TransactionTrait};
+use common::types::file::job::JobOperation;
 use common::types::file::r#move::MoveFilesRequest;
-use crate::middleware::middleware::AuthenticatedUser;
+use crate::jobs::{job_response, JobQueue, JOB_THRESHOLD};
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::error::internal_error;
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::routes::file::copy::copy_destination;
+use crate::routes::file::tree::{contribution, FileTreeExtension};
+use crate::webhooks;
+use common::types::webhook::event::WebhookEvent;
 
 #[post("move")]
 pub async fn r#move(
     database: web::Data<DatabaseConnection>,
+    queue: web::Data<JobQueue>,
     payload: web::Json<MoveFilesRequest>,
     authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> impl Responder {
-    match File::update_many()
-        .col_expr(
-            file::Column::Path,
-            Expr::Value(payload.destination_path.clone().into()),
-        )
-        .filter(file::Column::Id.is_in(payload.file_ids.clone()))
-        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
-        .exec(database.get_ref())
+    let destination = match database
+        .resolve_reference(&authenticated_user.id, &payload.destination_path)
         .await
     {
-        Ok(_) => {}
-        Err(err) => {
-            return HttpResponse::InternalServerError()
-                .body(format!("Failed to update the files: {:?}", err));
+        Ok(Some(id)) => id,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => return internal_error("Failed to resolve the destination", err),
+    };
+
+    let mut file_ids = Vec::with_capacity(payload.file_ids.len());
+    for reference in &payload.file_ids {
+        match database.resolve_reference(&authenticated_user.id, reference).await {
+            Ok(Some(id)) if !id.is_empty() => file_ids.push(id),
+            Ok(_) => return HttpResponse::NotFound().finish(),
+            Err(err) => return internal_error("Failed to resolve the files", err),
+        }
+    }
+
+    // Entries stay in their owner's tree, storage keys can't follow them into someone else's.
+    let owner_id = match authorize(database.get_ref(), &authenticated_user.id, &destination, Role::Editor).await {
+        Ok(access) => access.owner_id,
+        Err(response) => return response,
+    };
+
+    match copy_destination(database.get_ref(), &owner_id, &destination).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().body("The destination is not a directory"),
+        Err(err) => return internal_error("Failed to check the move destination", err),
+    }
+
+    for file_id in &file_ids {
+        match authorize(database.get_ref(), &authenticated_user.id, file_id, Role::Editor).await {
+            Ok(access) if access.owner_id == owner_id => {}
+            Ok(_) => return HttpResponse::BadRequest().body("Entries can't be moved to another user's files"),
+            Err(response) => return response,
         }
     }
 
+    let destination_ancestors = match database
+        .ancestor_ids(&owner_id, &destination)
+        .await
+    {
+        Ok(ids) => ids,
+        Err(err) => return internal_error("Failed to resolve the destination", err),
+    };
+
+    if file_ids.iter().any(|id| destination_ancestors.contains(id)) {
+        return HttpResponse::BadRequest().body("Cannot move a directory into itself");
+    }
+
+    if file_ids.len() > JOB_THRESHOLD {
+        let operation = JobOperation::Move(MoveFilesRequest {
+            file_ids: file_ids.clone(),
+            destination_path: destination.clone(),
+        });
+        return match queue.submit(&database, &owner_id, &authenticated_user.id, &operation).await {
+            Ok(job) => {
+                record_logged(
+                    database.get_ref(),
+                    &authenticated_user.id,
+                    &client,
+                    AuditEvent::new("file.move", file_ids)
+                        .after(json!({ "path": destination, "job_id": job.id })),
+                )
+                .await;
+
+                HttpResponse::Accepted().json(job_response(job))
+            }
+            Err(err) => internal_error("Failed to queue the move", err),
+        };
+    }
+
+    let update = move_entries(&database, &owner_id, &file_ids, &destination).await;
+
+    let moved = match update {
+        Ok(moved) => moved,
+        Err(err) => return internal_error("Failed to update the files", err),
+    };
+
+    let previous_paths = moved
+        .iter()
+        .map(|entry| (entry.id.clone(), Value::from(entry.path.clone())))
+        .collect::<Map<_, _>>();
+
+    record_logged(
+        database.get_ref(),
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("file.move", moved.into_iter().map(|entry| entry.id).collect())
+            .before(json!({ "paths": previous_paths }))
+            .after(json!({ "path": destination })),
+    )
+    .await;
+
     HttpResponse::Ok().finish()
 }
+
+/// Re-parents the entries under `destination` and shifts their totals from the old
+/// ancestors to the new ones. Callers are expected to have ruled out cycles first. Returns
+/// the rows as they were before the move.
+pub async fn move_entries(
+    database: &DatabaseConnection,
+    owner_id: &str,
+    file_ids: &[String],
+    destination: &str,
+) -> Result<Vec<file::Model>, DbErr> {
+    let transaction = database.begin().await?;
+
+    let moved = File::find()
+        .filter(file::Column::Id.is_in(file_ids.to_vec()))
+        .filter(file::Column::OwnerId.eq(owner_id))
+        .all(&transaction)
+        .await?;
+
+    if !moved.is_empty() {
+        // Both ends of the move count, so a folder-scoped hook sees files leave and arrive.
+        let mut touched = moved.iter().map(|entry| entry.id.clone()).collect::<Vec<_>>();
+        touched.push(destination.to_string());
+
+        webhooks::emit(
+            &transaction,
+            owner_id,
+            WebhookEvent::Moved,
+            &touched,
+            json!({
+                "destination": destination,
+                "files": moved.iter().map(|entry| json!({
+                    "id": entry.id,
+                    "file_name": entry.file_name,
+                    "previous_path": entry.path,
+                })).collect::<Vec<_>>(),
+            }),
+        )
+        .await?;
+    }
+
+    File::update_many()
+        .col_expr(
+            file::Column::Path,
+            Expr::Value(destination.into()),
+        )
+        .filter(file::Column::Id.is_in(file_ids.to_vec()))
+        .filter(file::Column::OwnerId.eq(owner_id))
+        .exec(&transaction)
+        .await?;
+
+    for entry in moved.iter().filter(|entry| entry.path != destination) {
+        let (size, count) = contribution(entry);
+        transaction
+            .adjust_ancestors(owner_id, &entry.path, -size, -count)
+            .await?;
+        transaction
+            .adjust_ancestors(owner_id, destination, size, count)
+            .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(moved)
+}
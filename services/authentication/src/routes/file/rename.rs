@@ -1,4 +1,7 @@
-use crate::middleware::middleware::AuthenticatedUser;
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::error::internal_error;
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
 use actix_web::{HttpResponse, Responder, post, web};
 use common::entities::file;
 use common::entities::prelude::File;
@@ -6,18 +9,25 @@ use common::types::file::rename::RenameFileRequest;
 use migration::Expr;
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait};
 use sea_orm::{ExprTrait, QueryFilter};
+use serde_json::json;
 
 #[post("rename")]
 pub async fn rename(
     database: web::Data<DatabaseConnection>,
     payload: web::Json<RenameFileRequest>,
     authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> impl Responder {
     let db = database.get_ref();
 
+    let access = match authorize(db, &authenticated_user.id, &payload.file_id, Role::Editor).await {
+        Ok(access) => access,
+        Err(response) => return response,
+    };
+
     let file_to_rename = match File::find()
         .filter(file::Column::Id.eq(payload.file_id.clone()))
-        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .filter(file::Column::OwnerId.eq(access.owner_id.clone()))
         .one(db)
         .await
     {
@@ -68,19 +78,30 @@ pub async fn rename(
             .await;
 
         if let Err(e) = update_children {
-            return HttpResponse::InternalServerError().body(e.to_string());
+            return internal_error("Failed to rename", e);
         }
     }
 
     let update_self = File::update_many()
         .filter(file::Column::Id.eq(payload.file_id.to_owned()))
-        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
-        .col_expr(file::Column::FileName, Expr::value(new_name))
+        .filter(file::Column::OwnerId.eq(access.owner_id.clone()))
+        .col_expr(file::Column::FileName, Expr::value(new_name.clone()))
         .exec(db)
         .await;
 
-    match update_self {
-        Ok(_) => HttpResponse::Ok().finish(),
-        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    if let Err(e) = update_self {
+        return internal_error("Failed to rename", e);
     }
+
+    record_logged(
+        db,
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("file.rename", vec![payload.file_id.clone()])
+            .before(json!({ "file_name": old_name }))
+            .after(json!({ "file_name": new_name })),
+    )
+    .await;
+
+    HttpResponse::Ok().finish()
 }
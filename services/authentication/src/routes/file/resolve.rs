@@ -0,0 +1,77 @@
+use crate::access::{authorize, Role};
+use crate::middleware::middleware::AuthenticatedUser;
+use crate::routes::file::tree::{human_path, FileTreeExtension};
+use actix_web::{HttpResponse, post, web};
+use common::entities::file;
+use common::entities::prelude::File;
+use common::types::file::resolve::{ResolveRequest, ResolveResponse};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+#[post("resolve")]
+pub async fn resolve(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ResolveRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> HttpResponse {
+    let file_id = match (&payload.path, &payload.file_id) {
+        (_, Some(file_id)) => file_id.clone(),
+        (Some(path), None) => match database.resolve_path(&authenticated_user.id, path).await {
+            Ok(Some(id)) => id,
+            Ok(None) => return HttpResponse::NotFound().finish(),
+            Err(err) => {
+                log::error!("Path resolution error: {:?}", err);
+                return HttpResponse::InternalServerError().finish();
+            }
+        },
+        (None, None) => {
+            return HttpResponse::BadRequest().json("Either path or file_id must be provided");
+        }
+    };
+
+    if file_id.is_empty() {
+        return HttpResponse::Ok().json(ResolveResponse {
+            file_id,
+            path: "/".to_string(),
+            is_directory: true,
+            breadcrumbs: vec![],
+            owner_id: authenticated_user.id.clone(),
+            file_type: String::new(),
+        });
+    }
+
+    let access = match authorize(database.get_ref(), &authenticated_user.id, &file_id, Role::Viewer).await {
+        Ok(access) => access,
+        Err(response) => return response,
+    };
+
+    let file = match File::find()
+        .filter(file::Column::Id.eq(file_id.clone()))
+        .filter(file::Column::OwnerId.eq(access.owner_id.clone()))
+        .one(database.get_ref())
+        .await
+    {
+        Ok(Some(file)) => file,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Path resolution error: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let breadcrumbs = match database.breadcrumbs(&access.owner_id, &file.id).await {
+        Ok(breadcrumbs) => access.visible_breadcrumbs(breadcrumbs),
+        Err(err) => {
+            log::error!("Breadcrumb error: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    HttpResponse::Ok().json(ResolveResponse {
+        file_id: file.id,
+        path: human_path(&breadcrumbs),
+        is_directory: file.is_directory,
+        breadcrumbs,
+        owner_id: access.owner_id,
+        file_type: file.file_type,
+    })
+}
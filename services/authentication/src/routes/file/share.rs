@@ -0,0 +1,61 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::webhooks;
+use actix_web::{post, web, HttpResponse};
+use common::entities::file;
+use common::entities::prelude::File;
+use common::types::file::share::ShareRequest;
+use common::types::webhook::event::WebhookEvent;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde_json::json;
+
+/// Called by the edge before it hands out a share link, so links are only created for the
+/// caller's own files and every one of them ends up in the audit log.
+#[post("share")]
+pub async fn share(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> HttpResponse {
+    let shared = match File::find()
+        .filter(file::Column::Id.eq(payload.file_id.clone()))
+        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .one(database.get_ref())
+        .await
+    {
+        Ok(Some(shared)) => shared,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to look up the shared file: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if let Err(err) = webhooks::emit(
+        database.get_ref(),
+        &authenticated_user.id,
+        WebhookEvent::Shared,
+        std::slice::from_ref(&shared.id),
+        json!({
+            "id": shared.id,
+            "file_name": shared.file_name,
+            "path": shared.path,
+        }),
+    )
+    .await
+    {
+        log::error!("Failed to queue webhooks for the share of {}: {:?}", shared.id, err);
+    }
+
+    record_logged(
+        database.get_ref(),
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("share.create", vec![shared.id])
+            .after(json!({ "file_name": shared.file_name })),
+    )
+    .await;
+
+    HttpResponse::Ok().finish()
+}
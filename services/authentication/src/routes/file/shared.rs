@@ -0,0 +1,71 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, FileGrant, User};
+use common::entities::{file_grant, user};
+use common::types::file::grant::{GrantRole, SharedWithMeElement, SharedWithMeResponse};
+use common::types::file::list::ListFileElement;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::HashMap;
+
+/// Entries other users have granted the caller, newest first. Only the granted entries
+/// themselves are listed, their contents are browsed through `list` by id.
+#[post("shared")]
+pub async fn shared(
+    database: web::Data<DatabaseConnection>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let result: Result<Vec<SharedWithMeElement>, DbErr> = async {
+        let grants = FileGrant::find()
+            .find_also_related(File)
+            .filter(file_grant::Column::GranteeId.eq(authenticated_user.id.clone()))
+            .order_by_desc(file_grant::Column::CreatedAt)
+            .all(database.get_ref())
+            .await?;
+
+        let owner_ids = grants
+            .iter()
+            .map(|(grant, _)| grant.owner_id.clone())
+            .collect::<Vec<_>>();
+
+        let owners = User::find()
+            .filter(user::Column::Id.is_in(owner_ids))
+            .all(database.get_ref())
+            .await?
+            .into_iter()
+            .map(|owner| (owner.id, owner.username))
+            .collect::<HashMap<_, _>>();
+
+        Ok(grants
+            .into_iter()
+            .filter_map(|(grant, entry)| {
+                let entry = entry?;
+                Some(SharedWithMeElement {
+                    owner: owners.get(&grant.owner_id).cloned().unwrap_or_default(),
+                    owner_id: grant.owner_id,
+                    role: GrantRole::parse(&grant.role)?,
+                    shared_at: grant.created_at,
+                    entry: ListFileElement {
+                        id: entry.id,
+                        file_name: entry.file_name,
+                        file_size: entry.file_size,
+                        file_type: entry.file_type,
+                        created_at: entry.created_at,
+                        path: entry.path,
+                        upload_completed: entry.upload_completed,
+                        is_directory: entry.is_directory,
+                        item_count: entry.item_count,
+                    },
+                })
+            })
+            .collect())
+    }
+    .await;
+
+    match result {
+        Ok(files) => HttpResponse::Ok().json(SharedWithMeResponse { files }),
+        Err(err) => {
+            log::error!("Failed to list the files shared with the user: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use common::entities::file;
+use common::types::file::list::Breadcrumb;
+use sea_orm::prelude::async_trait;
+use sea_orm::{ConnectionTrait, DbErr, Statement, Value};
+
+/// How much a single entry adds to the totals of every directory above it, as
+/// `(size, item_count)`. Directories carry their own subtree along with them and
+/// uploads only count once they have been completed.
+pub fn contribution(entry: &file::Model) -> (i64, i64) {
+    if entry.is_directory {
+        (entry.file_size, entry.item_count + 1)
+    } else if entry.upload_completed {
+        (entry.file_size, 1)
+    } else {
+        (0, 0)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait FileTreeExtension {
+    async fn adjust_ancestors(
+        &self,
+        owner_id: &str,
+        parent_id: &str,
+        size_delta: i64,
+        count_delta: i64,
+    ) -> Result<(), DbErr>;
+    async fn ancestor_ids(&self, owner_id: &str, file_id: &str) -> Result<Vec<String>, DbErr>;
+    async fn is_within(&self, owner_id: &str, root_id: &str, file_id: &str) -> Result<bool, DbErr>;
+    async fn breadcrumbs(&self, owner_id: &str, file_id: &str) -> Result<Vec<Breadcrumb>, DbErr>;
+    async fn resolve_path(&self, owner_id: &str, path: &str) -> Result<Option<String>, DbErr>;
+    async fn resolve_reference(&self, owner_id: &str, reference: &str) -> Result<Option<String>, DbErr>;
+}
+
+/// Splits a human path such as `/Projects/2026/report.pdf` into its names.
+pub fn path_segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|segment| !segment.is_empty()).collect()
+}
+
+/// Joins breadcrumbs back into the `/Projects/2026/report.pdf` form.
+pub fn human_path(breadcrumbs: &[Breadcrumb]) -> String {
+    format!(
+        "/{}",
+        breadcrumbs
+            .iter()
+            .map(|crumb| crumb.name.as_str())
+            .collect::<Vec<_>>()
+            .join("/")
+    )
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionTrait + Sync> FileTreeExtension for C {
+    async fn adjust_ancestors(
+        &self,
+        owner_id: &str,
+        parent_id: &str,
+        size_delta: i64,
+        count_delta: i64,
+    ) -> Result<(), DbErr> {
+        if parent_id.is_empty() || (size_delta == 0 && count_delta == 0) {
+            return Ok(());
+        }
+
+        // language=PostgreSQL
+        let sql = r#"
+            WITH RECURSIVE ancestors AS (
+                SELECT id, path FROM file WHERE id = $1 AND owner_id = $2
+                UNION
+                SELECT f.id, f.path FROM file f
+                INNER JOIN ancestors a ON f.id = a.path
+                WHERE f.owner_id = $2
+            )
+            UPDATE file
+            SET file_size = file_size + $3, item_count = item_count + $4
+            WHERE id IN (SELECT id FROM ancestors);
+        "#;
+
+        self.execute_raw(Statement::from_sql_and_values(
+            self.get_database_backend(),
+            sql,
+            [
+                Value::from(parent_id),
+                Value::from(owner_id),
+                Value::from(size_delta),
+                Value::from(count_delta),
+            ],
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn ancestor_ids(&self, owner_id: &str, file_id: &str) -> Result<Vec<String>, DbErr> {
+        if file_id.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // language=PostgreSQL
+        let sql = r#"
+            WITH RECURSIVE ancestors AS (
+                SELECT id, path FROM file WHERE id = $1 AND owner_id = $2
+                UNION
+                SELECT f.id, f.path FROM file f
+                INNER JOIN ancestors a ON f.id = a.path
+                WHERE f.owner_id = $2
+            )
+            SELECT id FROM ancestors;
+        "#;
+
+        let rows = self
+            .query_all_raw(Statement::from_sql_and_values(
+                self.get_database_backend(),
+                sql,
+                [Value::from(file_id), Value::from(owner_id)],
+            ))
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row.try_get::<String>("", "id").ok())
+            .collect())
+    }
+
+    /// Whether the entry is the root itself or anywhere below it.
+    async fn is_within(&self, owner_id: &str, root_id: &str, file_id: &str) -> Result<bool, DbErr> {
+        Ok(self
+            .ancestor_ids(owner_id, file_id)
+            .await?
+            .iter()
+            .any(|id| id == root_id))
+    }
+
+    async fn breadcrumbs(&self, owner_id: &str, file_id: &str) -> Result<Vec<Breadcrumb>, DbErr> {
+        // language=PostgreSQL
+        let sql = r#"
+            WITH RECURSIVE trail AS (
+                SELECT id, file_name, path FROM file WHERE id = $1 AND owner_id = $2
+                UNION ALL
+                SELECT f.id, f.file_name, f.path FROM file f
+                INNER JOIN trail t ON f.id = t.path
+            )
+            SELECT id, file_name FROM trail;
+        "#;
+
+        let rows = self
+            .query_all_raw(Statement::from_sql_and_values(
+                self.get_database_backend(),
+                sql,
+                [Value::from(file_id), Value::from(owner_id)],
+            ))
+            .await?;
+
+        let mut breadcrumbs = rows
+            .into_iter()
+            .map(|row| Breadcrumb {
+                id: row.try_get::<String>("", "id").unwrap_or_default(),
+                name: row.try_get::<String>("", "file_name").unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        breadcrumbs.reverse();
+
+        Ok(breadcrumbs)
+    }
+
+    async fn resolve_path(&self, owner_id: &str, path: &str) -> Result<Option<String>, DbErr> {
+        let segments = path_segments(path);
+
+        if segments.is_empty() {
+            return Ok(Some(String::new()));
+        }
+
+        // Walks down one name per level. Names aren't unique within a folder, the oldest
+        // entry wins so the same path keeps resolving to the same file.
+        // language=PostgreSQL
+        let sql = r#"
+            WITH RECURSIVE walk AS (
+                SELECT f.id, 1 AS depth, f.created_at FROM file f
+                WHERE f.owner_id = $2 AND f.path = '' AND f.file_name = ($1::text[])[1]
+                UNION ALL
+                SELECT f.id, w.depth + 1, f.created_at FROM file f
+                INNER JOIN walk w ON f.path = w.id
+                WHERE f.owner_id = $2 AND f.file_name = ($1::text[])[w.depth + 1]
+            )
+            SELECT id FROM walk
+            WHERE depth = cardinality($1::text[])
+            ORDER BY created_at ASC
+            LIMIT 1;
+        "#;
+
+        let segments = segments.into_iter().map(String::from).collect::<Vec<_>>();
+
+        let row = self
+            .query_one_raw(Statement::from_sql_and_values(
+                self.get_database_backend(),
+                sql,
+                [Value::from(segments), Value::from(owner_id)],
+            ))
+            .await?;
+
+        Ok(row.and_then(|row| row.try_get::<String>("", "id").ok()))
+    }
+
+    async fn resolve_reference(&self, owner_id: &str, reference: &str) -> Result<Option<String>, DbErr> {
+        if reference.starts_with('/') {
+            self.resolve_path(owner_id, reference).await
+        } else {
+            Ok(Some(reference.to_string()))
+        }
+    }
+}
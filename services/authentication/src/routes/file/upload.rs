@@ -1,15 +1,21 @@
-use crate::middleware::middleware::AuthenticatedUser;
+use crate::access::{authorize, Role};
+use crate::audit::{record, record_logged, AuditEvent};
+use crate::error::internal_error;
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::routes::file::tree::FileTreeExtension;
+use crate::webhooks;
 use actix_web::{post, web, HttpResponse};
-use log::{error};
 use common::entities::file;
 use common::entities::prelude::File;
-use common::types::file::upload_complete::CompleteUploadRequest;
+use common::types::file::upload_complete::{CompleteUploadRequest, Part};
 use common::types::file::upload_init::{InitUploadInternalRequest, InitUploadInternalResponse};
+use common::types::webhook::event::WebhookEvent;
 use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::sea_query::prelude::chrono;
 use sea_orm::ColumnTrait;
 use sea_orm::QueryFilter;
-use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use serde_json::json;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, Set, TransactionTrait};
 use storage::s3_manager::S3StorageManager;
 use storage::s3_scoped_storage::S3ScopedStorage;
 use storage::StorageBackend;
@@ -20,45 +26,68 @@ pub async fn init(
     payload: web::Json<InitUploadInternalRequest>,
     s3_scoped_storage: web::Data<S3StorageManager>,
     authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> HttpResponse {
+    let parent_id = match database
+        .resolve_reference(&authenticated_user.id, &payload.path)
+        .await
+    {
+        Ok(Some(id)) => id,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => return internal_error("Failed to resolve the upload path", err),
+    };
+
+    // Uploads into a shared folder belong to the folder's owner and count against their storage.
+    let owner_id = match authorize(database.get_ref(), &authenticated_user.id, &parent_id, Role::Editor).await {
+        Ok(access) => access.owner_id,
+        Err(response) => return response,
+    };
+
     let insert = File::insert(file::ActiveModel {
         id: Set(payload.file_id.clone()),
         file_name: Set(payload.filename.clone()),
-        owner_id: Set(authenticated_user.id.clone()),
+        owner_id: Set(owner_id.clone()),
         created_at: Set(DateTimeWithTimeZone::from(chrono::Utc::now())),
         upload_completed: Set(false),
         file_type: Set(payload.content_type.clone()),
         file_size: Set(payload.size as i64),
-        path: Set(payload.path.clone()),
+        path: Set(parent_id.clone()),
         is_directory: Set(false),
+        item_count: Set(0),
     })
     .exec(database.get_ref())
     .await;
 
-    if insert.is_err() {
-        return HttpResponse::InternalServerError().body(format!(
-            "Failed to create file record: {}",
-            insert.err().unwrap()
-        ));
+    if let Err(err) = insert {
+        return internal_error("Failed to create the file record", err);
     }
 
     let storage = S3ScopedStorage {
-        user_id: authenticated_user.id.clone(),
+        user_id: owner_id.clone(),
         bucket: s3_scoped_storage.bucket.clone(),
         client: s3_scoped_storage.client.clone(),
     };
 
     let id = match storage.create_upload(&payload.file_id).await {
         Ok(res) => res,
-        Err(err) => {
-            return HttpResponse::InternalServerError().body(format!(
-                "Failed to create upload session: {}",
-                err.to_string()
-            ));
-        }
+        Err(err) => return internal_error("Failed to create the upload session", err),
     };
 
-    HttpResponse::Ok().json(InitUploadInternalResponse { upload_id: id })
+    record_logged(
+        database.get_ref(),
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("upload.init", vec![payload.file_id.clone()]).after(json!({
+            "file_name": payload.filename,
+            "file_size": payload.size,
+            "file_type": payload.content_type,
+            "path": parent_id,
+            "owner_id": owner_id,
+        })),
+    )
+    .await;
+
+    HttpResponse::Ok().json(InitUploadInternalResponse { upload_id: id, owner_id })
 }
 
 #[post("complete")]
@@ -67,52 +96,133 @@ pub async fn complete(
     payload: web::Json<CompleteUploadRequest>,
     s3_scoped_storage: web::Data<S3StorageManager>,
     authenticated_user: AuthenticatedUser,
+    client: ClientContext,
 ) -> HttpResponse {
+    let owner_id = match authorize(database.get_ref(), &authenticated_user.id, &payload.file_id, Role::Editor).await {
+        Ok(access) => access.owner_id,
+        Err(response) => return response,
+    };
+
     let storage = S3ScopedStorage {
-        user_id: authenticated_user.id.clone(),
+        user_id: owner_id.clone(),
         bucket: s3_scoped_storage.bucket.clone(),
         client: s3_scoped_storage.client.clone(),
     };
 
+    if let Err(response) = complete_object(&storage, &payload.file_id, &payload.upload_id, &payload.parts).await {
+        return response;
+    }
+
+    let update = mark_completed(
+        database.get_ref(),
+        &owner_id,
+        &authenticated_user.id,
+        &client,
+        &payload.file_id,
+        None,
+    )
+    .await;
+
+    if let Err(err) = update {
+        return internal_error("Failed to update the file record", err);
+    }
+
+    HttpResponse::Ok().finish()
+}
+
+/// Stitches the uploaded parts together in storage.
+pub async fn complete_object(
+    storage: &S3ScopedStorage,
+    file_id: &str,
+    upload_id: &str,
+    parts: &[Part],
+) -> Result<(), HttpResponse> {
     match storage
         .complete_upload(
-            &payload.file_id,
-            &payload.upload_id,
-            payload
-                .parts
+            file_id,
+            upload_id,
+            parts
                 .iter()
                 .map(|part| (part.part_number, part.etag.clone()))
                 .collect::<Vec<(u32, String)>>(),
         )
         .await
     {
-        Ok(_) => {}
-        Err(err) => {
-            if let Some(s3_err) = err.downcast_ref::<aws_sdk_s3::Error>() {
-                error!("S3 Error: {:?}", s3_err);
-            } else {
-                error!("S3 Error: {:?}", err);
-            }
-            return HttpResponse::InternalServerError().body(format!(
-                "S3 Error: {}", err
-            ));
-        }
+        Ok(_) => Ok(()),
+        Err(err) => match err.downcast_ref::<aws_sdk_s3::Error>() {
+            Some(s3_err) => Err(internal_error("Failed to complete the upload", s3_err)),
+            None => Err(internal_error("Failed to complete the upload", err)),
+        },
     }
+}
 
-    let update = File::update_many()
-        .col_expr(file::Column::UploadCompleted, true.into())
-        .filter(file::Column::Id.eq(payload.file_id.clone()))
-        .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
-        .exec(database.get_ref())
-        .await;
-    
-    if update.is_err() {
-        error!("Failed to update file records: {}", update.clone().err().unwrap());
-        HttpResponse::InternalServerError().body(format!(
-            "Failed to update file record: {}",
-            update.err().unwrap()
-        ));
+/// Marks a pending upload as completed and adds it to the totals above it. `file_size`
+/// replaces the size given at init when the stored size has been checked. Does nothing for an
+/// upload that isn't pending.
+pub async fn mark_completed(
+    database: &DatabaseConnection,
+    owner_id: &str,
+    actor_id: &str,
+    client: &ClientContext,
+    file_id: &str,
+    file_size: Option<i64>,
+) -> Result<(), DbErr> {
+    let transaction = database.begin().await?;
+
+    let uploaded = File::find()
+        .filter(file::Column::Id.eq(file_id))
+        .filter(file::Column::OwnerId.eq(owner_id))
+        .filter(file::Column::UploadCompleted.eq(false))
+        .one(&transaction)
+        .await?;
+
+    if let Some(mut uploaded) = uploaded {
+        // The find doesn't lock the row, a concurrent complete can pass it too. Only the one
+        // whose update still sees the upload pending goes on to touch the totals.
+        let mut update = File::update_many()
+            .col_expr(file::Column::UploadCompleted, true.into())
+            .filter(file::Column::Id.eq(uploaded.id.clone()))
+            .filter(file::Column::OwnerId.eq(owner_id))
+            .filter(file::Column::UploadCompleted.eq(false));
+
+        if let Some(file_size) = file_size {
+            update = update.col_expr(file::Column::FileSize, file_size.into());
+            uploaded.file_size = file_size;
+        }
+
+        if update.exec(&transaction).await?.rows_affected != 1 {
+            return transaction.rollback().await;
+        }
+
+        transaction
+            .adjust_ancestors(owner_id, &uploaded.path, uploaded.file_size, 1)
+            .await?;
+
+        record(
+            &transaction,
+            actor_id,
+            client,
+            AuditEvent::new("upload.complete", vec![uploaded.id.clone()])
+                .before(json!({ "upload_completed": false }))
+                .after(json!({ "upload_completed": true, "file_size": uploaded.file_size })),
+        )
+        .await?;
+
+        webhooks::emit(
+            &transaction,
+            owner_id,
+            WebhookEvent::UploadCompleted,
+            std::slice::from_ref(&uploaded.id),
+            json!({
+                "id": uploaded.id,
+                "file_name": uploaded.file_name,
+                "file_size": uploaded.file_size,
+                "file_type": uploaded.file_type,
+                "path": uploaded.path,
+            }),
+        )
+        .await?;
     }
 
-    HttpResponse::Ok().finish()
+    transaction.commit().await
 }
@@ -0,0 +1,80 @@
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{FileGrant, User};
+use common::entities::file_grant;
+use common::types::file::grant::CreateGrantRequest;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde_json::json;
+
+/// Only the owner hands out access, editors can't pass it on.
+#[post("create")]
+pub async fn create(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<CreateGrantRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    if payload.file_id.is_empty() {
+        return HttpResponse::BadRequest().json("The root can't be shared");
+    }
+
+    if let Err(response) = authorize(database.get_ref(), &authenticated_user.id, &payload.file_id, Role::Owner).await {
+        return response;
+    }
+
+    let grantee = match User::find()
+        .filter(Expr::cust_with_values("lower(email) = lower($1)", [payload.email.trim()]))
+        .one(database.get_ref())
+        .await
+    {
+        Ok(Some(grantee)) => grantee,
+        Ok(None) => return HttpResponse::NotFound().json("No Ledger user has that email"),
+        Err(err) => {
+            log::error!("Failed to look up the grantee: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if grantee.id == authenticated_user.id {
+        return HttpResponse::BadRequest().json("You already own this file");
+    }
+
+    let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+    let inserted = FileGrant::insert(file_grant::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        file_id: Set(payload.file_id.clone()),
+        owner_id: Set(authenticated_user.id.clone()),
+        grantee_id: Set(grantee.id.clone()),
+        role: Set(payload.role.as_str().to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    })
+    .on_conflict(
+        OnConflict::columns([file_grant::Column::FileId, file_grant::Column::GranteeId])
+            .update_columns([file_grant::Column::Role, file_grant::Column::UpdatedAt])
+            .to_owned(),
+    )
+    .exec_without_returning(database.get_ref())
+    .await;
+
+    if let Err(err) = inserted {
+        log::error!("Failed to save the grant: {:?}", err);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    record_logged(
+        database.get_ref(),
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("grant.create", vec![payload.file_id.clone()])
+            .after(json!({ "grantee_id": grantee.id, "role": payload.role.as_str() })),
+    )
+    .await;
+
+    HttpResponse::Ok().finish()
+}
@@ -0,0 +1,49 @@
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{delete, web, HttpResponse, Responder};
+use common::entities::file_grant;
+use common::entities::prelude::FileGrant;
+use common::types::file::grant::DeleteGrantRequest;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde_json::json;
+
+/// The owner takes a grant back. A grantee may also remove their own, to leave a share.
+#[delete("delete")]
+pub async fn delete(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<DeleteGrantRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    if payload.grantee_id != authenticated_user.id
+        && let Err(response) = authorize(database.get_ref(), &authenticated_user.id, &payload.file_id, Role::Owner).await
+    {
+        return response;
+    }
+
+    match FileGrant::delete_many()
+        .filter(file_grant::Column::FileId.eq(payload.file_id.clone()))
+        .filter(file_grant::Column::GranteeId.eq(payload.grantee_id.clone()))
+        .exec(database.get_ref())
+        .await
+    {
+        Ok(result) if result.rows_affected == 0 => HttpResponse::NotFound().finish(),
+        Ok(_) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("grant.delete", vec![payload.file_id.clone()])
+                    .before(json!({ "grantee_id": payload.grantee_id })),
+            )
+            .await;
+
+            HttpResponse::Ok().finish()
+        }
+        Err(err) => {
+            log::error!("Failed to delete the grant: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
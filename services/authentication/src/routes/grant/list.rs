@@ -0,0 +1,47 @@
+use crate::access::{authorize, Role};
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file_grant;
+use common::entities::prelude::FileGrant;
+use common::types::file::grant::{GrantElement, GrantRole, ListGrantsRequest, ListGrantsResponse};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+/// Who has been granted the entry itself. Grants on folders above it aren't repeated here.
+#[post("list")]
+pub async fn list(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ListGrantsRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    if let Err(response) = authorize(database.get_ref(), &authenticated_user.id, &payload.file_id, Role::Owner).await {
+        return response;
+    }
+
+    match FileGrant::find()
+        .find_also_linked(file_grant::GranteeLink)
+        .filter(file_grant::Column::FileId.eq(payload.file_id.clone()))
+        .order_by_asc(file_grant::Column::CreatedAt)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(grants) => HttpResponse::Ok().json(ListGrantsResponse {
+            grants: grants
+                .into_iter()
+                .filter_map(|(grant, grantee)| {
+                    let grantee = grantee?;
+                    Some(GrantElement {
+                        grantee_id: grant.grantee_id,
+                        username: grantee.username,
+                        email: grantee.email,
+                        role: GrantRole::parse(&grant.role)?,
+                        created_at: grant.created_at,
+                    })
+                })
+                .collect(),
+        }),
+        Err(err) => {
+            log::error!("Failed to list grants: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::jobs::{cancel as cancel_job, job_response};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::job;
+use common::entities::prelude::Job;
+use common::types::file::job::JobRequest;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde_json::json;
+
+#[post("cancel")]
+pub async fn cancel(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<JobRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    let result: Result<(bool, Option<job::Model>), DbErr> = async {
+        let cancelled = cancel_job(&database, &authenticated_user.id, &payload.job_id).await?;
+
+        let job = Job::find()
+            .filter(job::Column::Id.eq(payload.job_id.clone()))
+            .filter(job::Column::SubmittedBy.eq(authenticated_user.id.clone()))
+            .one(database.get_ref())
+            .await?;
+
+        Ok((cancelled, job))
+    }
+    .await;
+
+    match result {
+        Ok((true, Some(job))) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("job.cancel", vec![job.id.clone()])
+                    .after(json!({ "kind": job.kind, "processed_items": job.processed_items })),
+            )
+            .await;
+
+            HttpResponse::Ok().json(job_response(job))
+        }
+        // Already finished, the caller gets the final state back.
+        Ok((false, Some(job))) => HttpResponse::Conflict().json(job_response(job)),
+        Ok((_, None)) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to cancel the job: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
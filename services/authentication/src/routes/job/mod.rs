@@ -0,0 +1,3 @@
+pub mod submit;
+pub mod status;
+pub mod cancel;
@@ -0,0 +1,28 @@
+use crate::jobs::job_response;
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::job;
+use common::entities::prelude::Job;
+use common::types::file::job::JobRequest;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+#[post("status")]
+pub async fn status(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<JobRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    match Job::find()
+        .filter(job::Column::Id.eq(payload.job_id.clone()))
+        .filter(job::Column::SubmittedBy.eq(authenticated_user.id.clone()))
+        .one(database.get_ref())
+        .await
+    {
+        Ok(Some(job)) => HttpResponse::Ok().json(job_response(job)),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to fetch the job: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::jobs::{job_response, JobQueue};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::types::file::job::JobOperation;
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+
+#[post("submit")]
+pub async fn submit(
+    database: web::Data<DatabaseConnection>,
+    queue: web::Data<JobQueue>,
+    payload: web::Json<JobOperation>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    match queue.submit(&database, &authenticated_user.id, &authenticated_user.id, &payload).await {
+        Ok(job) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("job.submit", vec![job.id.clone()])
+                    .after(json!({ "kind": job.kind, "payload": job.payload })),
+            )
+            .await;
+
+            HttpResponse::Accepted().json(job_response(job))
+        }
+        Err(err) => {
+            log::error!("Failed to queue the job: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
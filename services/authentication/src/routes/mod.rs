@@ -1,11 +1,19 @@
 use actix_web::middleware::from_fn;
 use crate::routes::file::*;
+use crate::routes::job::*;
 use crate::routes::user::*;
 use actix_web::web;
 use crate::middleware::middleware::reject_bypassed_traffic;
 
+pub mod audit;
 pub mod file;
+pub mod grant;
+pub mod job;
+pub mod outbox;
+pub mod request;
+pub mod share;
 pub mod user;
+pub mod webhook;
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -29,7 +37,67 @@ pub fn routes(cfg: &mut web::ServiceConfig) {
                     )
                     .service(metadata::metadata)
                     .service(r#move::r#move)
-                    .service(rename::rename),
+                    .service(rename::rename)
+                    .service(resolve::resolve)
+                    .service(file::share::share)
+                    .service(changes::changes)
+                    .service(events::events)
+                    .service(shared::shared),
+            )
+            .service(
+                web::scope("/grant")
+                    .service(grant::create::create)
+                    .service(grant::list::list)
+                    .service(grant::delete::delete),
+            )
+            .service(
+                web::scope("/request")
+                    .service(request::create::create)
+                    .service(request::list::list)
+                    .service(request::delete::delete)
+                    .service(request::uploads::uploads)
+                    .service(request::info::info)
+                    .service(
+                        web::scope("/upload")
+                            .service(request::upload::init)
+                            .service(request::complete::complete),
+                    ),
+            )
+            .service(web::scope("/audit").service(audit::list::list))
+            .service(
+                web::scope("/share")
+                    .service(share::save::save)
+                    .service(share::resolve::resolve)
+                    .service(share::consume::consume)
+                    .service(share::list::list)
+                    .service(share::revoke::revoke)
+                    .service(share::browse::browse)
+                    .service(share::entry::entry)
+                    .service(share::explode::explode)
+                    .service(share::record::record)
+                    .service(share::access::access)
+                    .service(share::collection::collection)
+                    .service(share::collection_zip::collection_zip)
+                    .service(share::items::items),
+            )
+            .service(
+                web::scope("/webhook")
+                    .service(webhook::create::create)
+                    .service(webhook::list::list)
+                    .service(webhook::delete::delete)
+                    .service(webhook::deliveries::deliveries)
+                    .service(webhook::enable::enable),
+            )
+            .service(
+                web::scope("/outbox")
+                    .service(outbox::dead::dead)
+                    .service(outbox::retry::retry),
+            )
+            .service(
+                web::scope("/job")
+                    .service(submit::submit)
+                    .service(status::status)
+                    .service(cancel::cancel),
             )
             .service(
                 web::scope("/user")
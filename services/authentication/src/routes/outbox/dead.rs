@@ -0,0 +1,56 @@
+use crate::outbox::STATUS_DEAD;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::StorageOutbox;
+use common::entities::storage_outbox;
+use common::types::outbox::dead_letter::{DeadLetterElement, DeadLetterListRequest, DeadLetterListResponse};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+/// Dead-lettered storage operations, most recently failed first.
+#[post("dead")]
+pub async fn dead(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<DeadLetterListRequest>,
+) -> impl Responder {
+    let limit = payload.limit.unwrap_or(50).min(200) as u64;
+    let offset = payload.offset.unwrap_or(0) as u64;
+
+    let mut query = StorageOutbox::find().filter(storage_outbox::Column::Status.eq(STATUS_DEAD));
+
+    if let Some(owner_id) = &payload.owner_id {
+        query = query.filter(storage_outbox::Column::OwnerId.eq(owner_id.clone()));
+    }
+
+    let mut entries = match query
+        .order_by_desc(storage_outbox::Column::UpdatedAt)
+        .limit(limit + 1)
+        .offset(offset)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(entries) => entries,
+        Err(err) => {
+            log::error!("Error fetching dead-lettered storage operations: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let has_more = entries.len() as u64 > limit;
+    if has_more { entries.pop(); }
+
+    HttpResponse::Ok().json(DeadLetterListResponse {
+        entries: entries
+            .into_iter()
+            .map(|entry| DeadLetterElement {
+                id: entry.id,
+                owner_id: entry.owner_id,
+                operation: entry.operation,
+                payload: entry.payload,
+                attempts: entry.attempts,
+                last_error: entry.last_error,
+                created_at: entry.created_at,
+                updated_at: entry.updated_at,
+            })
+            .collect(),
+        has_more,
+    })
+}
@@ -0,0 +1,5 @@
+//! Operator routes for storage operations that ran out of attempts. The edge doesn't expose
+//! them, they are reached directly with the origin secret.
+
+pub mod dead;
+pub mod retry;
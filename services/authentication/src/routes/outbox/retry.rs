@@ -0,0 +1,35 @@
+use crate::outbox::{STATUS_DEAD, STATUS_PENDING};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::StorageOutbox;
+use common::entities::storage_outbox;
+use common::types::outbox::dead_letter::{DeadLetterRetryRequest, DeadLetterRetryResponse};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Puts dead-lettered operations back in line with a fresh set of attempts. Their last error
+/// stays until the next attempt replaces it.
+#[post("retry")]
+pub async fn retry(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<DeadLetterRetryRequest>,
+) -> impl Responder {
+    let result = StorageOutbox::update_many()
+        .col_expr(storage_outbox::Column::Status, Expr::value(STATUS_PENDING))
+        .col_expr(storage_outbox::Column::Attempts, Expr::value(0))
+        .col_expr(storage_outbox::Column::NextAttemptAt, Expr::current_timestamp())
+        .col_expr(storage_outbox::Column::UpdatedAt, Expr::current_timestamp())
+        .filter(storage_outbox::Column::Id.is_in(payload.ids.clone()))
+        .filter(storage_outbox::Column::Status.eq(STATUS_DEAD))
+        .exec(database.get_ref())
+        .await;
+
+    match result {
+        Ok(result) => HttpResponse::Ok().json(DeadLetterRetryResponse {
+            retried: result.rows_affected,
+        }),
+        Err(err) => {
+            log::error!("Failed to retry storage operations: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
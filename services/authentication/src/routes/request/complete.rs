@@ -0,0 +1,128 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::ClientContext;
+use crate::outbox::enqueue_delete;
+use crate::routes::file::upload::{complete_object, mark_completed};
+use super::rejected;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, FileRequest, FileRequestUpload};
+use common::entities::{file, file_request, file_request_upload};
+use common::types::file::file_request::FileRequestCompleteRequest;
+use sea_orm::sea_query::{Expr, ExprTrait};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionTrait};
+use serde_json::json;
+use storage::s3_manager::S3StorageManager;
+use storage::s3_scoped_storage::S3ScopedStorage;
+use storage::StorageBackend;
+
+/// Finishes an anonymous upload. An upload started before the link expired may still finish.
+///
+/// The size given at init is only the visitor's word, so the stored object is measured and an
+/// oversized one is thrown away and its slot given back.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("complete")]
+pub async fn complete(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<FileRequestCompleteRequest>,
+    s3_scoped_storage: web::Data<S3StorageManager>,
+    client: ClientContext,
+) -> impl Responder {
+    let found: Result<Option<(file_request::Model, file::Model)>, DbErr> = async {
+        let Some(upload) = FileRequestUpload::find_by_id(payload.file_id.clone())
+            .filter(file_request_upload::Column::Token.eq(payload.token.clone()))
+            .one(database.get_ref())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let Some(request) = FileRequest::find_by_id(payload.token.clone()).one(database.get_ref()).await? else {
+            return Ok(None);
+        };
+
+        let pending = File::find_by_id(upload.file_id)
+            .filter(file::Column::OwnerId.eq(request.owner_id.clone()))
+            .filter(file::Column::UploadCompleted.eq(false))
+            .one(database.get_ref())
+            .await?;
+
+        Ok(pending.map(|pending| (request, pending)))
+    }
+    .await;
+
+    let (request, pending) = match found {
+        Ok(Some(found)) => found,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to look up the upload for file request {}: {:?}", payload.token, err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let storage = S3ScopedStorage {
+        user_id: request.owner_id.clone(),
+        bucket: s3_scoped_storage.bucket.clone(),
+        client: s3_scoped_storage.client.clone(),
+    };
+
+    if let Err(response) = complete_object(&storage, &payload.file_id, &payload.upload_id, &payload.parts).await {
+        return response;
+    }
+
+    let file_size = match storage.object_size(&payload.file_id).await {
+        Ok(file_size) => file_size,
+        Err(err) => {
+            log::error!("Failed to measure upload {}: {:?}", payload.file_id, err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if request.max_file_size.is_some_and(|max| file_size > max) {
+        let released: Result<(), DbErr> = async {
+            let transaction = database.begin().await?;
+
+            File::delete_by_id(pending.id.clone()).exec(&transaction).await?;
+            FileRequest::update_many()
+                .col_expr(file_request::Column::UploadCount, Expr::col(file_request::Column::UploadCount).sub(1))
+                .filter(file_request::Column::Token.eq(request.token.clone()))
+                .filter(file_request::Column::UploadCount.gt(0))
+                .exec(&transaction)
+                .await?;
+            enqueue_delete(&transaction, &request.owner_id, vec![payload.file_id.clone()]).await?;
+
+            transaction.commit().await
+        }
+        .await;
+
+        if let Err(err) = released {
+            log::error!("Failed to release oversized upload {}: {:?}", payload.file_id, err);
+        }
+
+        return rejected("too_large");
+    }
+
+    if let Err(err) = mark_completed(
+        database.get_ref(),
+        &request.owner_id,
+        &request.owner_id,
+        &client,
+        &pending.id,
+        Some(file_size),
+    )
+    .await
+    {
+        log::error!("Failed to update file records: {}", err);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    record_logged(
+        database.get_ref(),
+        &request.owner_id,
+        &client,
+        AuditEvent::new("request.upload.complete", vec![pending.id.clone()])
+            .after(json!({ "token": request.token, "file_size": file_size })),
+    )
+    .await;
+
+    HttpResponse::Ok().finish()
+}
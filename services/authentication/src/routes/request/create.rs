@@ -0,0 +1,112 @@
+use crate::access::{authorize, Role};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file_request;
+use common::entities::prelude::{File, FileRequest};
+use common::types::file::file_request::{CreateFileRequestRequest, FileRequestElement};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use serde_json::json;
+
+/// Opens an upload-only link into one of the caller's folders. Requests are the owner's to make,
+/// uploads through them land in the owner's storage.
+#[post("create")]
+pub async fn create(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<CreateFileRequestRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    if payload.folder_id.is_empty() {
+        return HttpResponse::BadRequest().json("Pick a folder for the uploads");
+    }
+
+    if let Err(response) = authorize(database.get_ref(), &authenticated_user.id, &payload.folder_id, Role::Owner).await {
+        return response;
+    }
+
+    let folder = match File::find_by_id(payload.folder_id.clone()).one(database.get_ref()).await {
+        Ok(Some(folder)) if folder.is_directory => folder,
+        Ok(Some(_)) => return HttpResponse::BadRequest().json("Uploads can only go into a folder"),
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to look up the folder: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let expires_at = match payload.expires_in {
+        Some(seconds) => Some(DateTimeWithTimeZone::from(
+            now + chrono::Duration::seconds(i64::try_from(seconds).unwrap_or(i64::MAX).min(i32::MAX as i64)),
+        )),
+        None => payload.expires_at,
+    };
+
+    let allowed_types = payload.allowed_types.as_ref().map(|types| {
+        types
+            .iter()
+            .map(|pattern| pattern.trim().to_lowercase())
+            .filter(|pattern| !pattern.is_empty())
+            .collect::<Vec<_>>()
+    });
+
+    let now = DateTimeWithTimeZone::from(now);
+    let request = file_request::Model {
+        token: uuid::Uuid::new_v4().simple().to_string(),
+        owner_id: authenticated_user.id.clone(),
+        folder_id: folder.id.clone(),
+        title: payload.title.clone().filter(|title| !title.trim().is_empty()),
+        max_file_size: payload.max_file_size.map(|max| i64::try_from(max).unwrap_or(i64::MAX)),
+        max_files: payload.max_files.map(|max| i32::try_from(max).unwrap_or(i32::MAX)),
+        allowed_types: allowed_types.clone().map(|types| json!(types)),
+        upload_count: 0,
+        expires_at,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let inserted = FileRequest::insert(file_request::ActiveModel {
+        token: Set(request.token.clone()),
+        owner_id: Set(request.owner_id.clone()),
+        folder_id: Set(request.folder_id.clone()),
+        title: Set(request.title.clone()),
+        max_file_size: Set(request.max_file_size),
+        max_files: Set(request.max_files),
+        allowed_types: Set(request.allowed_types.clone()),
+        upload_count: Set(0),
+        expires_at: Set(request.expires_at),
+        created_at: Set(now),
+        updated_at: Set(now),
+    })
+    .exec_without_returning(database.get_ref())
+    .await;
+
+    if let Err(err) = inserted {
+        log::error!("Failed to save the file request: {:?}", err);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    record_logged(
+        database.get_ref(),
+        &authenticated_user.id,
+        &client,
+        AuditEvent::new("request.create", vec![folder.id.clone()])
+            .after(json!({ "token": request.token, "expires_at": request.expires_at })),
+    )
+    .await;
+
+    HttpResponse::Ok().json(FileRequestElement {
+        token: request.token,
+        folder_id: folder.id,
+        folder_name: folder.file_name,
+        title: request.title,
+        max_file_size: payload.max_file_size,
+        max_files: payload.max_files,
+        allowed_types,
+        upload_count: 0,
+        expires_at: request.expires_at,
+        created_at: now,
+    })
+}
@@ -0,0 +1,45 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{delete, web, HttpResponse, Responder};
+use common::entities::file_request;
+use common::entities::prelude::FileRequest;
+use common::types::file::file_request::FileRequestTokenRequest;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde_json::json;
+
+/// Closes a file request. Files already sent through it stay, along with who sent them.
+#[delete("delete")]
+pub async fn delete(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<FileRequestTokenRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    match FileRequest::delete_many()
+        .filter(file_request::Column::Token.eq(payload.token.clone()))
+        .filter(file_request::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .exec_with_returning(database.get_ref())
+        .await
+    {
+        Ok(deleted) if deleted.is_empty() => HttpResponse::NotFound().finish(),
+        Ok(deleted) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new(
+                    "request.delete",
+                    deleted.iter().map(|request| request.folder_id.clone()).collect(),
+                )
+                .before(json!({ "token": payload.token })),
+            )
+            .await;
+
+            HttpResponse::Ok().finish()
+        }
+        Err(err) => {
+            log::error!("Failed to delete the file request: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
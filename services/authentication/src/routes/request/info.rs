@@ -0,0 +1,48 @@
+use super::{allowed_types, open_request};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, User};
+use common::types::file::file_request::{FileRequestInfoResponse, FileRequestTokenRequest};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+
+/// What the upload page shows an anonymous visitor: who is asking, for what, and within which
+/// limits. The folder's contents stay hidden.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("info")]
+pub async fn info(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<FileRequestTokenRequest>,
+) -> impl Responder {
+    let request = match open_request(database.get_ref(), &payload.token).await {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    let details: Result<Option<(String, String)>, DbErr> = async {
+        let Some(folder) = File::find_by_id(request.folder_id.clone()).one(database.get_ref()).await? else {
+            return Ok(None);
+        };
+        let owner = User::find_by_id(request.owner_id.clone()).one(database.get_ref()).await?;
+        Ok(owner.map(|owner| (folder.file_name, owner.username)))
+    }
+    .await;
+
+    match details {
+        Ok(Some((folder_name, owner))) => HttpResponse::Ok().json(FileRequestInfoResponse {
+            allowed_types: allowed_types(&request),
+            title: request.title,
+            folder_name,
+            owner,
+            max_file_size: request.max_file_size.map(|max| u64::try_from(max).unwrap_or(0)),
+            uploads_remaining: request
+                .max_files
+                .map(|max| u32::try_from(max - request.upload_count).unwrap_or(0)),
+            expires_at: request.expires_at,
+        }),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to describe file request {}: {:?}", payload.token, err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
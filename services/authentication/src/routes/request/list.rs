@@ -0,0 +1,47 @@
+use super::allowed_types;
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file_request;
+use common::entities::prelude::{File, FileRequest};
+use common::types::file::file_request::{FileRequestElement, ListFileRequestsResponse};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+/// The caller's file requests, newest first. Expired ones stay listed so they can be cleaned up.
+#[post("list")]
+pub async fn list(
+    database: web::Data<DatabaseConnection>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    match FileRequest::find()
+        .find_also_related(File)
+        .filter(file_request::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .order_by_desc(file_request::Column::CreatedAt)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(requests) => HttpResponse::Ok().json(ListFileRequestsResponse {
+            requests: requests
+                .into_iter()
+                .filter_map(|(request, folder)| {
+                    let folder = folder?;
+                    Some(FileRequestElement {
+                        allowed_types: allowed_types(&request),
+                        token: request.token,
+                        folder_id: request.folder_id,
+                        folder_name: folder.file_name,
+                        title: request.title,
+                        max_file_size: request.max_file_size.map(|max| u64::try_from(max).unwrap_or(0)),
+                        max_files: request.max_files.map(|max| u32::try_from(max).unwrap_or(0)),
+                        upload_count: u32::try_from(request.upload_count).unwrap_or(0),
+                        expires_at: request.expires_at,
+                        created_at: request.created_at,
+                    })
+                })
+                .collect(),
+        }),
+        Err(err) => {
+            log::error!("Failed to list file requests: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,67 @@
+pub mod complete;
+pub mod create;
+pub mod delete;
+pub mod info;
+pub mod list;
+pub mod upload;
+pub mod uploads;
+
+use actix_web::HttpResponse;
+use common::entities::file_request;
+use common::entities::prelude::FileRequest;
+use common::types::file::file_request::FileRequestRejectedResponse;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+/// Answer for an anonymous upload the link doesn't take.
+pub fn rejected(error: &str) -> HttpResponse {
+    let body = FileRequestRejectedResponse { error: error.to_string() };
+    match error {
+        "too_large" => HttpResponse::PayloadTooLarge().json(body),
+        "type_not_allowed" => HttpResponse::UnsupportedMediaType().json(body),
+        _ => HttpResponse::Gone().json(body),
+    }
+}
+
+/// The request behind a public token. Answers 404 for a token that isn't on record and 410 once
+/// it has expired.
+pub async fn open_request(database: &DatabaseConnection, token: &str) -> Result<file_request::Model, HttpResponse> {
+    match FileRequest::find_by_id(token).one(database).await {
+        Ok(Some(request)) if request.expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now()) => {
+            Err(rejected("expired"))
+        }
+        Ok(Some(request)) => Ok(request),
+        Ok(None) => Err(HttpResponse::NotFound().finish()),
+        Err(err) => {
+            log::error!("Failed to look up file request {}: {:?}", token, err);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// The allowed types of a request, `None` when anything goes.
+pub fn allowed_types(request: &file_request::Model) -> Option<Vec<String>> {
+    request
+        .allowed_types
+        .clone()
+        .and_then(|types| serde_json::from_value(types).ok())
+}
+
+/// Whether a file matches one of the patterns: an exact MIME type, a family such as `image/*`,
+/// or an extension such as `.pdf`.
+pub fn type_allowed(allowed: &[String], filename: &str, content_type: &str) -> bool {
+    let filename = filename.to_lowercase();
+    let content_type = content_type.to_lowercase();
+
+    allowed.iter().any(|pattern| {
+        let pattern = pattern.trim().to_lowercase();
+        if pattern.starts_with('.') {
+            filename.ends_with(&pattern)
+        } else if let Some(family) = pattern.strip_suffix("/*") {
+            content_type
+                .split_once('/')
+                .is_some_and(|(kind, _)| kind == family)
+        } else {
+            content_type == pattern
+        }
+    })
+}
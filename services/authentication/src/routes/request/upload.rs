@@ -0,0 +1,162 @@
+use super::{allowed_types, open_request, rejected, type_allowed};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::ClientContext;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, FileRequestUpload};
+use common::entities::{file, file_request_upload};
+use common::types::file::file_request::FileRequestUploadInternalRequest;
+use common::types::file::upload_init::InitUploadInternalResponse;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, Set, Statement, TransactionTrait, Value};
+use serde_json::json;
+use storage::s3_manager::S3StorageManager;
+use storage::s3_scoped_storage::S3ScopedStorage;
+use storage::StorageBackend;
+
+/// Starts an anonymous upload into the request's folder. The file belongs to the owner from the
+/// start, the visitor only leaves their name behind.
+///
+/// The slot is taken with a conditional update, so concurrent uploads can't go past
+/// `max_files`. Uploads that never complete keep their slot.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("init")]
+pub async fn init(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<FileRequestUploadInternalRequest>,
+    s3_scoped_storage: web::Data<S3StorageManager>,
+    client: ClientContext,
+) -> impl Responder {
+    let uploader_name = payload.uploader_name.trim();
+    if uploader_name.is_empty() || uploader_name.chars().count() > 200 {
+        return HttpResponse::BadRequest().json("Tell the owner who you are, in at most 200 characters");
+    }
+
+    let uploader_email = payload
+        .uploader_email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| !email.is_empty());
+    if uploader_email.is_some_and(|email| !email.contains('@') || email.len() > 320) {
+        return HttpResponse::BadRequest().json("That email address doesn't look right");
+    }
+
+    let request = match open_request(database.get_ref(), &payload.token).await {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    if request
+        .max_file_size
+        .is_some_and(|max| payload.size > u64::try_from(max).unwrap_or(0))
+    {
+        return rejected("too_large");
+    }
+
+    if let Some(allowed) = allowed_types(&request)
+        && !type_allowed(&allowed, &payload.filename, &payload.content_type)
+    {
+        return rejected("type_not_allowed");
+    }
+
+    let storage = S3ScopedStorage {
+        user_id: request.owner_id.clone(),
+        bucket: s3_scoped_storage.bucket.clone(),
+        client: s3_scoped_storage.client.clone(),
+    };
+
+    let result: Result<Option<String>, anyhow::Error> = async {
+        let transaction = database.begin().await?;
+
+        // language=PostgreSQL
+        let sql = r#"
+            UPDATE file_request
+            SET upload_count = upload_count + 1, updated_at = now()
+            WHERE token = $1
+              AND (max_files IS NULL OR upload_count < max_files)
+              AND (expires_at IS NULL OR expires_at > now())
+            RETURNING token;
+        "#;
+
+        let reserved = transaction
+            .query_one_raw(Statement::from_sql_and_values(
+                transaction.get_database_backend(),
+                sql,
+                [Value::from(request.token.clone())],
+            ))
+            .await?;
+
+        if reserved.is_none() {
+            return Ok(None);
+        }
+
+        let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+        File::insert(file::ActiveModel {
+            id: Set(payload.file_id.clone()),
+            file_name: Set(payload.filename.clone()),
+            owner_id: Set(request.owner_id.clone()),
+            created_at: Set(now),
+            upload_completed: Set(false),
+            file_type: Set(payload.content_type.clone()),
+            file_size: Set(i64::try_from(payload.size).unwrap_or(i64::MAX)),
+            path: Set(request.folder_id.clone()),
+            is_directory: Set(false),
+            item_count: Set(0),
+        })
+        .exec_without_returning(&transaction)
+        .await?;
+
+        FileRequestUpload::insert(file_request_upload::ActiveModel {
+            file_id: Set(payload.file_id.clone()),
+            token: Set(Some(request.token.clone())),
+            owner_id: Set(request.owner_id.clone()),
+            uploader_name: Set(uploader_name.to_string()),
+            uploader_email: Set(uploader_email.map(str::to_string)),
+            created_at: Set(now),
+        })
+        .exec_without_returning(&transaction)
+        .await?;
+
+        let upload_id = storage.create_upload(&payload.file_id).await?;
+
+        transaction.commit().await?;
+
+        Ok(Some(upload_id))
+    }
+    .await;
+
+    match result {
+        Ok(Some(upload_id)) => {
+            record_logged(
+                database.get_ref(),
+                &request.owner_id,
+                &client,
+                AuditEvent::new("request.upload.init", vec![payload.file_id.clone()]).after(json!({
+                    "token": request.token,
+                    "file_name": payload.filename,
+                    "file_size": payload.size,
+                    "file_type": payload.content_type,
+                    "path": request.folder_id,
+                    "uploader_name": uploader_name,
+                    "uploader_email": uploader_email,
+                })),
+            )
+            .await;
+
+            HttpResponse::Ok().json(InitUploadInternalResponse {
+                upload_id,
+                owner_id: request.owner_id,
+            })
+        }
+        Ok(None) => rejected(if request.expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now()) {
+            "expired"
+        } else {
+            "full"
+        }),
+        Err(err) => {
+            log::error!("Failed to start an upload for file request {}: {:?}", request.token, err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
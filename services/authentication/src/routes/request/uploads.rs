@@ -0,0 +1,45 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file_request_upload;
+use common::entities::prelude::{File, FileRequestUpload};
+use common::types::file::file_request::{FileRequestTokenRequest, FileRequestUploadElement, FileRequestUploadsResponse};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+/// What came in through one of the caller's file requests and who sent it, newest first.
+#[post("uploads")]
+pub async fn uploads(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<FileRequestTokenRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    match FileRequestUpload::find()
+        .find_also_related(File)
+        .filter(file_request_upload::Column::Token.eq(payload.token.clone()))
+        .filter(file_request_upload::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .order_by_desc(file_request_upload::Column::CreatedAt)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(uploads) => HttpResponse::Ok().json(FileRequestUploadsResponse {
+            uploads: uploads
+                .into_iter()
+                .filter_map(|(upload, uploaded)| {
+                    let uploaded = uploaded?;
+                    Some(FileRequestUploadElement {
+                        file_id: upload.file_id,
+                        file_name: uploaded.file_name,
+                        file_size: uploaded.file_size,
+                        upload_completed: uploaded.upload_completed,
+                        uploader_name: upload.uploader_name,
+                        uploader_email: upload.uploader_email,
+                        created_at: upload.created_at,
+                    })
+                })
+                .collect(),
+        }),
+        Err(err) => {
+            log::error!("Failed to list file request uploads: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
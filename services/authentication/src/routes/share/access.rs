@@ -0,0 +1,137 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{Share, ShareAccess};
+use common::entities::{share, share_access};
+use common::types::file::share_access::{
+    ShareAccessCount, ShareAccessElement, ShareAccessRequest, ShareAccessResponse, ShareAccessSummary,
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Statement, Value,
+};
+
+/// The history of one of the caller's links, newest first, with totals over all of it. Links
+/// revoked since keep their history.
+#[post("access")]
+pub async fn access(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareAccessRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let limit = payload.limit.unwrap_or(50).min(200) as u64;
+    let offset = payload.offset.unwrap_or(0) as u64;
+
+    let result: Result<Option<ShareAccessResponse>, DbErr> = async {
+        let summary = summarize(database.get_ref(), &payload.token, &authenticated_user.id).await?;
+
+        if summary.visits == 0 {
+            let owned = Share::find()
+                .filter(share::Column::Token.eq(payload.token.clone()))
+                .filter(share::Column::OwnerId.eq(authenticated_user.id.clone()))
+                .count(database.get_ref())
+                .await?;
+
+            if owned == 0 {
+                return Ok(None);
+            }
+        }
+
+        let mut entries = ShareAccess::find()
+            .filter(share_access::Column::Token.eq(payload.token.clone()))
+            .filter(share_access::Column::OwnerId.eq(authenticated_user.id.clone()))
+            .order_by_desc(share_access::Column::Id)
+            .limit(limit + 1)
+            .offset(offset)
+            .all(database.get_ref())
+            .await?;
+
+        let has_more = entries.len() as u64 > limit;
+        if has_more { entries.pop(); }
+
+        Ok(Some(ShareAccessResponse {
+            summary,
+            entries: entries
+                .into_iter()
+                .map(|entry| ShareAccessElement {
+                    id: entry.id,
+                    file_id: entry.file_id,
+                    action: entry.action,
+                    outcome: entry.outcome,
+                    country: entry.country,
+                    asn: entry.asn.and_then(|asn| u32::try_from(asn).ok()),
+                    as_organization: entry.as_organization,
+                    user_agent: entry.user_agent,
+                    created_at: entry.created_at,
+                })
+                .collect(),
+            has_more,
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(Some(response)) => HttpResponse::Ok().json(response),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to load the access history of share {}: {:?}", payload.token, err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+async fn summarize(database: &DatabaseConnection, token: &str, owner_id: &str) -> Result<ShareAccessSummary, DbErr> {
+    let values = [Value::from(token), Value::from(owner_id)];
+
+    // language=PostgreSQL
+    let totals_sql = r#"
+        SELECT
+            count(*) AS visits,
+            count(*) FILTER (WHERE outcome = 'ok' AND action IN ('download', 'zip')) AS downloads,
+            count(*) FILTER (WHERE outcome <> 'ok') AS denied,
+            min(created_at) FILTER (WHERE outcome = 'ok' AND action IN ('download', 'zip')) AS first_download_at,
+            max(created_at) FILTER (WHERE outcome = 'ok' AND action IN ('download', 'zip')) AS last_download_at
+        FROM share_access
+        WHERE token = $1 AND owner_id = $2;
+    "#;
+
+    // language=PostgreSQL
+    let countries_sql = r#"
+        SELECT country, count(*) AS count
+        FROM share_access
+        WHERE token = $1 AND owner_id = $2
+        GROUP BY country
+        ORDER BY count DESC, country;
+    "#;
+
+    let backend = database.get_database_backend();
+
+    let totals = database
+        .query_one_raw(Statement::from_sql_and_values(backend, totals_sql, values.clone()))
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("share_access totals".to_string()))?;
+
+    let countries = database
+        .query_all_raw(Statement::from_sql_and_values(backend, countries_sql, values))
+        .await?;
+
+    let count = |column: &str| -> Result<u64, DbErr> {
+        Ok(u64::try_from(totals.try_get::<i64>("", column)?).unwrap_or(0))
+    };
+
+    Ok(ShareAccessSummary {
+        visits: count("visits")?,
+        downloads: count("downloads")?,
+        denied: count("denied")?,
+        first_download_at: totals.try_get("", "first_download_at")?,
+        last_download_at: totals.try_get("", "last_download_at")?,
+        countries: countries
+            .iter()
+            .map(|row| {
+                Ok(ShareAccessCount {
+                    country: row.try_get("", "country")?,
+                    count: u64::try_from(row.try_get::<i64>("", "count")?).unwrap_or(0),
+                })
+            })
+            .collect::<Result<_, DbErr>>()?,
+    })
+}
@@ -0,0 +1,85 @@
+use crate::routes::file::tree::FileTreeExtension;
+use crate::routes::share::shared_root;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file;
+use common::entities::prelude::File;
+use common::types::file::list::{ListFileElement, ListFilesResponse};
+use common::types::file::share_folder::SharedBrowseRequest;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect};
+
+/// One page of a directory inside a shared folder. Breadcrumbs start at the shared root, the
+/// recipient never learns what sits above it.
+#[post("browse")]
+pub async fn browse(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<SharedBrowseRequest>,
+) -> impl Responder {
+    let limit = payload.limit.unwrap_or(50).min(200) as u64;
+    let offset = payload.offset.unwrap_or(0) as u64;
+
+    let result: Result<Option<ListFilesResponse>, DbErr> = async {
+        let Some(root) = shared_root(&database, &payload.root_id).await? else {
+            return Ok(None);
+        };
+
+        let directory_id = payload.directory_id.clone().unwrap_or_else(|| root.id.clone());
+
+        if !database.is_within(&root.owner_id, &root.id, &directory_id).await? {
+            return Ok(None);
+        }
+
+        let mut files = File::find()
+            .filter(file::Column::OwnerId.eq(root.owner_id.clone()))
+            .filter(file::Column::Path.eq(directory_id.clone()))
+            .filter(
+                Condition::any()
+                    .add(file::Column::IsDirectory.eq(true))
+                    .add(file::Column::UploadCompleted.eq(true)),
+            )
+            .order_by(file::Column::IsDirectory, Order::Desc)
+            .order_by(file::Column::FileName, Order::Asc)
+            .limit(limit + 1)
+            .offset(offset)
+            .all(database.get_ref())
+            .await?;
+
+        let has_more = files.len() as u64 > limit;
+        if has_more { files.pop(); }
+
+        let breadcrumbs = database
+            .breadcrumbs(&root.owner_id, &directory_id)
+            .await?
+            .into_iter()
+            .skip_while(|crumb| crumb.id != root.id)
+            .collect();
+
+        Ok(Some(ListFilesResponse {
+            breadcrumbs,
+            files: files
+                .into_iter()
+                .map(|v| ListFileElement {
+                    id: v.id,
+                    file_name: v.file_name,
+                    file_size: v.file_size,
+                    file_type: v.file_type,
+                    created_at: v.created_at,
+                    path: v.path,
+                    upload_completed: v.upload_completed,
+                    is_directory: v.is_directory,
+                    item_count: v.item_count,
+                })
+                .collect(),
+            has_more,
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(Some(response)) => HttpResponse::Ok().json(response),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to list the shared folder: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, Share, ShareItem};
+use common::entities::{file, share, share_item};
+use common::types::file::share_collection::ShareCollectionSaveRequest;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, Set, TransactionTrait};
+use serde_json::json;
+
+/// Files a single collection link may carry.
+const MAX_COLLECTION_ITEMS: usize = 500;
+
+/// Records a collection issued by the edge. Every file has to be one of the caller's own
+/// completed uploads, folders are shared through their own links.
+#[post("collection")]
+pub async fn collection(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareCollectionSaveRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    let mut file_ids: Vec<String> = Vec::with_capacity(payload.file_ids.len());
+    for file_id in &payload.file_ids {
+        if !file_ids.contains(file_id) {
+            file_ids.push(file_id.clone());
+        }
+    }
+
+    if file_ids.is_empty() || file_ids.len() > MAX_COLLECTION_ITEMS {
+        return HttpResponse::BadRequest().json(format!(
+            "A collection holds between 1 and {} files",
+            MAX_COLLECTION_ITEMS
+        ));
+    }
+
+    let result: Result<bool, DbErr> = async {
+        let owned = File::find()
+            .filter(file::Column::Id.is_in(file_ids.clone()))
+            .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
+            .filter(file::Column::IsDirectory.eq(false))
+            .filter(file::Column::UploadCompleted.eq(true))
+            .count(database.get_ref())
+            .await?;
+
+        if owned != file_ids.len() as u64 {
+            return Ok(false);
+        }
+
+        let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+        let transaction = database.begin().await?;
+
+        Share::insert(share::ActiveModel {
+            token: Set(payload.token.clone()),
+            owner_id: Set(authenticated_user.id.clone()),
+            file_id: Set(None),
+            title: Set(payload
+                .title
+                .as_deref()
+                .map(str::trim)
+                .filter(|title| !title.is_empty())
+                .map(|title| title.chars().take(200).collect())),
+            password_hash: Set(payload.password_hash.clone()),
+            expires_at: Set(payload.expires_at),
+            max_downloads: Set(payload.max_downloads.map(|max| i32::try_from(max).unwrap_or(i32::MAX))),
+            download_count: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+            revoked_at: Set(None),
+        })
+        .exec_without_returning(&transaction)
+        .await?;
+
+        ShareItem::insert_many(file_ids.iter().enumerate().map(|(position, file_id)| share_item::ActiveModel {
+            token: Set(payload.token.clone()),
+            file_id: Set(file_id.clone()),
+            position: Set(position as i32),
+        }))
+        .exec_without_returning(&transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("share.create", file_ids)
+                    .after(json!({ "token": payload.token, "collection": true })),
+            )
+            .await;
+
+            HttpResponse::Ok().finish()
+        }
+        Ok(false) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to save the collection: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
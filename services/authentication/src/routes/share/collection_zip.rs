@@ -0,0 +1,71 @@
+use crate::routes::file::explode::explode_items;
+use crate::routes::share::collection_items;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::types::file::share_collection::SharedCollectionZipRequest;
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use storage::s3_manager::S3StorageManager;
+
+/// The zip listing for files of a collection. They come from different folders, so clashing
+/// names are numbered the way a desktop would, `report (2).pdf`.
+#[post("collection/zip")]
+pub async fn collection_zip(
+    database: web::Data<DatabaseConnection>,
+    s3_client: web::Data<S3StorageManager>,
+    payload: web::Json<SharedCollectionZipRequest>,
+) -> impl Responder {
+    let (collection, items) = match collection_items(&database, &payload.token).await {
+        Ok(Some(found)) => found,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to look up the collection: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let item_ids = items.into_iter().map(|item| item.id).collect::<Vec<_>>();
+
+    let item_ids = if payload.item_ids.is_empty() {
+        item_ids
+    } else if payload.item_ids.iter().all(|id| item_ids.contains(id)) {
+        payload.item_ids.clone()
+    } else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let mut response = match explode_items(&database, &s3_client, &collection.owner_id, &item_ids).await {
+        Ok(response) => response,
+        Err(err) => {
+            log::error!("Failed to explode the collection: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    // Keep the order the owner picked, the tree query doesn't.
+    response
+        .items
+        .sort_by_key(|item| item_ids.iter().position(|id| id == &item.id));
+
+    let mut taken = HashSet::new();
+    for item in &mut response.items {
+        item.virtual_path = unique_name(&item.virtual_path, &mut taken);
+    }
+
+    HttpResponse::Ok().json(response)
+}
+
+fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    if taken.insert(name.to_lowercase()) {
+        return name.to_string();
+    }
+
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+
+    (2..)
+        .map(|n| format!("{} ({}){}", stem, n, extension))
+        .find(|candidate| taken.insert(candidate.to_lowercase()))
+        .unwrap_or_else(|| name.to_string())
+}
@@ -0,0 +1,62 @@
+use crate::routes::share::revoked_response;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::Share;
+use common::entities::share;
+use common::types::file::share::{ShareConsumeResponse, ShareTokenRequest};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Statement, Value};
+
+/// Counts one download of a share token. The check and the increment are a single conditional
+/// update, so concurrent downloads can never go past a limit. Answers 410 once the limit is used
+/// up, the link has expired or it was revoked, with a `ShareRevokedResponse` for the latter,
+/// and 404 for a token that isn't on record.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("consume")]
+pub async fn consume(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareTokenRequest>,
+) -> impl Responder {
+    let result: Result<Option<Result<Option<u32>, share::Model>>, DbErr> = async {
+        // language=PostgreSQL
+        let sql = r#"
+            UPDATE share
+            SET download_count = download_count + 1, updated_at = now()
+            WHERE token = $1
+              AND (max_downloads IS NULL OR download_count < max_downloads)
+              AND (expires_at IS NULL OR expires_at > now())
+              AND revoked_at IS NULL
+            RETURNING max_downloads - download_count AS remaining;
+        "#;
+
+        let row = database
+            .query_one_raw(Statement::from_sql_and_values(
+                database.get_database_backend(),
+                sql,
+                [Value::from(payload.token.clone())],
+            ))
+            .await?;
+
+        if let Some(row) = row {
+            let remaining = row.try_get::<Option<i32>>("", "remaining")?;
+            return Ok(Some(Ok(remaining.map(|remaining| remaining.max(0) as u32))));
+        }
+
+        let share = Share::find_by_id(payload.token.clone())
+            .one(database.get_ref())
+            .await?;
+
+        Ok(share.map(Err))
+    }
+    .await;
+
+    match result {
+        Ok(Some(Ok(remaining))) => HttpResponse::Ok().json(ShareConsumeResponse { remaining }),
+        Ok(Some(Err(share))) if share.revoked_at.is_some() => revoked_response(share.revoked_at),
+        Ok(Some(Err(_))) => HttpResponse::Gone().finish(),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to count the share download: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::routes::file::tree::FileTreeExtension;
+use crate::routes::share::shared_root;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file;
+use common::entities::prelude::File;
+use common::types::file::metadata::MetadataResponse;
+use common::types::file::share_folder::SharedEntryRequest;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+/// Metadata of a completed file inside a shared folder, for the edge to presign.
+#[post("entry")]
+pub async fn entry(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<SharedEntryRequest>,
+) -> impl Responder {
+    let result: Result<Option<file::Model>, DbErr> = async {
+        let Some(root) = shared_root(&database, &payload.root_id).await? else {
+            return Ok(None);
+        };
+
+        if !database.is_within(&root.owner_id, &root.id, &payload.file_id).await? {
+            return Ok(None);
+        }
+
+        File::find()
+            .filter(file::Column::Id.eq(payload.file_id.clone()))
+            .filter(file::Column::OwnerId.eq(root.owner_id.clone()))
+            .filter(file::Column::IsDirectory.eq(false))
+            .filter(file::Column::UploadCompleted.eq(true))
+            .one(database.get_ref())
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(Some(data)) => HttpResponse::Ok().json(MetadataResponse {
+            file_name: data.file_name,
+            size: data.file_size as u64,
+            content_type: data.file_type,
+            path: data.path,
+            created_at: data.created_at,
+            owner_id: data.owner_id,
+            item_count: data.item_count,
+            is_directory: data.is_directory,
+        }),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to look up the shared file: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
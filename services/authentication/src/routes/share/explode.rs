@@ -0,0 +1,49 @@
+use crate::routes::file::explode::explode_items;
+use crate::routes::file::tree::FileTreeExtension;
+use crate::routes::share::shared_root;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::types::file::share_folder::SharedExplodeRequest;
+use sea_orm::DatabaseConnection;
+use storage::s3_manager::S3StorageManager;
+
+/// The zip listing for entries of a shared folder, the whole folder when none are given.
+#[post("explode")]
+pub async fn explode(
+    database: web::Data<DatabaseConnection>,
+    s3_client: web::Data<S3StorageManager>,
+    payload: web::Json<SharedExplodeRequest>,
+) -> impl Responder {
+    let root = match shared_root(&database, &payload.root_id).await {
+        Ok(Some(root)) => root,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to look up the shared folder: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let item_ids = if payload.item_ids.is_empty() {
+        vec![root.id.clone()]
+    } else {
+        payload.item_ids.clone()
+    };
+
+    for item_id in &item_ids {
+        match database.is_within(&root.owner_id, &root.id, item_id).await {
+            Ok(true) => {}
+            Ok(false) => return HttpResponse::NotFound().finish(),
+            Err(err) => {
+                log::error!("Failed to check the shared entries: {:?}", err);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    }
+
+    match explode_items(&database, &s3_client, &root.owner_id, &item_ids).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(err) => {
+            log::error!("Failed to explode the shared folder: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
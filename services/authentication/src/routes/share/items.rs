@@ -0,0 +1,39 @@
+use crate::routes::share::collection_items;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::types::file::share::ShareTokenRequest;
+use common::types::file::share_collection::{SharedItem, SharedItemsResponse};
+use sea_orm::DatabaseConnection;
+
+/// The files of a collection, for the share page and for the edge to presign one of them.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("items")]
+pub async fn items(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareTokenRequest>,
+) -> impl Responder {
+    match collection_items(&database, &payload.token).await {
+        Ok(Some((collection, items))) => HttpResponse::Ok().json(SharedItemsResponse {
+            title: collection
+                .title
+                .unwrap_or_else(|| format!("{} files", items.len())),
+            owner_id: collection.owner_id,
+            created_at: collection.created_at,
+            items: items
+                .into_iter()
+                .map(|item| SharedItem {
+                    id: item.id,
+                    file_name: item.file_name,
+                    file_size: u64::try_from(item.file_size).unwrap_or(0),
+                    file_type: item.file_type,
+                    created_at: item.created_at,
+                })
+                .collect(),
+        }),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to list the collection: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, Share, ShareItem};
+use common::entities::{share, share_item};
+use common::types::file::share::{ListSharesResponse, ShareElement};
+use sea_orm::sea_query::{Expr, ExprTrait};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::collections::HashMap;
+
+/// The caller's links that still work, newest first. Expired links drop out here but stay on
+/// record so the share page can keep saying they expired, revoked ones drop out for good.
+#[post("list")]
+pub async fn list(
+    database: web::Data<DatabaseConnection>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let result: Result<Vec<ShareElement>, DbErr> = async {
+        let shares = Share::find()
+            .find_also_related(File)
+            .filter(share::Column::OwnerId.eq(authenticated_user.id.clone()))
+            .filter(share::Column::RevokedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(share::Column::ExpiresAt.is_null())
+                    .add(Expr::col(share::Column::ExpiresAt).gt(Expr::current_timestamp())),
+            )
+            .order_by_desc(share::Column::CreatedAt)
+            .all(database.get_ref())
+            .await?;
+
+        let collections = shares
+            .iter()
+            .filter(|(share, _)| share.file_id.is_none())
+            .map(|(share, _)| share.token.clone())
+            .collect::<Vec<_>>();
+
+        let item_counts: HashMap<String, i64> = ShareItem::find()
+            .select_only()
+            .column(share_item::Column::Token)
+            .column_as(share_item::Column::FileId.count(), "count")
+            .filter(share_item::Column::Token.is_in(collections))
+            .group_by(share_item::Column::Token)
+            .into_tuple::<(String, i64)>()
+            .all(database.get_ref())
+            .await?
+            .into_iter()
+            .collect();
+
+        Ok(shares
+            .into_iter()
+            .filter_map(|(share, shared)| {
+                let (file_name, is_directory, item_count) = match (&share.file_id, shared) {
+                    (Some(_), Some(shared)) => (shared.file_name, shared.is_directory, None),
+                    (Some(_), None) => return None,
+                    (None, _) => {
+                        let count = item_counts.get(&share.token).copied().unwrap_or(0);
+                        (
+                            share.title.clone().unwrap_or_else(|| format!("{} files", count)),
+                            false,
+                            Some(u32::try_from(count).unwrap_or(0)),
+                        )
+                    }
+                };
+
+                Some(ShareElement {
+                    token: share.token,
+                    file_id: share.file_id,
+                    file_name,
+                    is_directory,
+                    created_at: share.created_at,
+                    expires_at: share.expires_at,
+                    password_protected: share.password_hash.is_some(),
+                    max_downloads: share.max_downloads.map(|max| u32::try_from(max).unwrap_or(0)),
+                    download_count: u32::try_from(share.download_count).unwrap_or(0),
+                    item_count,
+                })
+            })
+            .collect())
+    }
+    .await;
+
+    match result {
+        Ok(shares) => HttpResponse::Ok().json(ListSharesResponse { shares }),
+        Err(err) => {
+            log::error!("Failed to list shares: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,67 @@
+pub mod access;
+pub mod browse;
+pub mod collection;
+pub mod collection_zip;
+pub mod consume;
+pub mod entry;
+pub mod explode;
+pub mod items;
+pub mod list;
+pub mod record;
+pub mod resolve;
+pub mod revoke;
+pub mod save;
+
+use actix_web::HttpResponse;
+use common::entities::prelude::{File, Share, ShareItem};
+use common::entities::{file, share, share_item};
+use common::types::file::share::ShareRevokedResponse;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+
+/// The 410 for a link that was revoked or rotated away.
+pub fn revoked_response(revoked_at: Option<DateTimeWithTimeZone>) -> HttpResponse {
+    HttpResponse::Gone().json(ShareRevokedResponse {
+        error: "revoked".to_string(),
+        revoked_at: revoked_at.unwrap_or_else(|| chrono::Utc::now().fixed_offset()),
+    })
+}
+
+/// The directory a folder share points at. The owner is taken from it, the edge only knows
+/// the token.
+pub async fn shared_root(database: &DatabaseConnection, root_id: &str) -> Result<Option<file::Model>, DbErr> {
+    File::find()
+        .filter(file::Column::Id.eq(root_id))
+        .filter(file::Column::IsDirectory.eq(true))
+        .one(database)
+        .await
+}
+
+/// A collection and its files that are still there, in the order they were picked. `None` for
+/// tokens that aren't collections.
+pub async fn collection_items(
+    database: &DatabaseConnection,
+    token: &str,
+) -> Result<Option<(share::Model, Vec<file::Model>)>, DbErr> {
+    let Some(collection) = Share::find_by_id(token)
+        .filter(share::Column::FileId.is_null())
+        .filter(share::Column::RevokedAt.is_null())
+        .one(database)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let items = ShareItem::find()
+        .find_also_related(File)
+        .filter(share_item::Column::Token.eq(token))
+        .order_by_asc(share_item::Column::Position)
+        .all(database)
+        .await?
+        .into_iter()
+        .filter_map(|(_, item)| item)
+        .filter(|item| item.owner_id == collection.owner_id && item.upload_completed && !item.is_directory)
+        .collect();
+
+    Ok(Some((collection, items)))
+}
@@ -0,0 +1,55 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{Share, ShareAccess};
+use common::entities::share_access;
+use common::types::file::share_access::ShareAccessRecordRequest;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveValue::NotSet, DatabaseConnection, DbErr, EntityTrait, Set};
+
+/// Adds a visit to a link's history. Tokens that aren't on record have no owner to show it to
+/// and are dropped with a 404.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("record")]
+pub async fn record(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareAccessRecordRequest>,
+) -> impl Responder {
+    let result: Result<bool, DbErr> = async {
+        let Some(share) = Share::find_by_id(payload.token.clone()).one(database.get_ref()).await? else {
+            return Ok(false);
+        };
+
+        ShareAccess::insert(share_access::ActiveModel {
+            id: NotSet,
+            token: Set(share.token),
+            owner_id: Set(share.owner_id),
+            file_id: Set(payload.file_id.clone()),
+            action: Set(truncate(&payload.action, 32)),
+            outcome: Set(truncate(&payload.outcome, 32)),
+            country: Set(payload.country.as_deref().map(|country| truncate(country, 8))),
+            asn: Set(payload.asn.map(i64::from)),
+            as_organization: Set(payload.as_organization.as_deref().map(|name| truncate(name, 255))),
+            user_agent: Set(payload.user_agent.as_deref().map(|agent| truncate(agent, 1024))),
+            created_at: Set(DateTimeWithTimeZone::from(chrono::Utc::now())),
+        })
+        .exec_without_returning(database.get_ref())
+        .await?;
+
+        Ok(true)
+    }
+    .await;
+
+    match result {
+        Ok(true) => HttpResponse::Ok().finish(),
+        Ok(false) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to record access to share {}: {:?}", payload.token, err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Visitors pick their own user agent, the columns shouldn't grow with it.
+fn truncate(value: &str, max_chars: usize) -> String {
+    value.chars().take(max_chars).collect()
+}
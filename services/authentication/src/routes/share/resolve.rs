@@ -0,0 +1,37 @@
+use crate::routes::share::revoked_response;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::Share;
+use common::types::file::file_claims::FileShare;
+use common::types::file::share::ShareTokenRequest;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+/// The record behind a token, for the edge to refill its cache after a miss. Expired links are
+/// still answered so the edge can tell them apart from unknown ones, revoked links get a 410.
+///
+/// Reached by the edge for anonymous visitors, so it takes no authenticated user.
+#[post("resolve")]
+pub async fn resolve(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareTokenRequest>,
+) -> impl Responder {
+    match Share::find_by_id(payload.token.clone())
+        .one(database.get_ref())
+        .await
+    {
+        Ok(Some(share)) if share.revoked_at.is_some() => revoked_response(share.revoked_at),
+        Ok(Some(share)) => HttpResponse::Ok().json(FileShare {
+            collection: share.file_id.is_none(),
+            signed: false,
+            view_only: false,
+            file_id: share.file_id.unwrap_or_default(),
+            expires_at: share.expires_at,
+            password_hash: share.password_hash,
+            max_downloads: share.max_downloads.map(|max| max.max(0) as u32),
+        }),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to resolve the share token: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
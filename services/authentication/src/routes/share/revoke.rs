@@ -0,0 +1,76 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{post, web, HttpResponse, Responder};
+use common::types::file::share::{RevokedShare, ShareRevokeRequest, ShareRevokeResponse};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement, Value};
+use serde_json::json;
+
+/// Revokes one of the caller's links, or all of them. The rows stay behind as tombstones so a
+/// copy the edge still has cached is refused, and the revoked tokens are handed back so the
+/// edge can drop them from its cache.
+#[post("revoke")]
+pub async fn revoke(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareRevokeRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    let result: Result<Vec<RevokedShare>, DbErr> = async {
+        // language=PostgreSQL
+        let sql = r#"
+            UPDATE share
+            SET revoked_at = now(), updated_at = now()
+            WHERE owner_id = $1 AND ($2::text IS NULL OR token = $2) AND revoked_at IS NULL
+            RETURNING token, file_id;
+        "#;
+
+        let rows = database
+            .query_all_raw(Statement::from_sql_and_values(
+                database.get_database_backend(),
+                sql,
+                [
+                    Value::from(authenticated_user.id.clone()),
+                    Value::from(payload.token.clone()),
+                ],
+            ))
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(RevokedShare {
+                    token: row.try_get("", "token")?,
+                    file_id: row.try_get("", "file_id")?,
+                })
+            })
+            .collect()
+    }
+    .await;
+
+    let revoked = match result {
+        Ok(revoked) => revoked,
+        Err(err) => {
+            log::error!("Failed to revoke shares: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if payload.token.is_some() && revoked.is_empty() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    if !revoked.is_empty() {
+        record_logged(
+            database.get_ref(),
+            &authenticated_user.id,
+            &client,
+            AuditEvent::new(
+                "share.revoke",
+                revoked.iter().filter_map(|share| share.file_id.clone()).collect(),
+            )
+            .before(json!({ "tokens": revoked.len() })),
+        )
+        .await;
+    }
+
+    HttpResponse::Ok().json(ShareRevokeResponse { revoked })
+}
@@ -0,0 +1,88 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{File, Share};
+use common::entities::{file, share};
+use common::types::file::share::ShareSaveRequest;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, Set, TransactionTrait};
+
+/// Called by the edge before it caches a link. Changing the settings of an existing token keeps
+/// the downloads already counted, a rotation starts the new token from zero and revokes the
+/// one it replaces. Revoked tokens can't be brought back.
+#[post("save")]
+pub async fn save(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ShareSaveRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let result: Result<Option<u64>, DbErr> = async {
+        let owned = File::find()
+            .filter(file::Column::Id.eq(payload.file_id.clone()))
+            .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
+            .count(database.get_ref())
+            .await?;
+
+        if owned == 0 {
+            return Ok(None);
+        }
+
+        let transaction = database.begin().await?;
+
+        if let Some(replaces) = &payload.replaces {
+            Share::update_many()
+                .col_expr(share::Column::RevokedAt, Expr::current_timestamp())
+                .col_expr(share::Column::UpdatedAt, Expr::current_timestamp())
+                .filter(share::Column::Token.eq(replaces.clone()))
+                .filter(share::Column::OwnerId.eq(authenticated_user.id.clone()))
+                .filter(share::Column::RevokedAt.is_null())
+                .exec(&transaction)
+                .await?;
+        }
+
+        let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+        let written = Share::insert(share::ActiveModel {
+            token: Set(payload.token.clone()),
+            owner_id: Set(authenticated_user.id.clone()),
+            file_id: Set(Some(payload.file_id.clone())),
+            title: Set(None),
+            password_hash: Set(payload.password_hash.clone()),
+            expires_at: Set(payload.expires_at),
+            max_downloads: Set(payload.max_downloads.map(|max| max.min(i32::MAX as u32) as i32)),
+            download_count: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+            revoked_at: Set(None),
+        })
+        .on_conflict(
+            OnConflict::column(share::Column::Token)
+                .update_columns([
+                    share::Column::PasswordHash,
+                    share::Column::ExpiresAt,
+                    share::Column::MaxDownloads,
+                    share::Column::UpdatedAt,
+                ])
+                .action_and_where(share::Column::OwnerId.eq(authenticated_user.id.clone()))
+                .action_and_where(share::Column::RevokedAt.is_null())
+                .to_owned(),
+        )
+        .exec_without_returning(&transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(Some(written))
+    }
+    .await;
+
+    match result {
+        Ok(Some(0)) => HttpResponse::Conflict().finish(),
+        Ok(Some(_)) => HttpResponse::Ok().finish(),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("Failed to save the share: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -1,4 +1,7 @@
 use crate::ProviderConfiguration;
+use crate::error::{error_response, internal_error};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::ClientContext;
 use crate::routes::user::providers::success::login_success;
 use actix_web::{HttpResponse, post, web};
 use base64::Engine as _;
@@ -6,6 +9,7 @@ use base64::prelude::BASE64_STANDARD;
 use common::entities::prelude::{AuthSession, Passkey};
 use common::entities::{auth_session, passkey};
 use common::types::authentication::passkey_auth_complete::PasskeyAuthCompleteRequest;
+use common::types::error::ErrorCode;
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use uuid::Uuid;
 use webauthn_rs::prelude::*;
@@ -16,6 +20,7 @@ pub async fn auth_complete(
     webauth: web::Data<Webauthn>,
     payload: web::Json<PasskeyAuthCompleteRequest>,
     provider_config: web::Data<ProviderConfiguration>,
+    client_context: ClientContext,
 ) -> HttpResponse {
     let state_row = match AuthSession::find()
         .filter(auth_session::Column::UserId.eq(payload.ticket.clone()))
@@ -24,17 +29,13 @@ pub async fn auth_complete(
     {
         Ok(Some(s)) => s,
         Ok(None) => return HttpResponse::BadRequest().finish(),
-        Err(err) => {
-            return HttpResponse::InternalServerError().body(format!("Database error: {:?}", err));
-        }
+        Err(err) => return internal_error("Failed to load the login session", err),
     };
 
     let auth_state: DiscoverableAuthentication = match serde_json::from_value(state_row.state_data)
     {
         Ok(state) => state,
-        Err(err) => {
-            return HttpResponse::InternalServerError().body(format!("State error: {}", err));
-        }
+        Err(err) => return internal_error("Failed to read the login session", err),
     };
 
     let client_credentials: PublicKeyCredential = match serde_json::from_value(payload.data.clone())
@@ -46,8 +47,8 @@ pub async fn auth_complete(
     let ident = match webauth.identify_discoverable_authentication(&client_credentials) {
         Ok(i) => i,
         Err(err) => {
-            return HttpResponse::Unauthorized()
-                .body(format!("Failed to identify credentials: {}", err));
+            log::warn!("Failed to identify passkey credentials: {:?}", err);
+            return error_response(ErrorCode::Unauthorized, "Passkey authentication failed");
         }
     };
 
@@ -63,18 +64,13 @@ pub async fn auth_complete(
             println!("Failed to find credential with id: {}", cred_id_search);
             return HttpResponse::NotFound().finish();
         }
-        Err(err) => {
-            return HttpResponse::InternalServerError().body(format!("Database error: {:?}", err));
-        }
+        Err(err) => return internal_error("Failed to load the passkey", err),
     };
 
     let passkey_definition: webauthn_rs::prelude::Passkey =
         match serde_json::from_value(stored_row.passkey_data) {
             Ok(p) => p,
-            Err(err) => {
-                return HttpResponse::InternalServerError()
-                    .body(format!("Failed to parse stored passkey: {}", err));
-            }
+            Err(err) => return internal_error("Failed to read the stored passkey", err),
         };
 
     match webauth.finish_discoverable_authentication(
@@ -84,23 +80,24 @@ pub async fn auth_complete(
     ) {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::Unauthorized().body(format!("Authentication failed: {}", err));
+            log::warn!("Passkey authentication failed: {:?}", err);
+            return error_response(ErrorCode::Unauthorized, "Passkey authentication failed");
         }
     };
 
     let user_handle = match client_credentials.response.user_handle {
         Some(h) => h,
         None => {
-            return HttpResponse::Unauthorized()
-                .body("Authentication failed: No user handle in response");
+            log::warn!("Passkey response for {} came without a user handle", cred_id_search);
+            return error_response(ErrorCode::Unauthorized, "Passkey authentication failed");
         }
     };
 
-    let user_uuid = match Uuid::from_slice(&**user_handle) {
+    let user_uuid = match Uuid::from_slice(&user_handle) {
         Ok(u) => u.to_string(),
         Err(err) => {
-            return HttpResponse::InternalServerError()
-                .body(format!("Failed to parse user handle as UUID: {}", err));
+            log::warn!("Passkey user handle for {} is not a UUID: {:?}", cred_id_search, err);
+            return error_response(ErrorCode::Unauthorized, "Passkey authentication failed");
         }
     };
 
@@ -111,10 +108,19 @@ pub async fn auth_complete(
     {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::InternalServerError().json(format!("{:?}", err));
+            return internal_error("Failed to clear the login session", err);
         }
     };
 
+    record_logged(
+        database.get_ref(),
+        &user_uuid,
+        &client_context,
+        AuditEvent::new("user.login", vec![user_uuid.clone()])
+            .after(serde_json::json!({ "method": "passkey", "cred_id": cred_id_search })),
+    )
+    .await;
+
     login_success(
         user_uuid,
         provider_config.jwt_secret.clone(),
@@ -1,5 +1,8 @@
 use crate::routes::user::providers::success::login_success;
 use crate::ProviderConfiguration;
+use crate::error::{error_response, internal_error};
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::ClientContext;
 use actix_web::{post, web, HttpResponse};
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
@@ -7,6 +10,7 @@ use chrono::Utc;
 use common::entities::prelude::{AuthSession, Passkey, User};
 use common::entities::{auth_session, passkey, user};
 use common::types::authentication::passkey_complete::PasskeyCompleteRequest;
+use common::types::error::ErrorCode;
 use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::QueryFilter;
 use sea_orm::ColumnTrait;
@@ -20,6 +24,7 @@ pub async fn complete(
     webauth: web::Data<Webauthn>,
     payload: web::Json<PasskeyCompleteRequest>,
     provider_config: web::Data<ProviderConfiguration>,
+    client_context: ClientContext,
 ) -> HttpResponse {
     let state = match AuthSession::find()
         .filter(auth_session::Column::UserId.eq(payload.user_id.clone()))
@@ -28,7 +33,7 @@ pub async fn complete(
     {
         Ok(state) => state,
         Err(err) => {
-            return HttpResponse::BadRequest().body(format!("{:?}", err));
+            return internal_error("Failed to load the registration session", err);
         }
     };
 
@@ -49,25 +54,23 @@ pub async fn complete(
     let passkey_registration: PasskeyRegistration =
         match serde_json::from_value(registration.clone()) {
             Ok(passkey_registration) => passkey_registration,
-            Err(err) => {
-                return HttpResponse::BadRequest()
-                    .body(format!("Failed to deserialize ccr: {}", err));
-            }
+            Err(err) => return internal_error("Failed to read the registration session", err),
         };
 
     let deserialized: RegisterPublicKeyCredential =
         match serde_json::from_value(payload.data.clone()) {
             Ok(deserialized) => deserialized,
             Err(err) => {
-                return HttpResponse::BadRequest()
-                    .body(format!("Failed to deserialize ccr: {}", err));
+                log::warn!("Unreadable passkey registration credential: {:?}", err);
+                return error_response(ErrorCode::BadRequest, "Invalid passkey credential");
             }
         };
 
     let result = match webauth.finish_passkey_registration(&deserialized, &passkey_registration) {
         Ok(result) => result,
         Err(err) => {
-            return HttpResponse::BadRequest().body(format!("{:?}", err));
+            log::warn!("Passkey registration failed: {:?}", err);
+            return error_response(ErrorCode::BadRequest, "Passkey registration failed");
         }
     };
 
@@ -86,12 +89,14 @@ pub async fn complete(
     {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::BadRequest().body(format!("{:?}", err));
+            return internal_error("Failed to create the user", err);
         }
     }
 
+    let cred_id = BASE64_STANDARD.encode(result.cred_id());
+
     match Passkey::insert(passkey::ActiveModel {
-        cred_id: Set(BASE64_STANDARD.encode(result.cred_id())),
+        cred_id: Set(cred_id.clone()),
         user_id: Set(payload.user_id.clone()),
         passkey_data: Set(serde_json::to_value(&result).unwrap()),
         created_at: Set(DateTimeWithTimeZone::from(Utc::now())),
@@ -101,7 +106,7 @@ pub async fn complete(
     {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::InternalServerError().json(format!("{:?}", err));
+            return internal_error("Failed to store the passkey", err);
         }
     }
 
@@ -112,10 +117,19 @@ pub async fn complete(
     {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::InternalServerError().json(format!("{:?}", err));
+            return internal_error("Failed to clear the registration session", err);
         }
     };
 
+    record_logged(
+        database.get_ref(),
+        &payload.user_id,
+        &client_context,
+        AuditEvent::new("passkey.register", vec![payload.user_id.clone()])
+            .after(serde_json::json!({ "cred_id": cred_id, "email": payload.email })),
+    )
+    .await;
+
     login_success(
         payload.user_id.clone(),
         provider_config.jwt_secret.clone(),
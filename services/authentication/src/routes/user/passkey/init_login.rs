@@ -1,3 +1,4 @@
+use crate::error::internal_error;
 use actix_web::{post, web, HttpResponse};
 use common::entities::auth_session::ActiveModel;
 use common::entities::prelude::AuthSession;
@@ -40,7 +41,7 @@ pub async fn auth_init(
     {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::InternalServerError().json(format!("{:?}", err));
+            return internal_error("Failed to store the login session", err);
         }
     };
 
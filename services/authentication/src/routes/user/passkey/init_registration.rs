@@ -1,3 +1,4 @@
+use crate::error::internal_error;
 use actix_web::{post, web, HttpResponse};
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
@@ -82,7 +83,7 @@ pub async fn register(
     {
         Ok(_) => {}
         Err(err) => {
-            return HttpResponse::InternalServerError().json(format!("{:?}", err));
+            return internal_error("Failed to store the registration session", err);
         }
     };
 
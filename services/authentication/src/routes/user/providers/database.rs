@@ -47,7 +47,7 @@ impl ProviderExtension for DatabaseConnection {
             .filter(Column::Email.eq(email.clone()))
             .one(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Query Error: {}", e)))?;
+            .map_err(|e| Error::other(format!("DB Query Error: {}", e)))?;
 
         if let Some(existing_user) = existing_user {
             let mut active_model: ActiveModel = existing_user.into();
@@ -61,7 +61,7 @@ impl ProviderExtension for DatabaseConnection {
             let updated = active_model
                 .update(self)
                 .await
-                .map_err(|e| Error::new(ErrorKind::Other, format!("DB Update Error: {}", e)))?;
+                .map_err(|e| Error::other(format!("DB Update Error: {}", e)))?;
 
             return Ok(updated.id);
         }
@@ -95,7 +95,7 @@ impl ProviderExtension for DatabaseConnection {
             )
             .exec_with_returning(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Upsert Error: {}", e)))?;
+            .map_err(|e| Error::other(format!("DB Upsert Error: {}", e)))?;
 
         Ok(result.id)
     }
@@ -117,7 +117,7 @@ impl ProviderExtension for DatabaseConnection {
         RefreshToken::insert(refresh_token)
             .exec(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Insert Error: {}", e)))?;
+            .map_err(|e| Error::other(format!("DB Insert Error: {}", e)))?;
 
         Ok(())
     }
@@ -127,7 +127,7 @@ impl ProviderExtension for DatabaseConnection {
             .filter(refresh_token::Column::Token.eq(refresh_token))
             .exec(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Delete Error: {}", e)))?;
+            .map_err(|e| Error::other(format!("DB Delete Error: {}", e)))?;
 
         Ok(())
     }
@@ -140,7 +140,7 @@ impl ProviderExtension for DatabaseConnection {
             .filter(refresh_token::Column::Token.eq(refresh_token))
             .one(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Query Error: {}", e)));
+            .map_err(|e| Error::other(format!("DB Query Error: {}", e)));
 
         Ok(res?.ok_or_else(|| Error::new(ErrorKind::NotFound, "Refresh token not found"))?)
     }
@@ -150,7 +150,7 @@ impl ProviderExtension for DatabaseConnection {
             .filter(refresh_token::Column::ExpiresAt.lte(DateTimeWithTimeZone::from(Utc::now())))
             .exec(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Cleanup Error: {}", e)))?;
+            .map_err(|e| Error::other(format!("DB Cleanup Error: {}", e)))?;
 
         Ok(())
     }
@@ -160,7 +160,7 @@ impl ProviderExtension for DatabaseConnection {
             .filter(Column::Id.eq(user_id))
             .one(self)
             .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("DB Query Error: {}", e)))?
+            .map_err(|e| Error::other(format!("DB Query Error: {}", e)))?
             .ok_or_else(|| Error::new(ErrorKind::NotFound, "User not found"))?;
 
         Ok(user)
@@ -2,6 +2,8 @@ use crate::routes::user::providers::database::ProviderExtension;
 use crate::routes::user::providers::success::login_success;
 use crate::routes::user::providers::Provider;
 use crate::ProviderConfiguration;
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::ClientContext;
 use actix_web::{web, HttpResponse};
 use log::error;
 use sea_orm::DatabaseConnection;
@@ -41,6 +43,7 @@ pub async fn github_callback(
     query: web::Query<AuthRequest>,
     provider_config: web::Data<ProviderConfiguration>,
     database: web::Data<DatabaseConnection>,
+    client_context: ClientContext,
 ) -> HttpResponse {
     let client = reqwest::Client::new();
 
@@ -66,7 +69,7 @@ pub async fn github_callback(
     let body_text = resp.text().await.unwrap_or_else(|_| "Empty body".to_string());
     let clean_body = body_text.trim_start_matches('\u{feff}').trim();
 
-    let token: GitHubTokenResponse = match serde_json::from_str(&clean_body) {
+    let token: GitHubTokenResponse = match serde_json::from_str(clean_body) {
         Ok(t) => t,
         Err(e) => {
             error!("JSON Parse Error: {:?}. Raw body was: {}", e, clean_body);
@@ -148,5 +151,15 @@ pub async fn github_callback(
         Provider::GitHub
     ).await;
 
-    login_success(res.unwrap_or_else(|_| uuid::Uuid::new_v4().to_string()), provider_config.jwt_secret.clone(), provider_config.domain_root.clone(), database.get_ref().clone()).await
+    let user_uuid = res.unwrap_or_else(|_| uuid::Uuid::new_v4().to_string());
+
+    record_logged(
+        database.get_ref(),
+        &user_uuid,
+        &client_context,
+        AuditEvent::new("user.login", vec![user_uuid.clone()]).after(serde_json::json!({ "method": "github" })),
+    )
+    .await;
+
+    login_success(user_uuid, provider_config.jwt_secret.clone(), provider_config.domain_root.clone(), database.get_ref().clone()).await
 }
\ No newline at end of file
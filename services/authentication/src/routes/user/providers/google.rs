@@ -2,6 +2,8 @@ use crate::routes::user::providers::database::ProviderExtension;
 use crate::routes::user::providers::success::login_success;
 use crate::routes::user::providers::Provider;
 use crate::ProviderConfiguration;
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::ClientContext;
 use actix_web::{web, HttpResponse};
 use sea_orm::sea_query::prelude::serde_json;
 use sea_orm::DatabaseConnection;
@@ -24,7 +26,8 @@ pub struct AuthRequest {
 pub async fn google_callback(
     query: web::Query<AuthRequest>,
     provider_config: web::Data<ProviderConfiguration>,
-    database: web::Data<DatabaseConnection>
+    database: web::Data<DatabaseConnection>,
+    client_context: ClientContext,
 ) -> HttpResponse {
     let client = reqwest::Client::new();
 
@@ -68,5 +71,13 @@ pub async fn google_callback(
         .await
         .expect("Failed to sync Google user to database");
 
+    record_logged(
+        database.get_ref(),
+        &user_uuid,
+        &client_context,
+        AuditEvent::new("user.login", vec![user_uuid.clone()]).after(serde_json::json!({ "method": "google" })),
+    )
+    .await;
+
     login_success(user_uuid, provider_config.jwt_secret.clone(), provider_config.domain_root.clone(), database.as_ref().clone()).await
 }
\ No newline at end of file
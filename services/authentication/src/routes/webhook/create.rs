@@ -0,0 +1,113 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::routes::file::tree::FileTreeExtension;
+use crate::webhooks::target::check_url;
+use crate::webhooks::webhook_element;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::file;
+use common::entities::prelude::{File, Webhook};
+use common::entities::webhook;
+use common::types::webhook::create::CreateWebhookRequest;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde_json::json;
+
+/// Shortest secret accepted for signing deliveries.
+const MIN_SECRET_LENGTH: usize = 16;
+
+#[post("create")]
+pub async fn create(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<CreateWebhookRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    if let Err(message) = check_url(&payload.url).await {
+        return HttpResponse::BadRequest().json(message);
+    }
+
+    if payload.secret.len() < MIN_SECRET_LENGTH {
+        return HttpResponse::BadRequest()
+            .json(format!("Secret must be at least {} characters", MIN_SECRET_LENGTH));
+    }
+
+    if payload.events.is_empty() {
+        return HttpResponse::BadRequest().json("At least one event is required");
+    }
+
+    let folder_id = match &payload.folder_id {
+        None => None,
+        Some(reference) => {
+            let id = match database.resolve_reference(&authenticated_user.id, reference).await {
+                Ok(Some(id)) => id,
+                Ok(None) => return HttpResponse::NotFound().finish(),
+                Err(err) => {
+                    log::error!("Failed to resolve the webhook folder: {:?}", err);
+                    return HttpResponse::InternalServerError().finish();
+                }
+            };
+
+            // The root is the same as no scope at all.
+            if id.is_empty() {
+                None
+            } else {
+                match File::find()
+                    .filter(file::Column::Id.eq(id.clone()))
+                    .filter(file::Column::OwnerId.eq(authenticated_user.id.clone()))
+                    .filter(file::Column::IsDirectory.eq(true))
+                    .one(database.get_ref())
+                    .await
+                {
+                    Ok(Some(_)) => Some(id),
+                    Ok(None) => return HttpResponse::NotFound().finish(),
+                    Err(err) => {
+                        log::error!("Failed to look up the webhook folder: {:?}", err);
+                        return HttpResponse::InternalServerError().finish();
+                    }
+                }
+            }
+        }
+    };
+
+    let mut events = payload.events.clone();
+    events.sort();
+    events.dedup();
+
+    let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+    let hook = webhook::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        owner_id: Set(authenticated_user.id.clone()),
+        url: Set(payload.url.clone()),
+        secret: Set(payload.secret.clone()),
+        events: Set(serde_json::to_value(&events).unwrap_or_default()),
+        folder_id: Set(folder_id),
+        enabled: Set(true),
+        consecutive_failures: Set(0),
+        disabled_reason: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    match Webhook::insert(hook).exec_with_returning(database.get_ref()).await {
+        Ok(hook) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("webhook.create", vec![hook.id.clone()]).after(json!({
+                    "url": hook.url,
+                    "events": hook.events,
+                    "folder_id": hook.folder_id,
+                })),
+            )
+            .await;
+
+            HttpResponse::Ok().json(webhook_element(hook))
+        }
+        Err(err) => {
+            log::error!("Failed to create the webhook: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use actix_web::{delete, web, HttpResponse, Responder};
+use common::entities::prelude::Webhook;
+use common::entities::webhook;
+use common::types::webhook::list::WebhookRequest;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Pending deliveries go with the webhook.
+#[delete("delete")]
+pub async fn delete(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<WebhookRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    match Webhook::delete_many()
+        .filter(webhook::Column::Id.eq(payload.webhook_id.clone()))
+        .filter(webhook::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .exec(database.get_ref())
+        .await
+    {
+        Ok(result) if result.rows_affected == 0 => HttpResponse::NotFound().finish(),
+        Ok(_) => {
+            record_logged(
+                database.get_ref(),
+                &authenticated_user.id,
+                &client,
+                AuditEvent::new("webhook.delete", vec![payload.webhook_id.clone()]),
+            )
+            .await;
+
+            HttpResponse::Ok().finish()
+        }
+        Err(err) => {
+            log::error!("Failed to delete the webhook: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
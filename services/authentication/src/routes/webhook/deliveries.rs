@@ -0,0 +1,65 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::{Webhook, WebhookDelivery};
+use common::entities::{webhook, webhook_delivery};
+use common::types::webhook::deliveries::{DeliveryElement, ListDeliveriesRequest, ListDeliveriesResponse};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect};
+
+#[post("deliveries")]
+pub async fn deliveries(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<ListDeliveriesRequest>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    let limit = payload.limit.unwrap_or(50).min(200) as u64;
+    let offset = payload.offset.unwrap_or(0) as u64;
+
+    match Webhook::find()
+        .filter(webhook::Column::Id.eq(payload.webhook_id.clone()))
+        .filter(webhook::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .count(database.get_ref())
+        .await
+    {
+        Ok(0) => return HttpResponse::NotFound().finish(),
+        Ok(_) => {}
+        Err(err) => {
+            log::error!("Failed to look up the webhook: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    let mut deliveries = match WebhookDelivery::find()
+        .filter(webhook_delivery::Column::WebhookId.eq(payload.webhook_id.clone()))
+        .order_by_desc(webhook_delivery::Column::CreatedAt)
+        .limit(limit + 1)
+        .offset(offset)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(deliveries) => deliveries,
+        Err(err) => {
+            log::error!("Failed to list webhook deliveries: {:?}", err);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let has_more = deliveries.len() as u64 > limit;
+    if has_more { deliveries.pop(); }
+
+    HttpResponse::Ok().json(ListDeliveriesResponse {
+        deliveries: deliveries
+            .into_iter()
+            .map(|delivery| DeliveryElement {
+                id: delivery.id,
+                event: delivery.event,
+                status: delivery.status,
+                attempts: delivery.attempts,
+                response_status: delivery.response_status,
+                last_error: delivery.last_error,
+                created_at: delivery.created_at,
+                delivered_at: delivery.delivered_at,
+            })
+            .collect(),
+        has_more,
+    })
+}
@@ -0,0 +1,52 @@
+use crate::audit::{record_logged, AuditEvent};
+use crate::middleware::middleware::{AuthenticatedUser, ClientContext};
+use crate::webhooks::webhook_element;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::Webhook;
+use common::entities::webhook;
+use common::types::webhook::list::WebhookRequest;
+use migration::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde_json::json;
+
+/// Turns a webhook back on after it was disabled for failing, with a clean failure count.
+/// Deliveries still pending from before are picked up again.
+#[post("enable")]
+pub async fn enable(
+    database: web::Data<DatabaseConnection>,
+    payload: web::Json<WebhookRequest>,
+    authenticated_user: AuthenticatedUser,
+    client: ClientContext,
+) -> impl Responder {
+    let updated = Webhook::update_many()
+        .col_expr(webhook::Column::Enabled, Expr::value(true))
+        .col_expr(webhook::Column::ConsecutiveFailures, Expr::value(0))
+        .col_expr(webhook::Column::DisabledReason, Expr::value(Option::<String>::None))
+        .col_expr(webhook::Column::UpdatedAt, Expr::current_timestamp())
+        .filter(webhook::Column::Id.eq(payload.webhook_id.clone()))
+        .filter(webhook::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .exec_with_returning(database.get_ref())
+        .await;
+
+    match updated {
+        Ok(mut hooks) => match hooks.pop() {
+            Some(hook) => {
+                record_logged(
+                    database.get_ref(),
+                    &authenticated_user.id,
+                    &client,
+                    AuditEvent::new("webhook.enable", vec![hook.id.clone()])
+                        .after(json!({ "enabled": true })),
+                )
+                .await;
+
+                HttpResponse::Ok().json(webhook_element(hook))
+            }
+            None => HttpResponse::NotFound().finish(),
+        },
+        Err(err) => {
+            log::error!("Failed to enable the webhook: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::middleware::middleware::AuthenticatedUser;
+use crate::webhooks::webhook_element;
+use actix_web::{post, web, HttpResponse, Responder};
+use common::entities::prelude::Webhook;
+use common::entities::webhook;
+use common::types::webhook::list::ListWebhooksResponse;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+#[post("list")]
+pub async fn list(
+    database: web::Data<DatabaseConnection>,
+    authenticated_user: AuthenticatedUser,
+) -> impl Responder {
+    match Webhook::find()
+        .filter(webhook::Column::OwnerId.eq(authenticated_user.id.clone()))
+        .order_by_asc(webhook::Column::CreatedAt)
+        .all(database.get_ref())
+        .await
+    {
+        Ok(hooks) => HttpResponse::Ok().json(ListWebhooksResponse {
+            webhooks: hooks.into_iter().map(webhook_element).collect(),
+        }),
+        Err(err) => {
+            log::error!("Failed to list webhooks: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
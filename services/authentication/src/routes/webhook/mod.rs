@@ -0,0 +1,5 @@
+pub mod create;
+pub mod delete;
+pub mod deliveries;
+pub mod enable;
+pub mod list;
@@ -0,0 +1,185 @@
+use crate::webhooks::target::{check_url, PublicResolver};
+use crate::webhooks::{signature, DELIVERY_DELIVERED, DELIVERY_FAILED};
+use common::entities::prelude::{Webhook, WebhookDelivery};
+use common::entities::{webhook, webhook_delivery};
+use migration::{Expr, ExprTrait};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter, Statement};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Attempts per delivery before it is marked failed.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Failed attempts in a row, across deliveries, after which the webhook is switched off.
+pub const DISABLE_AFTER_FAILURES: i32 = 20;
+
+pub fn spawn_dispatcher(database: DatabaseConnection) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(PublicResolver)
+        .build()
+        .expect("Failed to build the webhook client");
+
+    tokio::spawn(async move {
+        loop {
+            match claim(&database).await {
+                Ok(deliveries) if !deliveries.is_empty() => {
+                    for delivery in deliveries {
+                        let id = delivery.id.clone();
+                        if let Err(err) = dispatch(&database, &client, delivery).await {
+                            log::error!("Failed to record the outcome of webhook delivery {}: {:?}", id, err);
+                        }
+                    }
+                    continue;
+                }
+                Ok(_) => {}
+                Err(err) => log::error!("Failed to claim webhook deliveries: {:?}", err),
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Leases due deliveries of enabled webhooks the same way the storage outbox does.
+async fn claim(database: &DatabaseConnection) -> Result<Vec<webhook_delivery::Model>, DbErr> {
+    // language=PostgreSQL
+    let sql = r#"
+        UPDATE webhook_delivery
+        SET attempts = attempts + 1,
+            next_attempt_at = now() + interval '5 minutes'
+        WHERE id IN (
+            SELECT d.id FROM webhook_delivery d
+            INNER JOIN webhook w ON w.id = d.webhook_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= now() AND w.enabled
+            ORDER BY d.next_attempt_at
+            LIMIT 20
+            FOR UPDATE OF d SKIP LOCKED
+        )
+        RETURNING *;
+    "#;
+
+    WebhookDelivery::find()
+        .from_raw_sql(Statement::from_string(DbBackend::Postgres, sql))
+        .all(database)
+        .await
+}
+
+fn backoff(attempts: i32) -> chrono::Duration {
+    chrono::Duration::seconds(std::cmp::min(30_i64 << attempts.clamp(0, 7), 3600))
+}
+
+async fn dispatch(
+    database: &DatabaseConnection,
+    client: &reqwest::Client,
+    delivery: webhook_delivery::Model,
+) -> Result<(), DbErr> {
+    let Some(hook) = Webhook::find_by_id(delivery.webhook_id.clone()).one(database).await? else {
+        return Ok(());
+    };
+
+    let body = delivery.payload.to_string();
+    let timestamp = chrono::Utc::now().timestamp();
+
+    // Webhooks created before URLs were checked, or whose host has since moved, fail like an
+    // unreachable endpoint would.
+    if let Err(message) = check_url(&hook.url).await {
+        return record_failure(database, &delivery, &hook, None, message).await;
+    }
+
+    let response = client
+        .post(&hook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Id", &hook.id)
+        .header("X-Webhook-Event", &delivery.event)
+        .header("X-Webhook-Delivery", &delivery.id)
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Signature", signature(&hook.secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await;
+
+    let (response_status, error) = match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16() as i32), None),
+        Ok(response) => (
+            Some(response.status().as_u16() as i32),
+            Some(format!("Endpoint responded with {}", response.status())),
+        ),
+        Err(err) => (None, Some(err.to_string())),
+    };
+
+    let Some(error) = error else {
+        WebhookDelivery::update_many()
+            .col_expr(webhook_delivery::Column::Status, Expr::value(DELIVERY_DELIVERED))
+            .col_expr(webhook_delivery::Column::ResponseStatus, Expr::value(response_status))
+            .col_expr(webhook_delivery::Column::LastError, Expr::value(Option::<String>::None))
+            .col_expr(webhook_delivery::Column::DeliveredAt, Expr::current_timestamp())
+            .filter(webhook_delivery::Column::Id.eq(delivery.id.clone()))
+            .exec(database)
+            .await?;
+
+        Webhook::update_many()
+            .col_expr(webhook::Column::ConsecutiveFailures, Expr::value(0))
+            .filter(webhook::Column::Id.eq(hook.id.clone()))
+            .exec(database)
+            .await?;
+
+        return Ok(());
+    };
+
+    record_failure(database, &delivery, &hook, response_status, error).await
+}
+
+async fn record_failure(
+    database: &DatabaseConnection,
+    delivery: &webhook_delivery::Model,
+    hook: &webhook::Model,
+    response_status: Option<i32>,
+    error: String,
+) -> Result<(), DbErr> {
+    let mut update = WebhookDelivery::update_many()
+        .col_expr(webhook_delivery::Column::ResponseStatus, Expr::value(response_status))
+        .col_expr(webhook_delivery::Column::LastError, Expr::value(error.clone()));
+
+    update = if delivery.attempts >= MAX_ATTEMPTS {
+        update.col_expr(webhook_delivery::Column::Status, Expr::value(DELIVERY_FAILED))
+    } else {
+        let next_attempt_at = DateTimeWithTimeZone::from(chrono::Utc::now() + backoff(delivery.attempts));
+        update.col_expr(webhook_delivery::Column::NextAttemptAt, Expr::value(next_attempt_at))
+    };
+
+    update
+        .filter(webhook_delivery::Column::Id.eq(delivery.id.clone()))
+        .exec(database)
+        .await?;
+
+    let failures = hook.consecutive_failures + 1;
+
+    let mut update = Webhook::update_many()
+        .col_expr(
+            webhook::Column::ConsecutiveFailures,
+            Expr::col(webhook::Column::ConsecutiveFailures).add(1),
+        )
+        .col_expr(webhook::Column::UpdatedAt, Expr::current_timestamp());
+
+    if failures >= DISABLE_AFTER_FAILURES {
+        log::warn!("Disabling webhook {} after {} failed deliveries: {}", hook.id, failures, error);
+
+        update = update
+            .col_expr(webhook::Column::Enabled, Expr::value(false))
+            .col_expr(
+                webhook::Column::DisabledReason,
+                Expr::value(format!("Disabled after {} failed deliveries in a row, last error: {}", failures, error)),
+            );
+    }
+
+    update
+        .filter(webhook::Column::Id.eq(hook.id.clone()))
+        .exec(database)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+pub mod dispatcher;
+pub mod target;
+
+use common::entities::prelude::{Webhook, WebhookDelivery};
+use common::entities::{webhook, webhook_delivery};
+use common::types::webhook::event::WebhookEvent;
+use common::types::webhook::list::WebhookElement;
+use hmac::{Hmac, Mac};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set, Statement, Value as DbValue};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_DELIVERED: &str = "delivered";
+pub const DELIVERY_FAILED: &str = "failed";
+
+/// Queues a delivery for every enabled webhook of the owner that listens for the event. Called
+/// from inside the transaction making the change, so a rolled back change never fires.
+///
+/// `touched_ids` are the entries the event is about, plus any directory they were moved into.
+/// Webhooks scoped to a folder only fire when one of them sits inside it.
+pub async fn emit<C: ConnectionTrait>(
+    connection: &C,
+    owner_id: &str,
+    event: WebhookEvent,
+    touched_ids: &[String],
+    data: Value,
+) -> Result<(), DbErr> {
+    let hooks = Webhook::find()
+        .filter(webhook::Column::OwnerId.eq(owner_id))
+        .filter(webhook::Column::Enabled.eq(true))
+        .all(connection)
+        .await?
+        .into_iter()
+        .filter(|hook| subscribed_events(hook).contains(&event))
+        .collect::<Vec<_>>();
+
+    if hooks.is_empty() {
+        return Ok(());
+    }
+
+    let scope = if hooks.iter().any(|hook| hook.folder_id.is_some()) {
+        containing_ids(connection, owner_id, touched_ids).await?
+    } else {
+        vec![]
+    };
+
+    let now = DateTimeWithTimeZone::from(chrono::Utc::now());
+
+    let deliveries = hooks
+        .into_iter()
+        .filter(|hook| hook.folder_id.as_ref().is_none_or(|folder| scope.contains(folder)))
+        .map(|hook| {
+            let id = uuid::Uuid::new_v4().to_string();
+
+            webhook_delivery::ActiveModel {
+                payload: Set(json!({
+                    "id": id,
+                    "event": event,
+                    "created_at": now,
+                    "data": data,
+                })),
+                id: Set(id),
+                webhook_id: Set(hook.id),
+                event: Set(event.as_str().to_string()),
+                status: Set(DELIVERY_PENDING.to_string()),
+                attempts: Set(0),
+                response_status: Set(None),
+                last_error: Set(None),
+                next_attempt_at: Set(now),
+                created_at: Set(now),
+                delivered_at: Set(None),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !deliveries.is_empty() {
+        WebhookDelivery::insert_many(deliveries).exec(connection).await?;
+    }
+
+    Ok(())
+}
+
+/// The entries themselves together with every directory above them.
+async fn containing_ids<C: ConnectionTrait>(
+    connection: &C,
+    owner_id: &str,
+    touched_ids: &[String],
+) -> Result<Vec<String>, DbErr> {
+    // language=PostgreSQL
+    let sql = r#"
+        WITH RECURSIVE ancestors AS (
+            SELECT id, path FROM file WHERE id = ANY($1) AND owner_id = $2
+            UNION
+            SELECT f.id, f.path FROM file f
+            INNER JOIN ancestors a ON f.id = a.path
+            WHERE f.owner_id = $2
+        )
+        SELECT id FROM ancestors;
+    "#;
+
+    let rows = connection
+        .query_all_raw(Statement::from_sql_and_values(
+            connection.get_database_backend(),
+            sql,
+            [DbValue::from(touched_ids.to_vec()), DbValue::from(owner_id)],
+        ))
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| row.try_get::<String>("", "id").ok())
+        .collect())
+}
+
+pub fn subscribed_events(hook: &webhook::Model) -> Vec<WebhookEvent> {
+    serde_json::from_value(hook.events.clone()).unwrap_or_default()
+}
+
+/// `sha256=` followed by the hex HMAC of `{timestamp}.{body}`. The timestamp is sent alongside
+/// so receivers can reject replays.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+pub fn webhook_element(hook: webhook::Model) -> WebhookElement {
+    WebhookElement {
+        events: subscribed_events(&hook),
+        id: hook.id,
+        url: hook.url,
+        folder_id: hook.folder_id,
+        enabled: hook.enabled,
+        consecutive_failures: hook.consecutive_failures,
+        disabled_reason: hook.disabled_reason,
+        created_at: hook.created_at,
+    }
+}
@@ -0,0 +1,108 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Checks that a webhook URL is an absolute https URL whose host only resolves to public
+/// addresses, so webhooks can't be pointed at the service's own network.
+pub async fn check_url(url: &str) -> Result<(), String> {
+    let url = match Url::parse(url) {
+        Ok(url) if url.scheme() == "https" && url.host().is_some() => url,
+        _ => return Err("Webhook URL must be an absolute https URL".to_string()),
+    };
+
+    let port = url.port_or_known_default().unwrap_or(443);
+    let host = url.host_str().unwrap_or_default();
+
+    // IPv6 hosts keep their brackets in the URL.
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => check_addresses(&[SocketAddr::new(ip, port)]),
+        Err(_) => check_addresses(&lookup(host, port).await?),
+    }
+}
+
+/// Resolves webhook hosts for the dispatcher's client and refuses any that lead to a
+/// non-public address. Checking again at connect time keeps a host that passed `check_url`
+/// from being re-pointed at an internal address afterwards.
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let addresses = lookup(&host, 0).await?;
+            check_addresses(&addresses)?;
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn lookup(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(addresses) => Ok(addresses.collect()),
+        Err(err) => Err(format!("Failed to resolve {}: {}", host, err)),
+    }
+}
+
+fn check_addresses(addresses: &[SocketAddr]) -> Result<(), String> {
+    if addresses.is_empty() {
+        return Err("Webhook host doesn't resolve to any address".to_string());
+    }
+
+    // One internal address is enough to refuse, the client may pick any of them.
+    match addresses.iter().find(|address| !is_public(address.ip())) {
+        Some(address) => Err(format!("Webhook host resolves to a non-public address ({})", address.ip())),
+        None => Ok(()),
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", carrier-grade NAT, IETF protocol assignments, benchmarking and
+        // the reserved 240.0.0.0/4.
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    // Mapped and NAT64 addresses reach whatever IPv4 address they carry.
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_public_v4(mapped);
+    }
+
+    let segments = ip.segments();
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [a, b] = segments[6].to_be_bytes();
+        let [c, d] = segments[7].to_be_bytes();
+        return is_public_v4(Ipv4Addr::new(a, b, c, d));
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local fc00::/7, link-local fe80::/10, the deprecated site-local fec0::/10 and
+        // documentation 2001:db8::/32.
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] & 0xffc0) == 0xfec0
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
@@ -9,6 +9,8 @@ use common::types::user::user_claims::UserClaims;
 pub struct AuthenticatedUser {
     pub id: String,
     pub session_token: String,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
 }
 
 pub(crate) async fn get_authenticated_user(req: &Request, ctx: &RouteContext<Arc<AppState>>) -> Result<AuthenticatedUser, AuthError> {
@@ -36,5 +38,7 @@ pub(crate) async fn get_authenticated_user(req: &Request, ctx: &RouteContext<Arc
     Ok(AuthenticatedUser {
         id: token_data.claims.user_id.to_string(),
         session_token: session_token.to_string(),
+        client_ip: req.headers().get("CF-Connecting-IP").ok().flatten(),
+        user_agent: req.headers().get("User-Agent").ok().flatten(),
     })
 }
\ No newline at end of file
@@ -15,4 +15,7 @@ macro_rules! try_authenticate {
     };
 }
 
-pub mod authentication;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod authentication;
+pub mod share_password;
+pub mod share_token;
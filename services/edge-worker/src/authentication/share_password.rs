@@ -0,0 +1,59 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+/// Workers cap Web Crypto PBKDF2 at this count, kept the same so hashes could move there.
+const ITERATIONS: u32 = 100_000;
+const SALT_LENGTH: usize = 16;
+const HASH_LENGTH: usize = 32;
+const SCHEME: &str = "pbkdf2-sha256";
+
+/// Hashes a share password as `pbkdf2-sha256$<iterations>$<salt>$<hash>`, base64 without
+/// padding, so the iteration count can be raised later without breaking older links.
+pub fn hash_password(password: &str) -> worker::Result<String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    getrandom::fill(&mut salt).map_err(|err| worker::Error::from(err.to_string()))?;
+
+    let hash = pbkdf2(password.as_bytes(), &salt, ITERATIONS);
+
+    Ok(format!(
+        "{}${}${}${}",
+        SCHEME,
+        ITERATIONS,
+        STANDARD_NO_PAD.encode(salt),
+        STANDARD_NO_PAD.encode(hash),
+    ))
+}
+
+/// False for a wrong password as well as for a hash it can't read.
+pub fn verify_password(password: &str, encoded: &str) -> bool {
+    let mut parts = encoded.split('$');
+
+    let (Some(SCHEME), Some(iterations), Some(salt), Some(expected), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+
+    let (Ok(iterations), Ok(salt), Ok(expected)) = (
+        iterations.parse::<u32>(),
+        STANDARD_NO_PAD.decode(salt),
+        STANDARD_NO_PAD.decode(expected),
+    ) else {
+        return false;
+    };
+
+    if iterations == 0 || expected.len() != HASH_LENGTH {
+        return false;
+    }
+
+    pbkdf2(password.as_bytes(), &salt, iterations).ct_eq(&expected).into()
+}
+
+fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32) -> [u8; HASH_LENGTH] {
+    let mut output = [0u8; HASH_LENGTH];
+    pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut output);
+    output
+}
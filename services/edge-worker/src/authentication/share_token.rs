@@ -0,0 +1,70 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use common::types::file::file_claims::SignedShareClaims;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Key id used when `SHARE_SECRET` is a bare secret rather than a list of `kid:secret` pairs.
+const DEFAULT_KEY_ID: &str = "0";
+
+/// The signing keys from `SHARE_SECRET`, written as `kid:secret` pairs separated by commas. New
+/// tokens are signed with the first, the rest still verify so older links keep working while
+/// a key is rotated out. A bare secret is a single key with id `0`.
+pub struct ShareKeys<'a> {
+    keys: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> ShareKeys<'a> {
+    pub fn parse(share_secret: &'a str) -> Self {
+        let keys = share_secret
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once(':') {
+                Some((kid, secret)) => (kid.trim(), secret.trim()),
+                None => (DEFAULT_KEY_ID, entry),
+            })
+            .filter(|(kid, secret)| !kid.is_empty() && !kid.contains('.') && !secret.is_empty())
+            .collect();
+
+        ShareKeys { keys }
+    }
+
+    /// `<kid>.<claims>.<signature>`, the claims as base64url JSON and the signature an
+    /// HMAC-SHA256 over everything before it.
+    pub fn sign(&self, claims: &SignedShareClaims) -> worker::Result<String> {
+        let Some((kid, secret)) = self.keys.first() else {
+            return Err(worker::Error::from("SHARE_SECRET holds no usable key"));
+        };
+
+        let signed = format!("{}.{}", kid, URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?));
+        let signature = mac(secret, &signed).finalize().into_bytes();
+
+        Ok(format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(signature)))
+    }
+
+    /// The claims of a token signed with one of the keys. Expiry and revocation are left to the
+    /// caller, an expired link still gets its 410.
+    pub fn verify(&self, token: &str) -> Option<SignedShareClaims> {
+        let (signed, signature) = token.rsplit_once('.')?;
+        let (kid, claims) = signed.split_once('.')?;
+
+        let (_, secret) = self.keys.iter().find(|(known, _)| *known == kid)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+        mac(secret, signed).verify_slice(&signature).ok()?;
+
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).ok()?).ok()
+    }
+}
+
+/// Random share tokens never contain a dot, signed ones always do.
+pub fn is_signed(token: &str) -> bool {
+    token.contains('.')
+}
+
+fn mac(secret: &str, signed: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(signed.as_bytes());
+    mac
+}
@@ -1,6 +1,8 @@
 use crate::types::configuration::Configuration;
+use crate::types::error::{envelope, error_response, request_id};
+use common::types::error::{ErrorCode, REQUEST_ID_HEADER};
 use std::sync::Arc;
-use worker::{event, Context, Env, Request, Response, Router};
+use worker::{console_error, event, Context, Env, Request, Response, Router};
 
 pub mod authentication;
 pub mod routes;
@@ -8,10 +10,12 @@ pub mod types;
 
 struct AppState {
     config: Configuration,
+    /// For work that may finish after the response is sent.
+    ctx: Context,
 }
 
 #[event(fetch)]
-pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response, worker::Error> {
+pub async fn main(req: Request, env: Env, ctx: Context) -> Result<Response, worker::Error> {
     console_error_panic_hook::set_once();
 
     let origin = req.headers().get("Origin")?.unwrap_or_default();
@@ -24,23 +28,37 @@ pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response, wor
         if is_allowed {
             headers.set("Access-Control-Allow-Origin", &origin)?;
             headers.set("Access-Control-Allow-Methods", "GET, POST, DELETE, OPTIONS, PATCH")?;
-            headers.set("Access-Control-Allow-Headers", "Content-Type, Authorization")?;
+            headers.set("Access-Control-Allow-Headers", "Content-Type, Authorization, Range")?;
             headers.set("Access-Control-Allow-Credentials", "true")?;
             headers.set("Vary", "Origin")?;
         }
         return Ok(Response::empty()?.with_headers(headers));
     }
 
+    // Cloudflare's own id for the request, so our logs line up with theirs.
+    let request_id = request_id(req.headers().get("CF-Ray")?);
+    let path = req.path();
+
     let state = Arc::new(AppState {
-        config: Configuration::gather_configuration(env.clone()),
+        config: Configuration::gather_configuration(env.clone(), request_id.clone()),
+        ctx,
     });
 
-    let mut response = Router::with_data(state.clone())
+    let response = Router::with_data(state.clone())
         .post_async("/upload/create", routes::upload::handle_create)
         .post_async("/upload/complete", routes::upload::handle_complete)
         .post_async("/download/create", routes::download::handle_create)
         .post_async("/download/share/create", routes::share_download::handle_share_download)
+        .post_async("/download/share/browse", routes::share_folder::handle_share_browse)
+        .post_async("/download/share/file", routes::share_folder::handle_share_file)
+        .post_async("/download/share/zip", routes::share_folder::handle_share_zip)
+        .post_async("/download/share/items", routes::share_collection::handle_share_items)
+        .get_async("/download/share/stream/:token", routes::share_stream::handle_share_stream)
         .post_async("/file/share", routes::share::handle_share)
+        .post_async("/file/share/collection", routes::share::handle_share_collection)
+        .post_async("/file/share/list", routes::share::handle_list)
+        .post_async("/file/share/revoke", routes::share::handle_revoke)
+        .post_async("/file/share/access", routes::share_access::handle_access)
         .post_async("/file/metadata", routes::metadata::handle_metadata)
         .post_async("/file/copy", routes::copy::handle_copy)
         .delete_async("/file/delete", routes::delete::handle_delete)
@@ -48,19 +66,54 @@ pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response, wor
         .post_async("/file/rename", routes::rename::handle_rename)
         .post_async("/file/zip", routes::zip::handle_zip)
         .post_async("/file/list", routes::list::handle_list)
+        .post_async("/file/resolve", routes::resolve::handle_resolve)
+        .post_async("/file/changes", routes::changes::handle_changes)
+        .get_async("/file/events", routes::events::handle_events)
         .post_async("/directory/create", routes::directory::handle_directory)
         .delete_async("/directory/delete", routes::directory::handle_directory_delete)
+        .post_async("/job/submit", routes::job::handle_submit)
+        .post_async("/job/status", routes::job::handle_status)
+        .post_async("/job/cancel", routes::job::handle_cancel)
+        .post_async("/audit/list", routes::audit::handle_audit_list)
+        .post_async("/webhook/create", routes::webhook::handle_create)
+        .post_async("/webhook/list", routes::webhook::handle_list)
+        .delete_async("/webhook/delete", routes::webhook::handle_delete)
+        .post_async("/webhook/deliveries", routes::webhook::handle_deliveries)
+        .post_async("/webhook/enable", routes::webhook::handle_enable)
+        .post_async("/grant/create", routes::grant::handle_create)
+        .post_async("/grant/list", routes::grant::handle_list)
+        .delete_async("/grant/delete", routes::grant::handle_delete)
+        .post_async("/file/shared", routes::grant::handle_shared)
+        .post_async("/request/create", routes::file_request::handle_create)
+        .post_async("/request/list", routes::file_request::handle_list)
+        .delete_async("/request/delete", routes::file_request::handle_delete)
+        .post_async("/request/uploads", routes::file_request::handle_uploads)
+        .post_async("/request/info", routes::file_request::handle_info)
+        .post_async("/request/upload/create", routes::file_request::handle_upload_create)
+        .post_async("/request/upload/complete", routes::file_request::handle_upload_complete)
         .post_async("/user/info", routes::user_info::handle_info)
         .post_async("/user/refresh", routes::user_refresh::handle_refresh)
         .post_async("/user/logout", routes::user_logout::handle_logout)
         .run(req, env)
-        .await?;
+        .await;
+
+    let response = match response {
+        Ok(response) => response,
+        Err(err) => {
+            console_error!("{} failed ({}): {}", path, request_id, err);
+            error_response(ErrorCode::Internal, ErrorCode::Internal.default_message())?
+        }
+    };
+
+    let mut response = envelope(response, &request_id).await?;
+    let headers = response.headers_mut();
+    headers.set(REQUEST_ID_HEADER, &request_id)?;
 
     if is_allowed {
-        let headers = response.headers_mut();
         headers.set("Access-Control-Allow-Origin", &origin)?;
         headers.set("Access-Control-Allow-Credentials", "true")?;
         headers.set("Vary", "Origin")?;
+        headers.append("Access-Control-Expose-Headers", REQUEST_ID_HEADER)?;
     }
 
     Ok(response)
@@ -16,7 +16,8 @@ pub async fn handle_delete(mut req: Request, ctx: RouteContext<Arc<AppState>>) -
         &payload
     ).await?;
 
-    if response.0 == 200 {
+    // 202 means the deletion was queued as a job, the files are going away either way.
+    if response.0 == 200 || response.0 == 202 {
         let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
 
         for file_id in payload.file_ids {
@@ -31,5 +32,9 @@ pub async fn handle_delete(mut req: Request, ctx: RouteContext<Arc<AppState>>) -
         }
     }
 
+    if response.0 == 202 {
+        return Ok(Response::from_json(&response.1)?.with_status(202));
+    }
+
     Ok(Response::empty()?.with_status(204))
 }
@@ -32,7 +32,7 @@ pub async fn handle_directory_delete(mut req: Request, ctx: RouteContext<Arc<App
 
     let payload: DeleteDirectoryRequest = req.json().await?;
 
-    let response = state.config.make_internal_request::<_, ()>(
+    let response = state.config.make_internal_request::<_, Value>(
         "/internal/file/directory/delete",
         &user,
         Method::Delete,
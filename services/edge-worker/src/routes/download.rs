@@ -1,5 +1,9 @@
 use crate::{authenticate, AppState};
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
 use common::types::file::download_init::{InitDownloadRequest, InitDownloadResponse};
+use common::types::file::resolve::{ResolveRequest, ResolveResponse};
+use serde_json::Value;
 use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -18,21 +22,96 @@ pub async fn handle_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -
     
     let req_body = req.json::<InitDownloadRequest>().await?;
 
+    // Resolved even when an id is given, the file may be shared from another user's storage.
+    let (path, file_id) = if req_body.file_id.starts_with('/') {
+        (Some(req_body.file_id.clone()), None)
+    } else {
+        (None, Some(req_body.file_id.clone()))
+    };
+
+    let resolved = state
+        .config
+        .make_internal_request::<_, Value>(
+            "/internal/file/resolve",
+            &authenticated_user,
+            Method::Post,
+            &ResolveRequest { path, file_id },
+        )
+        .await?;
+
+    if resolved.0 != 200 {
+        return Ok(Response::from_json(&resolved.1)?.with_status(resolved.0));
+    }
+
+    let resolved: ResolveResponse = serde_json::from_value(resolved.1)?;
+
+    if resolved.is_directory {
+        return error_response(ErrorCode::BadRequest, "Directories can't be downloaded directly");
+    }
+
     let bucket = Bucket::new(Url::from_str(&url)?, UrlStyle::Path, bucket_name, "auto").unwrap();
 
     let credentials = Credentials::new(access_key.as_str(), secret_key.as_str());
 
     let presigned_url_duration = Duration::from_secs(60 * 60);
 
-    let url = format!("{}/{}", authenticated_user.id, req_body.file_id);
+    let url = format!("{}/{}", resolved.owner_id, resolved.file_id);
+
+    let inline = req_body.inline.unwrap_or(false) && previewable(&resolved.file_type);
 
     let mut action = bucket.get_object(Some(&credentials), url.as_str());
     action.query_mut()
-        .insert("response-content-disposition", format!("attachment; filename=\"{}\"", req_body.file_name));
+        .insert("response-content-disposition", content_disposition(inline, &req_body.file_name));
+
+    if inline {
+        action.query_mut().insert("response-content-type", resolved.file_type.clone());
+    }
 
     let presigned_url = action.sign(presigned_url_duration);
 
     Response::from_json(&InitDownloadResponse {
         download_url: presigned_url.to_string(),
     })
-}
\ No newline at end of file
+}
+
+/// Types a browser can show without running anything of ours, the only ones ever served inline.
+/// Everything else, HTML and SVG included, is forced to download.
+pub(crate) fn previewable(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+    essence != "image/svg+xml"
+        && (essence.starts_with("image/")
+            || essence.starts_with("video/")
+            || essence.starts_with("audio/")
+            || matches!(essence.as_str(), "application/pdf" | "text/plain"))
+}
+
+/// A `Content-Disposition` value that survives any file name: a plain ASCII `filename` for old
+/// clients and the exact name as an RFC 5987 `filename*`.
+pub(crate) fn content_disposition(inline: bool, file_name: &str) -> String {
+    let fallback = file_name
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+
+    let mut encoded = String::with_capacity(file_name.len());
+    for byte in file_name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9'
+            | b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        if inline { "inline" } else { "attachment" },
+        fallback,
+        encoded
+    )
+}
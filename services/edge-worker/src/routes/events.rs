@@ -0,0 +1,37 @@
+use crate::{authenticate, AppState};
+use std::sync::Arc;
+use worker::{Headers, Request, Response, RouteContext};
+
+/// Proxies the change event stream. The body is piped through as it arrives, the worker never
+/// buffers it.
+pub async fn handle_events(req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let cursor = req
+        .url()?
+        .query_pairs()
+        .find(|(key, _)| key == "cursor")
+        .and_then(|(_, value)| value.parse::<i64>().ok());
+
+    let path = match cursor {
+        Some(cursor) => format!("/internal/file/events?cursor={}", cursor),
+        None => "/internal/file/events".to_string(),
+    };
+
+    let last_event_id = req.headers().get("Last-Event-ID")?;
+
+    let mut upstream = state.config.open_internal_stream(&path, &user, last_event_id).await?;
+
+    if upstream.status_code() != 200 {
+        return Ok(Response::empty()?.with_status(upstream.status_code()));
+    }
+
+    let headers = Headers::new();
+    headers.set("Content-Type", "text/event-stream")?;
+    headers.set("Cache-Control", "no-cache")?;
+
+    // Rebuilt rather than returned as is, fetched responses have immutable headers and the
+    // CORS headers still need to go on.
+    Ok(Response::from_stream(upstream.stream()?)?.with_headers(headers))
+}
@@ -0,0 +1,159 @@
+use crate::routes::upload::presign_parts;
+use crate::{authenticate, AppState};
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
+use common::types::file::file_request::{
+    CreateFileRequestRequest, FileRequestCompleteRequest, FileRequestTokenRequest,
+    FileRequestUploadInternalRequest, FileRequestUploadRequest,
+};
+use common::types::file::upload_init::{InitUploadInternalResponse, InitUploadResponse};
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+use worker::{Method, Request, Response, RouteContext};
+
+pub async fn handle_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: CreateFileRequestRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/request/create",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_list(req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/request/list",
+        &user,
+        Method::Post,
+        &Value::Null
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_delete(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: FileRequestTokenRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/request/delete",
+        &user,
+        Method::Delete,
+        &payload
+    ).await?;
+
+    if response.0 == 200 {
+        return Ok(Response::empty()?.with_status(204));
+    }
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_uploads(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: FileRequestTokenRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/request/uploads",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+/// What the public upload page needs to know about a request. Anyone with the token may ask.
+pub async fn handle_info(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let state = &ctx.data;
+
+    let payload: FileRequestTokenRequest = req.json().await?;
+
+    let (status, body, _) = state.config.make_unauthenticated_internal_request::<_, Value>(
+        "/internal/request/info",
+        Method::Post,
+        &payload,
+        Some(req.headers()),
+    ).await?;
+
+    Ok(Response::from_json(&body)?.with_status(status))
+}
+
+/// Starts an anonymous upload through a file request. Works like `/upload/create`, except the
+/// authentication service checks the request's limits instead of a session.
+pub async fn handle_upload_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let state = &ctx.data;
+
+    let payload: FileRequestUploadRequest = req.json().await?;
+    let file_id = Uuid::new_v4().to_string();
+
+    if payload.part_count >= 2000 {
+        return error_response(ErrorCode::BadRequest, "Part count must be less than 2000");
+    }
+
+    let (status, body, _) = state.config.make_unauthenticated_internal_request::<_, Value>(
+        "/internal/request/upload/init",
+        Method::Post,
+        &FileRequestUploadInternalRequest {
+            token: payload.token,
+            file_id: file_id.clone(),
+            filename: payload.filename,
+            size: payload.size,
+            content_type: payload.content_type,
+            uploader_name: payload.uploader_name,
+            uploader_email: payload.uploader_email,
+        },
+        Some(req.headers()),
+    ).await?;
+
+    if status != 200 {
+        return Ok(Response::from_json(&body)?.with_status(status));
+    }
+
+    let result: InitUploadInternalResponse = serde_json::from_value(body)?;
+
+    let urls = presign_parts(state, &result.owner_id, &file_id, &result.upload_id, payload.part_count)?;
+
+    Response::from_json(&InitUploadResponse {
+        file_id,
+        upload_urls: urls,
+        upload_id: result.upload_id,
+    })
+}
+
+pub async fn handle_upload_complete(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let state = &ctx.data;
+
+    let payload: FileRequestCompleteRequest = match req.json().await {
+        Ok(payload) => payload,
+        Err(_) => return error_response(ErrorCode::BadRequest, "Invalid request body"),
+    };
+
+    let (status, body, _) = state.config.make_unauthenticated_internal_request::<_, Value>(
+        "/internal/request/upload/complete",
+        Method::Post,
+        &payload,
+        Some(req.headers()),
+    ).await?;
+
+    if status == 200 {
+        return Ok(Response::empty()?.with_status(204));
+    }
+
+    Ok(Response::from_json(&body)?.with_status(status))
+}
@@ -0,0 +1,75 @@
+use crate::{authenticate, AppState};
+use common::types::file::grant::{CreateGrantRequest, DeleteGrantRequest, ListGrantsRequest};
+use serde_json::Value;
+use std::sync::Arc;
+use worker::{Method, Request, Response, RouteContext};
+
+pub async fn handle_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: CreateGrantRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/grant/create",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    if response.0 == 200 {
+        return Ok(Response::empty()?.with_status(204));
+    }
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_list(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: ListGrantsRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/grant/list",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_delete(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: DeleteGrantRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/grant/delete",
+        &user,
+        Method::Delete,
+        &payload
+    ).await?;
+
+    if response.0 == 200 {
+        return Ok(Response::empty()?.with_status(204));
+    }
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_shared(req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/file/shared",
+        &user,
+        Method::Post,
+        &Value::Null
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
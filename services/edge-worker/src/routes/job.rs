@@ -0,0 +1,53 @@
+use crate::{authenticate, AppState};
+use common::types::file::job::{JobOperation, JobRequest};
+use serde_json::Value;
+use std::sync::Arc;
+use worker::{Method, Request, Response, RouteContext};
+
+pub async fn handle_submit(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: JobOperation = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/job/submit",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_status(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: JobRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/job/status",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_cancel(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: JobRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/job/cancel",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
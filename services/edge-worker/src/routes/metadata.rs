@@ -33,10 +33,14 @@ pub async fn handle_metadata_inner(
         return Ok(Response::empty()?.with_status(metadata.0));
     }
 
-    kv.put(&cache_key, &metadata.1)?
-        .expiration_ttl(3600)
-        .execute()
-        .await?;
+    // A directory's size and item count cover everything below it and change with any upload,
+    // move or delete in the subtree, so only files are cached.
+    if !metadata.1.is_directory {
+        kv.put(&cache_key, &metadata.1)?
+            .expiration_ttl(3600)
+            .execute()
+            .await?;
+    }
 
     Ok(Response::from_json(&metadata.1)?.with_status(metadata.0))
 }
@@ -9,7 +9,20 @@ pub(crate) mod r#move;
 pub(crate) mod directory;
 pub(crate) mod list;
 pub(crate) mod zip;
+pub(crate) mod tar;
 pub(crate) mod share;
 pub(crate) mod share_download;
+pub(crate) mod share_folder;
+pub(crate) mod share_access;
+pub(crate) mod share_collection;
+pub(crate) mod share_stream;
 pub(crate) mod user_refresh;
-pub(crate) mod user_logout;
\ No newline at end of file
+pub(crate) mod user_logout;
+pub(crate) mod resolve;
+pub(crate) mod job;
+pub(crate) mod audit;
+pub(crate) mod webhook;
+pub(crate) mod changes;
+pub(crate) mod events;
+pub(crate) mod grant;
+pub(crate) mod file_request;
@@ -0,0 +1,21 @@
+use crate::{authenticate, AppState};
+use common::types::file::resolve::ResolveRequest;
+use serde_json::Value;
+use std::sync::Arc;
+use worker::{Method, Request, Response, RouteContext};
+
+pub async fn handle_resolve(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: ResolveRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/file/resolve",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
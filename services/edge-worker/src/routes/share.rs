@@ -1,8 +1,26 @@
+use crate::authentication::authentication::AuthenticatedUser;
+use crate::authentication::share_password::hash_password;
+use crate::authentication::share_token::{is_signed, ShareKeys};
 use crate::{AppState, authenticate};
-use common::types::file::file_claims::FileShare;
-use common::types::file::share::{ShareRequest, ShareResponse};
+use crate::types::error::error_response;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use common::types::error::ErrorCode;
+use common::types::file::file_claims::{FileShare, SignedShareClaims};
+use common::types::file::share::{
+    RevokedShare, SharePermission, ShareRequest, ShareResponse, ShareRevokeRequest, ShareRevokeResponse,
+    ShareSaveRequest, ShareTokenRequest,
+};
+use common::types::file::share_collection::{ShareCollectionRequest, ShareCollectionSaveRequest};
+use serde_json::Value;
 use std::sync::Arc;
-use worker::{Request, Response, RouteContext};
+use worker::{kv::KvStore, Method, Request, Response, RouteContext};
+
+/// KV won't expire a key any sooner than this.
+const MIN_SHARE_TTL: i64 = 60;
+
+/// How long the token of an expired link is kept, so the share page can say it expired rather
+/// than that it never existed.
+const EXPIRED_GRACE_DAYS: i64 = 30;
 
 pub async fn handle_share(
     mut req: Request,
@@ -10,27 +28,475 @@ pub async fn handle_share(
 ) -> worker::Result<Response> {
     let user = authenticate!(&req, &ctx);
 
-    let payload: ShareRequest = req.json().await?;
+    let mut payload: ShareRequest = req.json().await?;
+
+    if payload.signed.unwrap_or(false) {
+        return issue_signed(&ctx, &user, payload).await;
+    }
+
+    // Hashed here and never passed on to the authentication service.
+    let password = match payload.password.take() {
+        Some(password) if password.is_empty() => Some(None),
+        Some(password) => Some(Some(hash_password(&password)?)),
+        None => None,
+    };
+
+    let now = Utc::now();
+    let expires_at = match requested_expiry(now, payload.expires_in, payload.expires_at)? {
+        Ok(expires_at) => expires_at,
+        Err(denied) => return Ok(denied),
+    };
+
+    let response = ctx.data.config.make_internal_request::<_, Value>(
+        "/internal/file/share",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    if response.0 != 200 {
+        return Ok(Response::empty()?.with_status(response.0));
+    }
 
     let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
     let file_lookup_key = format!("file_map:{}:{}", user.id, payload.file_id);
 
+    let existing_token = kv.get(&file_lookup_key).text().await?;
+    let current = match &existing_token {
+        Some(existing_token) => cached_share(&ctx.data, &kv, existing_token).await?,
+        None => None,
+    }
+    // A link about to run out is treated as gone, its expiry couldn't be carried over anyway.
+    .filter(|current| {
+        current
+            .expires_at
+            .is_none_or(|at| (at.with_timezone(&Utc) - now).num_seconds() >= MIN_SHARE_TTL)
+    });
+
+    let rotate = payload.rotate.unwrap_or(false);
+    let reusable = current.is_some();
+
+    // Settings that weren't sent carry over, including onto a rotated token.
+    let (current_expiry, current_hash, current_limit) = current
+        .map(|current| (current.expires_at, current.password_hash, current.max_downloads))
+        .unwrap_or_default();
+
     let claims = FileShare {
         file_id: payload.file_id,
+        expires_at: expires_at
+            .map(|expires_at| expires_at.fixed_offset())
+            .or(current_expiry),
+        password_hash: password.unwrap_or(current_hash),
+        max_downloads: match payload.max_downloads {
+            Some(0) => None,
+            Some(max_downloads) => Some(max_downloads),
+            None => current_limit,
+        },
+        collection: false,
+        signed: false,
+        view_only: false,
     };
 
-    if let Some(existing_token) = kv.get(&file_lookup_key).text().await? {
-        return Ok(Response::from_json(&ShareResponse {
-            token: existing_token,
-        })?);
+    let (token, replaces) = match existing_token {
+        Some(existing_token) if rotate => (nanoid::nanoid!(8), Some(existing_token)),
+        Some(existing_token) if reusable => (existing_token, None),
+        _ => (nanoid::nanoid!(8), None),
+    };
+
+    // The record has to exist before the token is cached, or the first download could slip past
+    // its counter. Saving an unchanged link also records links made before the share table.
+    let save = ShareSaveRequest {
+        token: token.clone(),
+        file_id: claims.file_id.clone(),
+        password_hash: claims.password_hash.clone(),
+        expires_at: claims.expires_at,
+        max_downloads: claims.max_downloads,
+        replaces: replaces.clone(),
+    };
+
+    let response = ctx.data.config.make_internal_request::<_, Value>(
+        "/internal/share/save",
+        &user,
+        Method::Post,
+        &save
+    ).await?;
+
+    if response.0 != 200 {
+        return Ok(Response::empty()?.with_status(response.0));
     }
 
-    let download_id = nanoid::nanoid!(8);
+    if let Some(replaces) = replaces {
+        kv.delete(&replaces).await?;
+    }
+
+    store(&kv, &token, &file_lookup_key, &claims).await?;
+
+    Response::from_json(&ShareResponse {
+        token,
+        expires_at: claims.expires_at,
+        password_protected: claims.password_hash.is_some(),
+        max_downloads: claims.max_downloads,
+    })
+}
+
+/// A self-contained link: everything the edge needs to serve it is in the token, only its
+/// revocation is looked up. Nothing is stored, so there is nothing to count downloads against.
+async fn issue_signed(
+    ctx: &RouteContext<Arc<AppState>>,
+    user: &AuthenticatedUser,
+    payload: ShareRequest,
+) -> worker::Result<Response> {
+    if payload.password.as_deref().is_some_and(|password| !password.is_empty())
+        || payload.max_downloads.is_some_and(|max_downloads| max_downloads > 0)
+    {
+        return error_response(ErrorCode::BadRequest, "Signed links can't have a password or a download limit");
+    }
+
+    let mut permissions = payload
+        .permissions
+        .clone()
+        .unwrap_or_else(|| vec![SharePermission::View, SharePermission::Download]);
+    permissions.sort_by_key(|permission| *permission as u8);
+    permissions.dedup();
+
+    if permissions.is_empty() {
+        return error_response(ErrorCode::BadRequest, "A signed link needs at least one permission");
+    }
+
+    let expires_at = match requested_expiry(Utc::now(), payload.expires_in, payload.expires_at)? {
+        Ok(expires_at) => expires_at,
+        Err(denied) => return Ok(denied),
+    };
+
+    // Checks ownership and leaves the share in the audit log, like any other link.
+    let response = ctx.data.config.make_internal_request::<_, Value>(
+        "/internal/file/share",
+        user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    if response.0 != 200 {
+        return Ok(Response::empty()?.with_status(response.0));
+    }
+
+    let claims = SignedShareClaims {
+        file_id: payload.file_id,
+        owner_id: user.id.clone(),
+        expires_at: expires_at.map(|expires_at| expires_at.timestamp()),
+        permissions,
+        id: nanoid::nanoid!(12),
+    };
+
+    let token = ShareKeys::parse(&ctx.data.config.share_secret).sign(&claims)?;
+
+    Response::from_json(&ShareResponse {
+        token,
+        expires_at: expires_at.map(|expires_at| expires_at.fixed_offset()),
+        password_protected: false,
+        max_downloads: None,
+    })
+}
+
+/// Issues one link for several files. Unlike single files, collections aren't looked up by
+/// their contents, so every call makes a new token.
+pub async fn handle_share_collection(
+    mut req: Request,
+    ctx: RouteContext<Arc<AppState>>,
+) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+
+    let payload: ShareCollectionRequest = req.json().await?;
+
+    let password_hash = match payload.password.as_deref() {
+        Some(password) if !password.is_empty() => Some(hash_password(password)?),
+        _ => None,
+    };
+
+    let expires_at = match requested_expiry(Utc::now(), payload.expires_in, payload.expires_at)? {
+        Ok(expires_at) => expires_at.map(|expires_at| expires_at.fixed_offset()),
+        Err(denied) => return Ok(denied),
+    };
+
+    let claims = FileShare {
+        file_id: String::new(),
+        expires_at,
+        password_hash,
+        max_downloads: payload.max_downloads.filter(|max_downloads| *max_downloads > 0),
+        collection: true,
+        signed: false,
+        view_only: false,
+    };
+
+    let token = nanoid::nanoid!(8);
+
+    let save = ShareCollectionSaveRequest {
+        token: token.clone(),
+        file_ids: payload.file_ids,
+        title: payload.title,
+        password_hash: claims.password_hash.clone(),
+        expires_at: claims.expires_at,
+        max_downloads: claims.max_downloads,
+    };
+
+    let response = ctx.data.config.make_internal_request::<_, Value>(
+        "/internal/share/collection",
+        &user,
+        Method::Post,
+        &save
+    ).await?;
+
+    if response.0 != 200 {
+        return Ok(Response::from_json(&response.1)?.with_status(response.0));
+    }
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    cache_token(&kv, &token, &claims).await?;
+
+    Response::from_json(&ShareResponse {
+        token,
+        expires_at: claims.expires_at,
+        password_protected: claims.password_hash.is_some(),
+        max_downloads: claims.max_downloads,
+    })
+}
+
+/// The expiry asked for, `expires_in` winning over `expires_at`. `Err` holds the 400 for one
+/// that's too close for KV to honour or too far out to represent.
+fn requested_expiry(
+    now: DateTime<Utc>,
+    expires_in: Option<u64>,
+    expires_at: Option<DateTime<FixedOffset>>,
+) -> worker::Result<std::result::Result<Option<DateTime<Utc>>, Response>> {
+    let expires_at = match (expires_in, expires_at) {
+        (Some(seconds), _) => i64::try_from(seconds)
+            .ok()
+            .and_then(Duration::try_seconds)
+            .and_then(|ttl| now.checked_add_signed(ttl)),
+        (None, Some(expires_at)) => Some(expires_at.with_timezone(&Utc)),
+        (None, None) => return Ok(Ok(None)),
+    };
+
+    // Tokens stay cached for a grace period past their expiry, which has to fit as well.
+    let Some(expires_at) = expires_at
+        .filter(|expires_at| expires_at.checked_add_signed(Duration::days(EXPIRED_GRACE_DAYS)).is_some())
+    else {
+        return Ok(Err(error_response(ErrorCode::BadRequest, "Expiry is too far in the future")?));
+    };
+
+    if (expires_at - now).num_seconds() < MIN_SHARE_TTL {
+        return Ok(Err(error_response(ErrorCode::BadRequest, "Expiry must be at least a minute in the future")?));
+    }
+
+    Ok(Ok(Some(expires_at)))
+}
+
+pub async fn handle_list(req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+
+    let response = ctx.data.config.make_internal_request::<_, Value>(
+        "/internal/share/list",
+        &user,
+        Method::Post,
+        &Value::Null
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+/// Revokes on the authentication service first, then drops whatever the edge still caches for
+/// the revoked tokens.
+pub async fn handle_revoke(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+
+    let payload: ShareRevokeRequest = req.json().await?;
+
+    if let Some(token) = payload.token.as_deref().filter(|token| is_signed(token)) {
+        return revoke_signed(&ctx, &user, token).await;
+    }
+
+    let (status, revoked) = ctx.data.config.make_internal_request::<_, Value>(
+        "/internal/share/revoke",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    if status != 200 {
+        return Ok(Response::empty()?.with_status(status));
+    }
+
+    let revoked: ShareRevokeResponse = serde_json::from_value(revoked)?;
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+
+    for share in &revoked.revoked {
+        kv.delete(&share.token).await?;
+
+        let Some(file_id) = &share.file_id else {
+            continue;
+        };
+
+        let file_lookup_key = format!("file_map:{}:{}", user.id, file_id);
+        if kv.get(&file_lookup_key).text().await?.as_deref() == Some(share.token.as_str()) {
+            kv.delete(&file_lookup_key).await?;
+        }
+    }
+
+    Response::from_json(&revoked)
+}
+
+/// Signed tokens aren't stored anywhere, revoking one puts its id on a list in KV until the
+/// token would have expired anyway. Only the owner can revoke, and they present the token.
+async fn revoke_signed(
+    ctx: &RouteContext<Arc<AppState>>,
+    user: &AuthenticatedUser,
+    token: &str,
+) -> worker::Result<Response> {
+    let Some(claims) = ShareKeys::parse(&ctx.data.config.share_secret)
+        .verify(token)
+        .filter(|claims| claims.owner_id == user.id)
+    else {
+        return Ok(Response::empty()?.with_status(404));
+    };
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    let revoked_put = kv.put(&revoked_key(&claims.id), "1")?;
+
+    match claims.expires_at {
+        None => revoked_put.execute().await?,
+        Some(expires_at) if expires_at - Utc::now().timestamp() >= MIN_SHARE_TTL => {
+            revoked_put.expiration(expires_at as u64).execute().await?
+        }
+        // Too close to expiring for KV to take the exact time, the entry outlives it a little.
+        Some(_) => revoked_put.expiration_ttl(MIN_SHARE_TTL as u64).execute().await?,
+    }
+
+    Response::from_json(&ShareRevokeResponse {
+        revoked: vec![RevokedShare {
+            token: token.to_string(),
+            file_id: Some(claims.file_id),
+        }],
+    })
+}
+
+fn revoked_key(id: &str) -> String {
+    format!("share_revoked:{}", id)
+}
+
+/// A signed token checked against its signature and the revocation list, `None` for a forged,
+/// unknown-key or revoked one.
+async fn signed_share(state: &AppState, kv: &KvStore, token: &str) -> worker::Result<Option<FileShare>> {
+    let Some(claims) = ShareKeys::parse(&state.config.share_secret).verify(token) else {
+        return Ok(None);
+    };
+
+    if kv.get(&revoked_key(&claims.id)).text().await?.is_some() {
+        return Ok(None);
+    }
+
+    Ok(Some(FileShare {
+        file_id: claims.file_id,
+        expires_at: claims
+            .expires_at
+            .and_then(|expires_at| DateTime::from_timestamp(expires_at, 0))
+            .map(|expires_at| expires_at.fixed_offset()),
+        password_hash: None,
+        max_downloads: None,
+        collection: false,
+        signed: true,
+        view_only: !claims.permissions.contains(&SharePermission::Download),
+    }))
+}
+
+/// A share from the cache, refilled from the authentication service when the token has fallen
+/// out of it. Signed tokens carry their own claims and skip both.
+pub(crate) async fn cached_share(
+    state: &AppState,
+    kv: &KvStore,
+    token: &str,
+) -> worker::Result<Option<FileShare>> {
+    if is_signed(token) {
+        return signed_share(state, kv, token).await;
+    }
+
+    if let Some(claims) = kv.get(token).json::<FileShare>().await? {
+        return Ok(Some(claims));
+    }
+
+    let claims = resolve_share(state, token).await?;
+    if let Some(claims) = &claims {
+        cache_token(kv, token, claims).await?;
+    }
+
+    Ok(claims)
+}
+
+/// A share as the authentication service has it right now, for serving visitors. Revoking a
+/// link evicts it from the cache, but KV takes a while to forget it everywhere, so a cached
+/// copy could keep a revoked link open. Signed tokens carry their own claims.
+pub(crate) async fn current_share(
+    state: &AppState,
+    kv: &KvStore,
+    token: &str,
+) -> worker::Result<Option<FileShare>> {
+    if is_signed(token) {
+        return signed_share(state, kv, token).await;
+    }
+
+    resolve_share(state, token).await
+}
+
+async fn resolve_share(state: &AppState, token: &str) -> worker::Result<Option<FileShare>> {
+    let (status, claims, _) = state.config.make_unauthenticated_internal_request::<_, Value>(
+        "/internal/share/resolve",
+        Method::Post,
+        &ShareTokenRequest { token: token.to_string() },
+        None
+    ).await?;
+
+    match status {
+        200 => Ok(Some(serde_json::from_value(claims)?)),
+        // Revoked links answer 410, to a visitor they are gone the same as unknown ones.
+        404 | 410 => Ok(None),
+        status => Err(worker::Error::from(format!("Failed to resolve the share token: {}", status))),
+    }
+}
+
+/// Writes both keys of a share. The reverse key goes away the moment the link expires so the
+/// next share of the file issues a fresh token, the token itself lingers for the grace period.
+async fn store(
+    kv: &KvStore,
+    token: &str,
+    file_lookup_key: &str,
+    claims: &FileShare,
+) -> worker::Result<()> {
+    cache_token(kv, token, claims).await?;
+
+    let lookup_put = kv.put(file_lookup_key, token)?;
+    match claims.expires_at {
+        Some(expires_at) => lookup_put.expiration(expiry_timestamp(expires_at)).execute().await?,
+        None => lookup_put.execute().await?,
+    }
+
+    Ok(())
+}
+
+async fn cache_token(kv: &KvStore, token: &str, claims: &FileShare) -> worker::Result<()> {
+    let token_put = kv.put(token, serde_json::to_string(claims)?)?;
+
+    match claims.expires_at {
+        Some(expires_at) => {
+            token_put
+                .expiration(expiry_timestamp(expires_at + Duration::days(EXPIRED_GRACE_DAYS)))
+                .execute()
+                .await?
+        }
+        None => token_put.execute().await?,
+    }
 
-    kv.put(&download_id, serde_json::to_string(&claims)?)?
-        .execute()
-        .await?;
-    kv.put(&file_lookup_key, &download_id)?.execute().await?;
+    Ok(())
+}
 
-    Ok(Response::from_json(&ShareResponse { token: download_id })?)
-}
\ No newline at end of file
+fn expiry_timestamp(at: DateTime<FixedOffset>) -> u64 {
+    at.timestamp().max(0) as u64
+}
@@ -0,0 +1,119 @@
+use crate::authentication::share_token::{is_signed, ShareKeys};
+use crate::{authenticate, AppState};
+use common::types::file::share_access::{ShareAccessRecordRequest, ShareAccessRequest};
+use serde_json::Value;
+use std::sync::Arc;
+use worker::{console_error, console_log, Method, Request, Response, RouteContext};
+
+/// One visit to a share link, written to the link's history once the outcome is known.
+pub(crate) struct ShareVisit {
+    pub token: String,
+    action: &'static str,
+    file_id: Option<String>,
+    country: Option<String>,
+    asn: Option<u32>,
+    as_organization: Option<String>,
+    user_agent: Option<String>,
+    /// Keys failed password attempts, never written to the history.
+    pub client_ip: Option<String>,
+}
+
+impl ShareVisit {
+    /// Reads where the visitor comes from off Cloudflare's `cf` object, which is missing when
+    /// running locally.
+    pub(crate) fn new(req: &Request, token: &str, action: &'static str) -> Self {
+        let cf = req.cf();
+
+        ShareVisit {
+            token: token.to_string(),
+            action,
+            file_id: None,
+            country: cf.and_then(|cf| cf.country()),
+            asn: cf.and_then(|cf| cf.asn()),
+            as_organization: cf.and_then(|cf| cf.as_organization()),
+            user_agent: req.headers().get("User-Agent").ok().flatten(),
+            client_ip: req.headers().get("CF-Connecting-IP").ok().flatten(),
+        }
+    }
+
+    /// Files the visit under another action once it turns out to be something else, such as
+    /// the landing page of a folder share.
+    pub(crate) fn action(mut self, action: &'static str) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// The entry fetched from inside a shared folder.
+    pub(crate) fn file(mut self, file_id: &str) -> Self {
+        self.file_id = Some(file_id.to_string());
+        self
+    }
+
+    /// Best effort, a visitor never gets an error because their visit couldn't be written down.
+    /// The write runs after the response has gone out, the visitor doesn't wait for it.
+    ///
+    /// Signed tokens have no record to attach a history to, so their owners can't see who
+    /// opened them. Their visits only go to the worker's log, under the id in their claims.
+    pub(crate) fn record(&self, state: &AppState, outcome: &str) {
+        if is_signed(&self.token) {
+            if let Some(claims) = ShareKeys::parse(&state.config.share_secret).verify(&self.token) {
+                console_log!(
+                    "Signed share {} {}: {} (country {:?}, asn {:?})",
+                    claims.id,
+                    self.action,
+                    outcome,
+                    self.country,
+                    self.asn,
+                );
+            }
+            return;
+        }
+
+        let payload = ShareAccessRecordRequest {
+            token: self.token.clone(),
+            file_id: self.file_id.clone(),
+            action: self.action.to_string(),
+            outcome: outcome.to_string(),
+            country: self.country.clone(),
+            asn: self.asn,
+            as_organization: self.as_organization.clone(),
+            user_agent: self.user_agent.clone(),
+        };
+
+        let config = state.config.clone();
+
+        state.ctx.wait_until(async move {
+            let recorded = config
+                .make_unauthenticated_internal_request::<_, Value>(
+                    "/internal/share/record",
+                    Method::Post,
+                    &payload,
+                    None,
+                )
+                .await;
+
+            match recorded {
+                Ok((200 | 404, _, _)) => {}
+                Ok((status, _, _)) => console_error!("Recording a share visit failed with {}", status),
+                Err(err) => console_error!("Recording a share visit failed: {}", err),
+            }
+        });
+    }
+}
+
+/// Who opened one of the caller's links, from where, and whether they got the file.
+pub async fn handle_access(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: ShareAccessRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/share/access",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
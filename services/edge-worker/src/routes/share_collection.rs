@@ -0,0 +1,94 @@
+use crate::AppState;
+use crate::routes::share_access::ShareVisit;
+use crate::routes::share_download::{open_share, owner_name};
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
+use common::types::file::metadata::MetadataResponse;
+use common::types::file::share::ShareTokenRequest;
+use common::types::file::share_collection::{ShareItemsRequest, ShareItemsResponse, SharedItemsResponse};
+use serde_json::Value;
+use std::sync::Arc;
+use worker::*;
+
+/// Lists the files of a collection with their sizes. Listing isn't counted as a download.
+pub async fn handle_share_items(
+    mut req: Request,
+    ctx: RouteContext<Arc<AppState>>,
+) -> Result<Response> {
+    let payload: ShareItemsRequest = req.json().await?;
+    let visit = ShareVisit::new(&req, &payload.token, "browse");
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    let claims = match open_share(&ctx.data, &kv, &visit, payload.password.as_deref()).await? {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    if !claims.collection {
+        return error_response(ErrorCode::NotFound, "Link not found");
+    }
+
+    let Some(collection) = collection_items(&ctx.data, &payload.token).await? else {
+        return error_response(ErrorCode::NotFound, "Link not found");
+    };
+
+    let owner = owner_name(&ctx, &collection.owner_id).await?;
+    visit.record(&ctx.data, "ok");
+
+    Response::from_json(&ShareItemsResponse {
+        title: collection.title,
+        owner,
+        total_size: collection.items.iter().map(|item| item.file_size).sum(),
+        items: collection.items,
+    })
+}
+
+/// The files of a collection as the authentication service has them, `None` once the token is
+/// gone.
+pub(crate) async fn collection_items(state: &AppState, token: &str) -> Result<Option<SharedItemsResponse>> {
+    let (status, items, _) = state
+        .config
+        .make_unauthenticated_internal_request::<_, Value>(
+            "/internal/share/items",
+            Method::Post,
+            &ShareTokenRequest {
+                token: token.to_string(),
+            },
+            None,
+        )
+        .await?;
+
+    match status {
+        200 => Ok(Some(serde_json::from_value(items)?)),
+        404 => Ok(None),
+        status => Err(Error::from(format!("Failed to list the collection: {}", status))),
+    }
+}
+
+/// One file of a collection in the shape of a metadata lookup, for presigning.
+pub(crate) async fn collection_entry(
+    state: &AppState,
+    token: &str,
+    file_id: &str,
+) -> Result<Option<MetadataResponse>> {
+    let Some(collection) = collection_items(state, token).await? else {
+        return Ok(None);
+    };
+
+    let owner_id = collection.owner_id;
+
+    Ok(collection
+        .items
+        .into_iter()
+        .find(|item| item.id == file_id)
+        .map(|item| MetadataResponse {
+            file_name: item.file_name,
+            size: item.file_size,
+            content_type: item.file_type,
+            path: String::new(),
+            created_at: item.created_at,
+            owner_id,
+            item_count: 0,
+            is_directory: false,
+        }))
+}
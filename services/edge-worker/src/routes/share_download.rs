@@ -1,7 +1,18 @@
 use crate::AppState;
+use crate::authentication::share_password::verify_password;
+use crate::routes::download::{content_disposition, previewable};
+use crate::routes::share::current_share;
+use crate::routes::share_access::ShareVisit;
+use crate::routes::share_collection::collection_items;
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
 use common::types::file::file_claims::FileShare;
 use common::types::file::metadata::{MetadataRequest, MetadataResponse};
-use common::types::file::share::{ShareDownloadRequest, ShareDownloadResponse};
+use common::types::file::share::{
+    ShareConsumeResponse, ShareDownloadRequest, ShareDownloadResponse,
+    ShareExhaustedResponse, ShareExpiredResponse, SharePasswordResponse, ShareRevokedResponse,
+    ShareTokenRequest,
+};
 use common::types::user::user_info::{UserInfoPublicResponse, UserInfoRequest};
 use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
 use std::str::FromStr;
@@ -17,14 +28,38 @@ pub async fn handle_share_download(
     let state = ctx.data.clone();
 
     let payload: ShareDownloadRequest = req.json().await?;
+    let metadata_only = payload.metadata_only.unwrap_or(false);
+    let visit = ShareVisit::new(&req, &payload.token, if metadata_only { "view" } else { "download" });
 
     let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
 
-    let claims = match kv.get(&payload.token).json::<FileShare>().await? {
-        Some(c) => c,
-        None => return Response::error("Link expired or invalid", 410),
+    let claims = match open_share(&state, &kv, &visit, payload.password.as_deref()).await? {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
     };
 
+    // A collection's files are listed and downloaded one by one or zipped, like a folder's.
+    if claims.collection {
+        let Some(collection) = collection_items(&state, &payload.token).await? else {
+            return error_response(ErrorCode::NotFound, "Link not found");
+        };
+
+        let owner = owner_name(&ctx, &collection.owner_id).await?;
+        visit.action("view").record(&state, "ok");
+
+        return Response::from_json(&ShareDownloadResponse {
+            presigned_url: String::new(),
+            file_type: String::new(),
+            file_name: collection.title,
+            file_size: collection.items.iter().map(|item| item.file_size).sum(),
+            created_at: collection.created_at.to_string(),
+            owner,
+            downloads_remaining: None,
+            is_directory: false,
+            is_collection: true,
+        });
+    }
+
     let metadata_request = MetadataRequest {
         file_id: claims.file_id.clone(),
     };
@@ -40,8 +75,162 @@ pub async fn handle_share_download(
 
     let res = metadata_response.json::<MetadataResponse>().await?;
 
+    let owner = owner_name(&ctx, &res.owner_id).await?;
+
+    // Folders are browsed and zipped through their own routes, there is nothing to presign.
+    if res.is_directory || (metadata_only && (claims.max_downloads.is_some() || claims.view_only)) {
+        visit.action("view").record(&state, "ok");
+
+        return Response::from_json(&ShareDownloadResponse {
+            presigned_url: String::new(),
+            file_type: res.content_type,
+            file_name: res.file_name,
+            file_size: res.size,
+            created_at: res.created_at.to_string(),
+            owner,
+            downloads_remaining: None,
+            is_directory: res.is_directory,
+            is_collection: false,
+        });
+    }
+
+    let downloads_remaining = if metadata_only {
+        visit.record(&state, "ok");
+        None
+    } else {
+        match consume_download(&state, &visit, &claims).await? {
+            Ok(remaining) => remaining,
+            Err(denied) => return Ok(denied),
+        }
+    };
+
+    let inline_type = inline_type(payload.inline, &res.content_type);
+    let presigned_url = presign(&state, &res.owner_id, &claims.file_id, &res.file_name, inline_type)?;
+
+    Response::from_json(&ShareDownloadResponse {
+        presigned_url,
+        file_type: res.content_type,
+        file_name: res.file_name,
+        file_size: res.size,
+        created_at: res.created_at.to_string(),
+        owner,
+        downloads_remaining,
+        is_directory: false,
+        is_collection: false,
+    })
+}
+
+/// Looks the token up, past the cache so a revoked link is refused on every path, and checks
+/// its expiry and password. `Err` holds the response to send back instead, and the refusal goes
+/// into the link's history.
+pub(crate) async fn open_share(
+    state: &AppState,
+    kv: &kv::KvStore,
+    visit: &ShareVisit,
+    password: Option<&str>,
+) -> Result<std::result::Result<FileShare, Response>, Error> {
+    let claims = match current_share(state, kv, &visit.token).await? {
+        Some(c) => c,
+        None => return Ok(Err(error_response(ErrorCode::NotFound, "Link not found")?)),
+    };
+
+    if let Some(expired_at) = claims.expires_at
+        && expired_at <= chrono::Utc::now()
+    {
+        visit.record(state, "expired");
+        return Ok(Err(expired_response(expired_at)?));
+    }
+
+    if let Some(password_hash) = &claims.password_hash
+        && let Some((outcome, denied)) = check_password(kv, visit, password, password_hash).await?
+    {
+        visit.record(state, outcome);
+        return Ok(Err(denied));
+    }
+
+    Ok(Ok(claims))
+}
+
+/// Counts one download of a link, called right before the URL is handed out. `Ok` holds the
+/// downloads left for links with a limit, `Err` the response to send back instead. Either way
+/// the visit goes into the link's history.
+pub(crate) async fn consume_download(
+    state: &AppState,
+    visit: &ShareVisit,
+    claims: &FileShare,
+) -> Result<std::result::Result<Option<u32>, Response>, Error> {
+    if claims.view_only {
+        visit.record(state, "view_only");
+        return Ok(Err(error_response(ErrorCode::Forbidden, "This link doesn't allow downloads")?));
+    }
+
+    if claims.signed {
+        return Ok(Ok(None));
+    }
+
+    let consume = ShareTokenRequest {
+        token: visit.token.clone(),
+    };
+
+    let (status, counted, _) = state
+        .config
+        .make_unauthenticated_internal_request::<_, serde_json::Value>(
+            "/internal/share/consume",
+            Method::Post,
+            &consume,
+            None,
+        )
+        .await?;
+
+    // Revoked and rotated tokens keep their record, so a copy still cached here is refused.
+    let revoked = status == 410 && serde_json::from_value::<ShareRevokedResponse>(counted.clone()).is_ok();
+
+    let counted = match (status, claims.max_downloads) {
+        _ if revoked => Ok(Err(error_response(ErrorCode::NotFound, "Link not found")?)),
+        (200, _) => Ok(Ok(serde_json::from_value::<ShareConsumeResponse>(counted)?.remaining)),
+        // A limited token without a record is treated as used up rather than unlimited.
+        (404 | 410, Some(max_downloads)) => Ok(Err(Response::from_json(&ShareExhaustedResponse {
+            error: "exhausted".to_string(),
+            max_downloads,
+        })?
+        .with_status(410))),
+        // Cached before the share table existed, there is nothing to count against.
+        (404, None) => Ok(Ok(None)),
+        (410, None) => Ok(Err(expired_response(
+            claims.expires_at.unwrap_or_else(|| chrono::Utc::now().fixed_offset()),
+        )?)),
+        (status, _) => Ok(Err(error_response(ErrorCode::from_status(status), "Failed to count the download")?)),
+    };
+
+    let outcome = match (&counted, status) {
+        (Ok(Ok(_)), _) => "ok",
+        _ if revoked => "revoked",
+        (_, 404 | 410) if claims.max_downloads.is_some() => "exhausted",
+        (_, 410) => "expired",
+        _ => "error",
+    };
+    visit.record(state, outcome);
+
+    counted
+}
+
+/// The type to serve a file inline as, when that was asked for and the browser can show it.
+pub(crate) fn inline_type(inline: Option<bool>, content_type: &str) -> Option<&str> {
+    (inline.unwrap_or(false) && previewable(content_type)).then_some(content_type)
+}
+
+fn expired_response(expired_at: chrono::DateTime<chrono::FixedOffset>) -> Result<Response, Error> {
+    Ok(Response::from_json(&ShareExpiredResponse {
+        error: "expired".to_string(),
+        expired_at,
+    })?
+    .with_status(410))
+}
+
+/// The public name of a share's owner.
+pub(crate) async fn owner_name(ctx: &RouteContext<Arc<AppState>>, owner_id: &str) -> Result<String, Error> {
     let user_request = UserInfoRequest {
-        account_id: res.owner_id.clone(),
+        account_id: owner_id.to_string(),
     };
 
     let user_body_str = serde_json::to_string(&user_request)?;
@@ -52,9 +241,21 @@ pub async fn handle_share_download(
 
     let user_req = Request::new_with_init("http://internal/user", &user_init)?;
 
-    let mut user_req = crate::routes::user_info::handle_info_inner(user_req, &ctx).await?;
+    let mut user_req = crate::routes::user_info::handle_info_inner(user_req, ctx).await?;
     let user_res = user_req.json::<UserInfoPublicResponse>().await?;
 
+    Ok(user_res.username)
+}
+
+/// A short-lived download URL for one of the owner's objects. With `inline_type` it opens in
+/// the browser as that type instead, callers check it with `previewable` first.
+pub(crate) fn presign(
+    state: &AppState,
+    owner_id: &str,
+    file_id: &str,
+    file_name: &str,
+    inline_type: Option<&str>,
+) -> Result<String, Error> {
     let access_key = state.config.access_key.clone();
     let secret_key = state.config.secret_key.clone();
     let bucket_name = state.config.bucket.clone();
@@ -69,22 +270,84 @@ pub async fn handle_share_download(
     .map_err(|e| Error::from(e.to_string()))?;
 
     let credentials = Credentials::new(access_key.as_str(), secret_key.as_str());
-    let s3_path = format!("{}/{}", res.owner_id, claims.file_id);
+    let s3_path = format!("{}/{}", owner_id, file_id);
 
     let mut action = bucket.get_object(Some(&credentials), &s3_path);
     action.query_mut().insert(
         "response-content-disposition",
-        format!("attachment; filename=\"{}\"", res.file_name),
+        content_disposition(inline_type.is_some(), file_name),
     );
 
-    let presigned_url = action.sign(Duration::from_secs(300));
+    if let Some(content_type) = inline_type {
+        action.query_mut().insert("response-content-type", content_type.to_string());
+    }
 
-    Response::from_json(&ShareDownloadResponse {
-        presigned_url: presigned_url.to_string(),
-        file_type: res.content_type,
-        file_name: res.file_name,
-        file_size: res.size,
-        created_at: res.created_at.to_string(),
-        owner: user_res.username,
-    })
+    Ok(action.sign(Duration::from_secs(300)).to_string())
+}
+
+/// Failed password attempts allowed per token and client address within the window below.
+const MAX_PASSWORD_ATTEMPTS: u32 = 5;
+const PASSWORD_ATTEMPT_WINDOW: u64 = 15 * 60;
+
+/// `None` when the request may go ahead, otherwise the reason and the response to send back.
+///
+/// Failures are counted in KV per token and client address. Counting per token alone would let
+/// anyone holding the link lock its recipients out with five wrong guesses. The price is that
+/// a guesser spread over many addresses gets five tries from each, which the cost of every
+/// PBKDF2 check still keeps slow. The count is approximate under concurrent guessing, KV being
+/// eventually consistent.
+async fn check_password(
+    kv: &kv::KvStore,
+    visit: &ShareVisit,
+    password: Option<&str>,
+    password_hash: &str,
+) -> Result<Option<(&'static str, Response)>, Error> {
+    let attempts_key = format!(
+        "share_attempts:{}:{}",
+        visit.token,
+        visit.client_ip.as_deref().unwrap_or("unknown"),
+    );
+    let attempts = kv
+        .get(&attempts_key)
+        .text()
+        .await?
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    if attempts >= MAX_PASSWORD_ATTEMPTS {
+        return password_response("too_many_attempts", None, Some(PASSWORD_ATTEMPT_WINDOW), 429);
+    }
+
+    let Some(password) = password.filter(|password| !password.is_empty()) else {
+        return password_response("password_required", None, None, 401);
+    };
+
+    if verify_password(password, password_hash) {
+        return Ok(None);
+    }
+
+    let attempts = attempts + 1;
+    kv.put(&attempts_key, attempts.to_string())?
+        .expiration_ttl(PASSWORD_ATTEMPT_WINDOW)
+        .execute()
+        .await?;
+
+    let remaining = MAX_PASSWORD_ATTEMPTS.saturating_sub(attempts);
+    password_response("invalid_password", Some(remaining), None, 401)
+}
+
+fn password_response(
+    error: &'static str,
+    attempts_remaining: Option<u32>,
+    retry_after: Option<u64>,
+    status: u16,
+) -> Result<Option<(&'static str, Response)>, Error> {
+    let response = Response::from_json(&SharePasswordResponse {
+        error: error.to_string(),
+        attempts_remaining,
+        retry_after,
+    })?
+    .with_status(status);
+
+    Ok(Some((error, response)))
 }
@@ -0,0 +1,189 @@
+use crate::AppState;
+use crate::routes::share_access::ShareVisit;
+use crate::routes::share_collection::collection_entry;
+use crate::routes::share_download::{consume_download, inline_type, open_share, presign};
+use crate::routes::zip::stream_archive;
+use common::types::file::explode::ExplodeResponse;
+use common::types::file::file_claims::FileShare;
+use common::types::file::metadata::MetadataResponse;
+use common::types::file::share::ShareDownloadResponse;
+use common::types::file::share_collection::SharedCollectionZipRequest;
+use common::types::file::share_folder::{
+    ShareBrowseRequest, ShareFileRequest, ShareZipRequest, SharedEntryRequest, SharedExplodeRequest,
+    SharedBrowseRequest,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use worker::*;
+
+/// Lists a directory of a shared folder. Browsing isn't counted as a download.
+pub async fn handle_share_browse(
+    mut req: Request,
+    ctx: RouteContext<Arc<AppState>>,
+) -> Result<Response> {
+    let payload: ShareBrowseRequest = req.json().await?;
+    let visit = ShareVisit::new(&req, &payload.token, "browse");
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    let claims = match open_share(&ctx.data, &kv, &visit, payload.password.as_deref()).await? {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    let (status, listing, _) = ctx
+        .data
+        .config
+        .make_unauthenticated_internal_request::<_, Value>(
+            "/internal/share/browse",
+            Method::Post,
+            &SharedBrowseRequest {
+                root_id: claims.file_id,
+                directory_id: payload.directory_id,
+                limit: payload.limit,
+                offset: payload.offset,
+            },
+            None,
+        )
+        .await?;
+
+    if status != 200 {
+        return Ok(Response::empty()?.with_status(status));
+    }
+
+    visit.record(&ctx.data, "ok");
+
+    Response::from_json(&listing)
+}
+
+/// Hands out a download URL for one file inside a shared folder or a collection.
+pub async fn handle_share_file(
+    mut req: Request,
+    ctx: RouteContext<Arc<AppState>>,
+) -> Result<Response> {
+    let state = ctx.data.clone();
+    let payload: ShareFileRequest = req.json().await?;
+    let visit = ShareVisit::new(&req, &payload.token, "download").file(&payload.file_id);
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    let claims = match open_share(&ctx.data, &kv, &visit, payload.password.as_deref()).await? {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    let entry = match shared_file(&state, &claims, &payload.token, &payload.file_id).await? {
+        Ok(entry) => entry,
+        Err(status) => return Ok(Response::empty()?.with_status(status)),
+    };
+
+    let downloads_remaining = match consume_download(&state, &visit, &claims).await? {
+        Ok(remaining) => remaining,
+        Err(denied) => return Ok(denied),
+    };
+
+    let inline_type = inline_type(payload.inline, &entry.content_type);
+    let presigned_url = presign(&state, &entry.owner_id, &payload.file_id, &entry.file_name, inline_type)?;
+    let owner = crate::routes::share_download::owner_name(&ctx, &entry.owner_id).await?;
+
+    Response::from_json(&ShareDownloadResponse {
+        presigned_url,
+        file_type: entry.content_type,
+        file_name: entry.file_name,
+        file_size: entry.size,
+        created_at: entry.created_at.to_string(),
+        owner,
+        downloads_remaining,
+        is_directory: false,
+        is_collection: false,
+    })
+}
+
+/// One file inside a shared folder or a collection, `Err` with the status to answer when it
+/// isn't part of the share.
+pub(crate) async fn shared_file(
+    state: &AppState,
+    claims: &FileShare,
+    token: &str,
+    file_id: &str,
+) -> Result<std::result::Result<MetadataResponse, u16>> {
+    if claims.collection {
+        return Ok(collection_entry(state, token, file_id).await?.ok_or(404));
+    }
+
+    let (status, entry, _) = state
+        .config
+        .make_unauthenticated_internal_request::<_, Value>(
+            "/internal/share/entry",
+            Method::Post,
+            &SharedEntryRequest {
+                root_id: claims.file_id.clone(),
+                file_id: file_id.to_string(),
+            },
+            None,
+        )
+        .await?;
+
+    if status != 200 {
+        return Ok(Err(status));
+    }
+
+    Ok(Ok(serde_json::from_value(entry)?))
+}
+
+/// Streams a zip of a shared folder or collection, or of the selected entries in it. The whole
+/// archive counts as a single download.
+pub async fn handle_share_zip(
+    mut req: Request,
+    ctx: RouteContext<Arc<AppState>>,
+) -> Result<Response> {
+    let state = ctx.data.clone();
+    let payload: ShareZipRequest = req.json().await?;
+    let compress = payload.compress.unwrap_or(false);
+    let format = payload.archive_format.unwrap_or_default();
+    let visit = ShareVisit::new(&req, &payload.token, "zip");
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    let claims = match open_share(&ctx.data, &kv, &visit, payload.password.as_deref()).await? {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    let (status, items, _) = if claims.collection {
+        state
+            .config
+            .make_unauthenticated_internal_request::<_, Value>(
+                "/internal/share/collection/zip",
+                Method::Post,
+                &SharedCollectionZipRequest {
+                    token: payload.token.clone(),
+                    item_ids: payload.item_ids.unwrap_or_default(),
+                },
+                None,
+            )
+            .await?
+    } else {
+        state
+            .config
+            .make_unauthenticated_internal_request::<_, Value>(
+                "/internal/share/explode",
+                Method::Post,
+                &SharedExplodeRequest {
+                    root_id: claims.file_id.clone(),
+                    item_ids: payload.item_ids.unwrap_or_default(),
+                },
+                None,
+            )
+            .await?
+    };
+
+    if status != 200 {
+        return Ok(Response::empty()?.with_status(status));
+    }
+
+    let items: ExplodeResponse = serde_json::from_value(items)?;
+
+    if let Err(denied) = consume_download(&state, &visit, &claims).await? {
+        return Ok(denied);
+    }
+
+    stream_archive(items, compress, format)
+}
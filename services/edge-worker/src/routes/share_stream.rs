@@ -0,0 +1,130 @@
+use crate::AppState;
+use crate::routes::download::{content_disposition, previewable};
+use crate::routes::share_access::ShareVisit;
+use crate::routes::share_download::{open_share, presign};
+use crate::routes::share_folder::shared_file;
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
+use common::types::file::metadata::{MetadataRequest, MetadataResponse};
+use std::sync::Arc;
+use wasm_bindgen::JsValue;
+use worker::*;
+
+/// Storage headers worth passing on, the rest describe the bucket rather than the file.
+const FORWARDED_HEADERS: [&str; 4] = ["Content-Length", "Content-Range", "ETag", "Last-Modified"];
+
+/// Serves a shared file through the edge so browsers can play and render it in place, at
+/// `/download/share/stream/:token`, with `?f=` naming a file inside a folder or collection.
+/// `Range` is passed on to storage so players can seek without fetching the whole file.
+///
+/// Only links anyone may open qualify: a `<video>` element can't send a password, and every
+/// range it fetches would count against a download limit. Those links preview through the
+/// inline URL from `/download/share/create` instead.
+pub async fn handle_share_stream(req: Request, ctx: RouteContext<Arc<AppState>>) -> Result<Response> {
+    let state = ctx.data.clone();
+
+    let Some(token) = ctx.param("token").cloned() else {
+        return error_response(ErrorCode::NotFound, "Link not found");
+    };
+
+    let file_id = req
+        .url()?
+        .query_pairs()
+        .find(|(key, _)| key == "f")
+        .map(|(_, value)| value.into_owned());
+    let range = req.headers().get("Range")?;
+
+    let visit = ShareVisit::new(&req, &token, "view");
+
+    let kv = ctx.kv("DOWNLOAD_SESSIONS")?;
+    let claims = match open_share(&state, &kv, &visit, None).await? {
+        Ok(claims) => claims,
+        Err(denied) => return Ok(denied),
+    };
+
+    if claims.max_downloads.is_some() {
+        return error_response(ErrorCode::Forbidden, "Links with a download limit can't be streamed");
+    }
+
+    let (file_id, entry, visit) = match file_id {
+        Some(file_id) => match shared_file(&state, &claims, &token, &file_id).await? {
+            Ok(entry) => {
+                let visit = visit.file(&file_id);
+                (file_id, entry, visit)
+            }
+            Err(status) => return Ok(Response::empty()?.with_status(status)),
+        },
+        None if claims.collection => return error_response(ErrorCode::BadRequest, "Pick a file of the collection"),
+        None => {
+            let metadata_request = serde_json::to_string(&MetadataRequest {
+                file_id: claims.file_id.clone(),
+            })?;
+
+            let mut metadata_init = RequestInit::new();
+            metadata_init.with_method(Method::Post)
+                .with_body(Some(JsValue::from_str(&metadata_request)));
+
+            let metadata_req = Request::new_with_init("http://internal/metadata", &metadata_init)?;
+            let mut metadata_response = crate::routes::metadata::handle_metadata_inner(metadata_req, &ctx).await?;
+
+            if metadata_response.status_code() != 200 {
+                return Ok(Response::empty()?.with_status(metadata_response.status_code()));
+            }
+
+            let entry = metadata_response.json::<MetadataResponse>().await?;
+            (claims.file_id.clone(), entry, visit)
+        }
+    };
+
+    if entry.is_directory {
+        return error_response(ErrorCode::BadRequest, "Folders can't be streamed");
+    }
+
+    if !previewable(&entry.content_type) {
+        return error_response(ErrorCode::UnsupportedMediaType, "This file can't be previewed");
+    }
+
+    let url = presign(&state, &entry.owner_id, &file_id, &entry.file_name, Some(&entry.content_type))?;
+
+    let upstream_headers = Headers::new();
+    if let Some(range) = &range {
+        upstream_headers.set("Range", range)?;
+    }
+
+    let mut upstream_init = RequestInit::new();
+    upstream_init.with_method(Method::Get).with_headers(upstream_headers);
+
+    let upstream = Fetch::Request(Request::new_with_init(&url, &upstream_init)?).send().await?;
+    let status = upstream.status_code();
+
+    if !matches!(status, 200 | 206 | 416) {
+        console_error!("Storage answered {} when streaming {}", status, file_id);
+        return error_response(ErrorCode::Upstream, "Failed to fetch the file");
+    }
+
+    // A player fetches many ranges of one file, only the first counts as a visit.
+    if range.as_deref().is_none_or(|range| range.starts_with("bytes=0-")) {
+        visit.record(&state, "ok");
+    }
+
+    let headers = Headers::new();
+    for name in FORWARDED_HEADERS {
+        if let Some(value) = upstream.headers().get(name)? {
+            headers.set(name, &value)?;
+        }
+    }
+
+    headers.set("Accept-Ranges", "bytes")?;
+    headers.set("Content-Type", &entry.content_type)?;
+    headers.set("Content-Disposition", &content_disposition(true, &entry.file_name))?;
+    headers.set("X-Content-Type-Options", "nosniff")?;
+    headers.set("Content-Security-Policy", "sandbox")?;
+    headers.set("Cache-Control", "private, max-age=300")?;
+
+    let (_, body) = upstream.into_parts();
+
+    Ok(Response::builder()
+        .with_status(status)
+        .with_headers(headers)
+        .body(body))
+}
@@ -0,0 +1,291 @@
+use crate::routes::zip::{
+    error_report, write_fill, ItemBody, Missing, ERRORS_ENTRY, ERRORS_NOTICE,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use common::types::file::explode::PresignedExplodedItem;
+use futures_util::StreamExt;
+use futures_util::io::{AsyncWrite, AsyncWriteExt};
+
+/// Everything in a tarball comes in blocks of this size.
+const BLOCK: u64 = 512;
+
+/// The ustar header holds names up to 100 bytes and sizes up to eleven octal digits. Longer
+/// paths, non-ASCII ones and bigger files are described by a PAX header in front instead.
+const USTAR_NAME: usize = 100;
+const USTAR_MAX_SIZE: u64 = 0o77777777777;
+
+/// Writes the items as a ustar archive. Entries are cut or zero-filled to their recorded size,
+/// so the archive is exactly `tar_size` long when `exact` is set, even if files go missing:
+/// their space is handed to an `ERRORS.txt` listing them, padded with newlines. The caller
+/// closes the writer.
+pub(crate) async fn write_tar<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    items: Vec<PresignedExplodedItem>,
+    mut fetch: impl AsyncFnMut(&PresignedExplodedItem) -> std::result::Result<ItemBody, String>,
+    exact: bool,
+) -> std::io::Result<()> {
+    let mut missing = Vec::new();
+    let mut shortfall: u64 = 0;
+
+    for item in items {
+        let size = item.size.max(0) as u64;
+
+        // Empty files have nothing to fetch and so can't go missing.
+        let body = match size {
+            0 => None,
+            _ => match fetch(&item).await {
+                Ok(body) => Some(body),
+                Err(reason) => {
+                    shortfall += entry_size(&item.virtual_path, size);
+                    missing.push(Missing {
+                        path: item.virtual_path,
+                        reason,
+                    });
+                    continue;
+                }
+            },
+        };
+
+        write_header(writer, &item.virtual_path, size, item.created_at).await?;
+
+        let mut written: u64 = 0;
+        let mut failure = None;
+
+        if let Some(mut body) = body {
+            while let Some(chunk) = body.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(err) => {
+                        failure = Some(err);
+                        break;
+                    }
+                };
+
+                let chunk = &chunk[..chunk.len().min((size - written) as usize)];
+                writer.write_all(chunk).await?;
+                written += chunk.len() as u64;
+            }
+        }
+
+        // The header has promised `size` bytes, the rest of a broken file is zeros.
+        if written < size {
+            write_fill(writer, 0, size - written).await?;
+            missing.push(Missing {
+                path: item.virtual_path,
+                reason: format!(
+                    "incomplete, {} of {} bytes{}",
+                    written,
+                    size,
+                    failure.map(|failure| format!(" ({})", failure)).unwrap_or_default()
+                ),
+            });
+        }
+
+        write_fill(writer, 0, block_padding(size)).await?;
+    }
+
+    if !missing.is_empty() {
+        let report = error_report(&missing);
+
+        // Missing entries were at least two blocks each, so there is room for a header and a
+        // block of content. Files cut short are zero-filled in place and leave no room, when
+        // only those went wrong the list is left out.
+        let errors = if !exact {
+            Some((report.len() as u64, report))
+        } else if shortfall > 0 {
+            let mut len = shortfall - headers_size(ERRORS_ENTRY, shortfall - BLOCK);
+            len = shortfall - headers_size(ERRORS_ENTRY, len);
+            Some((len, if report.len() as u64 <= len { report } else { ERRORS_NOTICE.to_string() }))
+        } else {
+            None
+        };
+
+        if let Some((len, text)) = errors {
+            write_header(writer, ERRORS_ENTRY, len, Utc::now().fixed_offset()).await?;
+            writer.write_all(text.as_bytes()).await?;
+            write_fill(writer, b'\n', len - text.len() as u64).await?;
+            write_fill(writer, 0, block_padding(len)).await?;
+        }
+    }
+
+    // Two empty blocks end the archive.
+    write_fill(writer, 0, 2 * BLOCK).await
+}
+
+/// The exact length of the archive `write_tar` produces.
+pub(crate) fn tar_size(items: &[PresignedExplodedItem]) -> u64 {
+    items
+        .iter()
+        .map(|item| entry_size(&item.virtual_path, item.size.max(0) as u64))
+        .sum::<u64>()
+        + 2 * BLOCK
+}
+
+fn entry_size(path: &str, size: u64) -> u64 {
+    headers_size(path, size) + size + block_padding(size)
+}
+
+/// The header blocks in front of an entry's data, a PAX header and its records included.
+fn headers_size(path: &str, size: u64) -> u64 {
+    match pax_records(path, size) {
+        Some(records) => 2 * BLOCK + records.len() as u64 + block_padding(records.len() as u64),
+        None => BLOCK,
+    }
+}
+
+fn block_padding(len: u64) -> u64 {
+    (BLOCK - len % BLOCK) % BLOCK
+}
+
+/// PAX records for whatever doesn't fit the ustar header, `None` when everything does.
+fn pax_records(path: &str, size: u64) -> Option<String> {
+    let mut records = String::new();
+
+    if path.len() > USTAR_NAME || !path.is_ascii() {
+        records.push_str(&pax_record("path", path));
+    }
+
+    if size > USTAR_MAX_SIZE {
+        records.push_str(&pax_record("size", &size.to_string()));
+    }
+
+    (!records.is_empty()).then_some(records)
+}
+
+/// `<length> <key>=<value>\n`, where the length counts the whole record, its own digits too.
+fn pax_record(key: &str, value: &str) -> String {
+    let body = key.len() + value.len() + 3;
+    let mut len = body + body.to_string().len();
+    if len.to_string().len() > body.to_string().len() {
+        len += 1;
+    }
+
+    format!("{} {}={}\n", len, key, value)
+}
+
+async fn write_header<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    path: &str,
+    size: u64,
+    modified_at: DateTime<FixedOffset>,
+) -> std::io::Result<()> {
+    let mtime = modified_at.timestamp().max(0) as u64;
+
+    if let Some(records) = pax_records(path, size) {
+        let name = ascii_name(&format!("PaxHeaders/{}", path));
+        writer.write_all(&header_block(&name, records.len() as u64, mtime, b'x')).await?;
+        writer.write_all(records.as_bytes()).await?;
+        write_fill(writer, 0, block_padding(records.len() as u64)).await?;
+    }
+
+    writer
+        .write_all(&header_block(&ascii_name(path), size.min(USTAR_MAX_SIZE), mtime, b'0'))
+        .await
+}
+
+/// The path as it goes into the ustar name field, for readers that ignore PAX headers.
+fn ascii_name(path: &str) -> String {
+    let mut name = path
+        .chars()
+        .map(|c| if c.is_ascii() && !c.is_ascii_control() { c } else { '_' })
+        .collect::<String>();
+    name.truncate(USTAR_NAME);
+    name
+}
+
+fn header_block(name: &str, size: u64, mtime: u64, typeflag: u8) -> [u8; BLOCK as usize] {
+    let mut header = [0u8; BLOCK as usize];
+
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    octal(&mut header[100..108], 0o644);
+    octal(&mut header[108..116], 0);
+    octal(&mut header[116..124], 0);
+    octal(&mut header[124..136], size);
+    octal(&mut header[136..148], mtime.min(0o77777777777));
+    header[156] = typeflag;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is taken with its own field read as spaces.
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|byte| *byte as u32).sum();
+    header[148..155].copy_from_slice(format!("{:06o}\0", checksum).as_bytes());
+
+    header
+}
+
+/// Zero-padded octal filling all but the last byte of the field, which stays NUL.
+fn octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    field[..width].copy_from_slice(format!("{:0width$o}", value).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::zip::tests::{fake_fetch, item};
+    use futures_executor::block_on;
+
+    fn assert_exact_size(items: Vec<PresignedExplodedItem>) {
+        let expected = tar_size(&items);
+        let mut archive = Vec::new();
+
+        block_on(write_tar(&mut archive, items, fake_fetch, true)).unwrap();
+
+        assert_eq!(archive.len() as u64, expected);
+        assert_eq!(archive.len() as u64 % BLOCK, 0);
+    }
+
+    #[test]
+    fn archive_matches_its_size() {
+        assert_exact_size(vec![
+            item("notes.txt", 12, "ok"),
+            item("photos/holiday.jpg", 70_000, "ok"),
+            item("empty", 0, "ok"),
+            item("aligned.bin", 1024, "ok"),
+        ]);
+    }
+
+    #[test]
+    fn long_and_non_ascii_paths_get_pax_headers() {
+        let long = format!("{}/report.pdf", "nested-directory".repeat(8));
+        assert!(pax_records(&long, 10).is_some());
+        assert!(pax_records("photos/übersicht.png", 10).is_some());
+
+        assert_exact_size(vec![
+            item(&long, 10, "ok"),
+            item("photos/übersicht.png", 600, "ok"),
+            item(&"x".repeat(USTAR_NAME), 5, "ok"),
+        ]);
+    }
+
+    #[test]
+    fn missing_files_are_replaced_by_the_error_list() {
+        assert_exact_size(vec![
+            item("notes.txt", 12, "ok"),
+            item("videos/big.mp4", 5_000, "missing"),
+            item("a", 1, "missing"),
+        ]);
+    }
+
+    #[test]
+    fn error_list_with_a_long_path_fits_its_gap() {
+        let long = format!("{}/lost.txt", "deep".repeat(40));
+
+        assert_exact_size(vec![item(&long, 1, "missing")]);
+    }
+
+    #[test]
+    fn files_are_cut_or_filled_to_their_recorded_size() {
+        assert_exact_size(vec![item("grown.log", 500, "long"), item("shrunk.log", 500, "short")]);
+    }
+
+    #[test]
+    fn pax_record_length_counts_itself() {
+        for value in ["a", &"b".repeat(90), &"c".repeat(994)] {
+            let record = pax_record("path", value);
+            let (len, _) = record.split_once(' ').unwrap();
+            assert_eq!(len.parse::<usize>().unwrap(), record.len());
+        }
+    }
+}
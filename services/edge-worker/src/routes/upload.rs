@@ -1,4 +1,6 @@
 use crate::{AppState, authenticate};
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
 use common::types::file::upload_complete::CompleteUploadRequest;
 use common::types::file::upload_init::{
     InitUploadInternalRequest, InitUploadInternalResponse, InitUploadRequest, InitUploadResponse,
@@ -16,18 +18,11 @@ pub async fn handle_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -
 
     let state = ctx.data;
 
-    let access_key = state.config.access_key.clone();
-    let secret_key = state.config.secret_key.clone();
-    let bucket_name = state.config.bucket.clone();
-    let url = state.config.endpoint.clone();
-
-    let bucket = Bucket::new(Url::from_str(&url)?, UrlStyle::Path, bucket_name, "auto").unwrap();
-
     let req_body = &req.json::<InitUploadRequest>().await?;
     let file_id = Uuid::new_v4();
 
     if req_body.part_count >= 2000 {
-        return Response::error("Part count must be less than 2000", 400);
+        return error_response(ErrorCode::BadRequest, "Part count must be less than 2000");
     }
 
     let internal_req = InitUploadInternalRequest {
@@ -56,29 +51,50 @@ pub async fn handle_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -
 
     let result: InitUploadInternalResponse = serde_json::from_value(result.1)?;
 
+    // Parts go under the owner of the target folder, which is the caller unless it was shared.
+    let urls = presign_parts(&state, &result.owner_id, &file_id.to_string(), &result.upload_id, req_body.part_count)?;
+
+    Ok(Response::from_json(&InitUploadResponse {
+        file_id: file_id.to_string(),
+        upload_urls: urls,
+        upload_id: result.upload_id,
+    })?
+    .with_status(200))
+}
+
+/// Presigned URLs for every part of a multipart upload, stored under `{owner_id}/{file_id}`.
+pub(crate) fn presign_parts(
+    state: &AppState,
+    owner_id: &str,
+    file_id: &str,
+    upload_id: &str,
+    part_count: u64,
+) -> Result<Vec<String>> {
+    let access_key = state.config.access_key.clone();
+    let secret_key = state.config.secret_key.clone();
+    let bucket_name = state.config.bucket.clone();
+    let url = state.config.endpoint.clone();
+
+    let bucket = Bucket::new(Url::from_str(&url)?, UrlStyle::Path, bucket_name, "auto").unwrap();
+
     let credentials = Credentials::new(access_key.as_str(), secret_key.as_str());
 
     let presigned_url_duration = Duration::from_secs(60 * 60);
-    let url = format!("{}/{}", user.id, file_id);
+    let url = format!("{}/{}", owner_id, file_id);
 
     let mut urls = vec![];
-    for i in 1..=req_body.part_count {
+    for i in 1..=part_count {
         let action = bucket.upload_part(
             Some(&credentials),
             url.as_str(),
             i as u16,
-            result.upload_id.as_str(),
+            upload_id,
         );
         let presigned_url = action.sign(presigned_url_duration);
         urls.push(presigned_url.to_string());
     }
 
-    Ok(Response::from_json(&InitUploadResponse {
-        file_id: file_id.to_string(),
-        upload_urls: urls,
-        upload_id: result.upload_id,
-    })?
-    .with_status(200))
+    Ok(urls)
 }
 
 pub async fn handle_complete(
@@ -93,7 +109,7 @@ pub async fn handle_complete(
     let req_body = match serde_json::from_str::<CompleteUploadRequest>(&body) {
         Ok(data) => data,
         Err(_) => {
-            return Response::error("Invalid request body", 400);
+            return error_response(ErrorCode::BadRequest, "Invalid request body");
         }
     };
 
@@ -103,6 +119,9 @@ pub async fn handle_complete(
         .await
     {
         Ok(_) => Ok(Response::empty()?.with_status(204)),
-        Err(error) => Response::error(error.to_string(), 500),
+        Err(error) => {
+            console_error!("Failed to complete the upload: {}", error);
+            error_response(ErrorCode::Internal, ErrorCode::Internal.default_message())
+        }
     }
 }
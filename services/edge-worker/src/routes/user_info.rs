@@ -1,4 +1,6 @@
 use crate::{AppState, try_authenticate};
+use crate::types::error::error_response;
+use common::types::error::ErrorCode;
 use common::types::user::user_info::{UserInfoPublicResponse, UserInfoRequest, UserInfoResponse};
 use serde_json::Value;
 use std::sync::Arc;
@@ -17,7 +19,7 @@ pub async fn handle_info_inner(mut req: Request, ctx: &RouteContext<Arc<AppState
     } else if let Ok(payload) = &payload {
         &payload.account_id
     } else {
-        return Response::error("Missing account_id in request body and no valid authentication token provided", 400);
+        return error_response(ErrorCode::BadRequest, "Missing account_id in request body and no valid authentication token provided");
     };
 
     let state = ctx.data.clone();
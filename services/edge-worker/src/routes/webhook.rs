@@ -0,0 +1,89 @@
+use crate::{authenticate, AppState};
+use common::types::webhook::create::CreateWebhookRequest;
+use common::types::webhook::deliveries::ListDeliveriesRequest;
+use common::types::webhook::list::WebhookRequest;
+use serde_json::Value;
+use std::sync::Arc;
+use worker::{Method, Request, Response, RouteContext};
+
+pub async fn handle_create(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: CreateWebhookRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/webhook/create",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_list(req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/webhook/list",
+        &user,
+        Method::Post,
+        &Value::Null
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_delete(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: WebhookRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/webhook/delete",
+        &user,
+        Method::Delete,
+        &payload
+    ).await?;
+
+    if response.0 == 200 {
+        return Ok(Response::empty()?.with_status(204));
+    }
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_deliveries(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: ListDeliveriesRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/webhook/deliveries",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
+
+pub async fn handle_enable(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> worker::Result<Response> {
+    let user = authenticate!(&req, &ctx);
+    let state = &ctx.data;
+
+    let payload: WebhookRequest = req.json().await?;
+
+    let response = state.config.make_internal_request::<_, Value>(
+        "/internal/webhook/enable",
+        &user,
+        Method::Post,
+        &payload
+    ).await?;
+
+    Ok(Response::from_json(&response.1)?.with_status(response.0))
+}
@@ -1,8 +1,11 @@
 use crate::{AppState, authenticate};
 use async_zip::base::write::ZipFileWriter;
-use async_zip::{Compression, ZipDateTimeBuilder, ZipEntryBuilder};
-use common::types::file::explode::{ExplodeResponse, ZipRequest};
-use futures_util::StreamExt;
+use async_zip::{Compression, ZipDateTime, ZipEntryBuilder};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use crate::routes::tar::{tar_size, write_tar};
+use async_compression::futures::write::GzipEncoder;
+use common::types::file::explode::{ArchiveFormat, ExplodeResponse, PresignedExplodedItem, ZipRequest};
+use futures_util::{Stream, StreamExt};
 use futures_util::io::{AsyncWrite, AsyncWriteExt};
 use serde_json::Value;
 use std::pin::Pin;
@@ -11,13 +14,13 @@ use std::task::{Context, Poll};
 use web_sys::{TransformStream, WritableStreamDefaultWriter};
 use worker::*;
 
-struct WebStreamWriter {
+pub(crate) struct WebStreamWriter {
     writer: WritableStreamDefaultWriter,
     fut: Option<wasm_bindgen_futures::JsFuture>,
 }
 
 impl WebStreamWriter {
-    fn new(writer: WritableStreamDefaultWriter) -> Self {
+    pub(crate) fn new(writer: WritableStreamDefaultWriter) -> Self {
         Self { writer, fut: None }
     }
 }
@@ -32,10 +35,7 @@ impl AsyncWrite for WebStreamWriter {
             match Pin::new(&mut fut).poll(cx) {
                 Poll::Ready(Ok(_)) => {}
                 Poll::Ready(Err(e)) => {
-                    return Poll::Ready(Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("{:?}", e),
-                    )));
+                    return Poll::Ready(Err(std::io::Error::other(format!("{:?}", e))));
                 }
                 Poll::Pending => {
                     self.fut = Some(fut);
@@ -54,10 +54,7 @@ impl AsyncWrite for WebStreamWriter {
 
         match Pin::new(&mut fut).poll(cx) {
             Poll::Ready(Ok(_)) => Poll::Ready(Ok(buf.len())),
-            Poll::Ready(Err(e)) => Poll::Ready(Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("{:?}", e),
-            ))),
+            Poll::Ready(Err(e)) => Poll::Ready(Err(std::io::Error::other(format!("{:?}", e)))),
             Poll::Pending => {
                 self.fut = Some(fut);
                 Poll::Ready(Ok(buf.len()))
@@ -97,10 +94,6 @@ pub async fn handle_zip(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> R
     let state = ctx.data;
     let body = req.text().await?;
 
-    let ts = TransformStream::new().map_err(|_| Error::from("TS Fail"))?;
-    let writable = ts.writable();
-    let readable = ts.readable();
-
     let req_body: ZipRequest =
         serde_json::from_str(&body).map_err(|_| Error::from("Invalid JSON"))?;
 
@@ -115,74 +108,88 @@ pub async fn handle_zip(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> R
 
     let items: ExplodeResponse = serde_json::from_value(items_raw.1)?;
 
-    let items_clone = items.clone();
-    wasm_bindgen_futures::spawn_local(async move {
-        let writer = match writable.get_writer() {
-            Ok(writer) => writer,
-            Err(_) => {
-                console_error!("Failed to get writer from writable stream");
-                return;
-            }
-        };
-        let web_writer = WebStreamWriter::new(writer);
-        let mut zip = ZipFileWriter::new(web_writer);
-
-        for item in items_clone.items {
-            let presign_url: Url = match item.presign_url.parse() {
-                Ok(url) => url,
-                Err(e) => {
-                    console_error!("Failed to parse presign URL: {:?}", e);
-                    continue;
+    stream_archive(
+        items,
+        req_body.compress.unwrap_or(false),
+        req_body.archive_format.unwrap_or_default(),
+    )
+}
+
+/// Streams the exploded items into an archive as the response body, fetching each entry
+/// from its presigned URL as it is written. With `compress`, zip entries of types that
+/// compress well are deflated.
+pub(crate) fn stream_archive(items: ExplodeResponse, compress: bool, format: ArchiveFormat) -> Result<Response> {
+    let ts = TransformStream::new().map_err(|_| Error::from("TS Fail"))?;
+    let writable = ts.writable();
+    let writer = writable
+        .get_writer()
+        .map_err(|_| Error::from("Failed to get writer from writable stream"))?;
+    let writer = WebStreamWriter::new(writer);
+
+    // Only a gzipped or deflated archive's size is unknown until it's written.
+    let archive_size = match format {
+        ArchiveFormat::Zip => {
+            let zip64 = needs_zip64(&items.items);
+            let deflated = items
+                .items
+                .iter()
+                .any(|item| compression(compress, &item.file_type) == Compression::Deflate);
+            let archive_size = (!deflated).then(|| stored_archive_size(&items.items, zip64));
+
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(err) = write_archive(writer, items.items, fetch_item, compress, zip64, archive_size).await {
+                    console_error!("Failed to write the archive: {:?}", err);
                 }
-            };
+            });
 
-            if let Ok(mut s3_resp) = Fetch::Url(presign_url).send().await {
-                if s3_resp.status_code() == 200 {
-                    let entry = ZipEntryBuilder::new(item.virtual_path.into(), Compression::Stored);
-
-                    let unix_time = item.created_at.timestamp() as u64;
-
-                    let dt = ZipDateTimeBuilder::new()
-                        .year((unix_time / 31_536_000 + 1970) as i32)
-                        .month(((unix_time % 31_536_000) / 2_592_000 + 1) as u32)
-                        .day(((unix_time % 2_592_000) / 86_400 + 1) as u32)
-                        .hour(((unix_time % 86_400) / 3600) as u32)
-                        .minute(((unix_time % 3600) / 60) as u32)
-                        .second((unix_time % 60) as u32)
-                        .build();
-
-                    if let Ok(mut entry_writer) = zip
-                        .write_entry_stream(entry.last_modification_date(dt))
-                        .await
-                    {
-                        if let Ok(mut stream) = s3_resp.stream() {
-                            while let Some(Ok(chunk)) = stream.next().await {
-                                let _ = entry_writer.write_all(&chunk.to_vec()).await;
-                            }
-                        }
-                        let _ = entry_writer.close().await;
-                    }
+            archive_size
+        }
+        ArchiveFormat::Tar => {
+            let archive_size = tar_size(&items.items);
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut writer = writer;
+                if let Err(err) = write_tar(&mut writer, items.items, fetch_item, true).await {
+                    console_error!("Failed to write the archive: {:?}", err);
                 }
-            }
+                let _ = writer.close().await;
+            });
+
+            Some(archive_size)
         }
-        let _ = zip.close().await;
-    });
+        ArchiveFormat::TarGz => {
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut writer = GzipEncoder::new(writer);
+                if let Err(err) = write_tar(&mut writer, items.items, fetch_item, false).await {
+                    console_error!("Failed to write the archive: {:?}", err);
+                }
+                let _ = writer.close().await;
+            });
+
+            None
+        }
+    };
 
-    let raw_resp = web_sys::Response::new_with_opt_readable_stream(Some(&readable))
+    let raw_resp = web_sys::Response::new_with_opt_readable_stream(Some(&ts.readable()))
         .map_err(|_| Error::from("Response Construct Fail"))?;
 
-    let total_size: i64 = items.items.iter().map(|i| i.size).sum();
+    let (content_type, extension) = match format {
+        ArchiveFormat::Zip => ("application/zip", "zip"),
+        ArchiveFormat::Tar => ("application/x-tar", "tar"),
+        ArchiveFormat::TarGz => ("application/gzip", "tar.gz"),
+    };
 
     let headers = Headers::new();
-    headers.set("Content-Length", &total_size.to_string())?;
-    headers.set("Content-Type", "application/zip")?;
+    if let Some(archive_size) = archive_size {
+        headers.set("Content-Length", &archive_size.to_string())?;
+        headers.set("X-Archive-Size", &archive_size.to_string())?;
+    }
+    headers.set("Content-Type", content_type)?;
     headers.set(
         "Content-Disposition",
-        "attachment; filename=\"archive.zip\"",
+        &format!("attachment; filename=\"archive.{}\"", extension),
     )?;
 
-    headers.set("X-Archive-Size", &total_size.to_string())?;
-
     headers.set(
         "Access-Control-Expose-Headers",
         "Content-Length, Content-Disposition, X-Archive-Size",
@@ -190,3 +197,393 @@ pub async fn handle_zip(mut req: Request, ctx: RouteContext<Arc<AppState>>) -> R
 
     Ok(Response::from(raw_resp).with_headers(headers))
 }
+
+/// Name of the entry listing the files that couldn't be put into the archive.
+pub(crate) const ERRORS_ENTRY: &str = "ERRORS.txt";
+
+/// Stands in for the list when the bytes left over are too few to hold it.
+pub(crate) const ERRORS_NOTICE: &str = "Some files could not be added to this archive.\n";
+
+/// A file left out of the archive or cut short, and why.
+pub(crate) struct Missing {
+    pub(crate) path: String,
+    pub(crate) reason: String,
+}
+
+/// An item's contents as they arrive, chunk by chunk. `Err` ends the file early with the reason.
+pub(crate) type ItemBody = Pin<Box<dyn Stream<Item = std::result::Result<Vec<u8>, String>>>>;
+
+/// Writes every item into the archive, then an `ERRORS.txt` for the ones storage failed on.
+/// Errors are only returned once the archive itself can't be written any more, usually
+/// because the client went away.
+///
+/// With `archive_size` the length has already been promised in `Content-Length`, so entries
+/// are cut at their recorded size and whatever the missing files would have taken up is
+/// filled by the error list, padded to fit, or by the archive comment when even that is
+/// too big.
+async fn write_archive<W: AsyncWrite + Unpin>(
+    writer: W,
+    items: Vec<PresignedExplodedItem>,
+    mut fetch: impl AsyncFnMut(&PresignedExplodedItem) -> std::result::Result<ItemBody, String>,
+    compress: bool,
+    zip64: bool,
+    archive_size: Option<u64>,
+) -> std::result::Result<(), async_zip::error::ZipError> {
+    let mut zip = if zip64 {
+        ZipFileWriter::new(writer)
+    } else {
+        ZipFileWriter::new(writer).force_no_zip64()
+    };
+
+    let mut missing = Vec::new();
+    let mut shortfall: u64 = 0;
+
+    for item in items {
+        let size = item.size.max(0) as u64;
+
+        let mut body = match fetch(&item).await {
+            Ok(body) => body,
+            Err(reason) => {
+                shortfall += stored_entry_size(&item.virtual_path, size, zip64);
+                missing.push(Missing {
+                    path: item.virtual_path,
+                    reason,
+                });
+                continue;
+            }
+        };
+
+        let entry = ZipEntryBuilder::new(item.virtual_path.clone().into(), compression(compress, &item.file_type))
+            .last_modification_date(dos_datetime(item.created_at));
+        let mut entry_writer = zip.write_entry_stream(entry).await?;
+
+        let mut written: u64 = 0;
+        let mut failure = None;
+
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    failure = Some(err);
+                    break;
+                }
+            };
+
+            let chunk = match archive_size {
+                Some(_) => &chunk[..chunk.len().min((size - written) as usize)],
+                None => &chunk[..],
+            };
+
+            entry_writer.write_all(chunk).await?;
+            written += chunk.len() as u64;
+        }
+
+        entry_writer.close().await?;
+
+        if written < size {
+            shortfall += size - written;
+            missing.push(Missing {
+                path: item.virtual_path,
+                reason: format!(
+                    "incomplete, {} of {} bytes{}",
+                    written,
+                    size,
+                    failure.map(|failure| format!(" ({})", failure)).unwrap_or_default()
+                ),
+            });
+        }
+    }
+
+    if !missing.is_empty() {
+        let errors_entry = stored_entry_size(ERRORS_ENTRY, 0, zip64);
+        let report = error_report(&missing);
+
+        // How long the entry must be and what it starts with. Without room for an entry, the
+        // archive comment takes up the gap instead.
+        let errors = match archive_size {
+            None => Some((report.len() as u64, report)),
+            Some(_) if shortfall >= errors_entry + ERRORS_NOTICE.len() as u64 => {
+                let len = shortfall - errors_entry;
+                Some((len, if report.len() as u64 <= len { report } else { ERRORS_NOTICE.to_string() }))
+            }
+            Some(_) => {
+                let mut notice = ERRORS_NOTICE.to_string();
+                notice.truncate(shortfall as usize);
+                notice.extend(std::iter::repeat_n('\n', shortfall as usize - notice.len()));
+                zip.comment(notice);
+                None
+            }
+        };
+
+        if let Some((len, text)) = errors {
+            let entry = ZipEntryBuilder::new(ERRORS_ENTRY.to_string().into(), Compression::Stored)
+                .last_modification_date(dos_datetime(Utc::now().fixed_offset()));
+            let mut entry_writer = zip.write_entry_stream(entry).await?;
+            entry_writer.write_all(text.as_bytes()).await?;
+            write_fill(&mut entry_writer, b'\n', len - text.len() as u64).await?;
+            entry_writer.close().await?;
+        }
+    }
+
+    zip.close().await?.close().await?;
+
+    Ok(())
+}
+
+/// The item's object from storage, `Err` with the reason for the error list.
+pub(crate) async fn fetch_item(item: &PresignedExplodedItem) -> std::result::Result<ItemBody, String> {
+    let url = item.presign_url.parse::<Url>().map_err(|err| err.to_string())?;
+    let mut response = Fetch::Url(url).send().await.map_err(|err| err.to_string())?;
+
+    if response.status_code() != 200 {
+        return Err(format!("storage answered {}", response.status_code()));
+    }
+
+    let stream = response.stream().map_err(|err| err.to_string())?;
+    Ok(Box::pin(stream.map(|chunk| chunk.map_err(|err| err.to_string()))))
+}
+
+/// The contents of `ERRORS.txt`, one line per file.
+pub(crate) fn error_report(missing: &[Missing]) -> String {
+    let lines = missing
+        .iter()
+        .map(|missing| format!("{}: {}\n", missing.path, missing.reason))
+        .collect::<String>();
+
+    format!("{} file(s) could not be added to this archive.\n\n{}", missing.len(), lines)
+}
+
+/// Writes `count` copies of `byte` a block at a time, for padding that may run to gigabytes.
+pub(crate) async fn write_fill<W: AsyncWrite + Unpin>(writer: &mut W, byte: u8, count: u64) -> std::io::Result<()> {
+    let block = [byte; 64 * 1024];
+    let mut remaining = count;
+
+    while remaining > 0 {
+        let len = remaining.min(block.len() as u64) as usize;
+        writer.write_all(&block[..len]).await?;
+        remaining -= len as u64;
+    }
+
+    Ok(())
+}
+
+/// Fixed parts of a streamed entry as `async_zip` writes them: the local header, the data
+/// descriptor and the central directory record. Zip64 adds an extra field to the local
+/// header, and a longer one to the central record.
+const LOCAL_HEADER: u64 = 30;
+const DATA_DESCRIPTOR: u64 = 16;
+const CENTRAL_HEADER: u64 = 46;
+const ZIP64_LOCAL_EXTRA: u64 = 20;
+const ZIP64_CENTRAL_EXTRA: u64 = 28;
+
+/// The end of central directory record, and the Zip64 record and locator before it.
+const END_OF_CENTRAL_DIRECTORY: u64 = 22;
+const ZIP64_END_OF_CENTRAL_DIRECTORY: u64 = 56 + 20;
+
+/// Bytes a stored entry takes up in the archive, its name appearing in both headers.
+fn stored_entry_size(path: &str, size: u64, zip64: bool) -> u64 {
+    let fixed = LOCAL_HEADER + DATA_DESCRIPTOR + CENTRAL_HEADER + 2 * path.len() as u64;
+
+    if zip64 {
+        fixed + ZIP64_LOCAL_EXTRA + ZIP64_CENTRAL_EXTRA + size
+    } else {
+        fixed + size
+    }
+}
+
+/// The exact length of an archive of stored entries, as long as every file arrives whole.
+/// `write_archive` keeps it exact when they don't.
+fn stored_archive_size(items: &[PresignedExplodedItem], zip64: bool) -> u64 {
+    let entries: u64 = items
+        .iter()
+        .map(|item| stored_entry_size(&item.virtual_path, item.size.max(0) as u64, zip64))
+        .sum();
+
+    if zip64 {
+        entries + ZIP64_END_OF_CENTRAL_DIRECTORY + END_OF_CENTRAL_DIRECTORY
+    } else {
+        entries + END_OF_CENTRAL_DIRECTORY
+    }
+}
+
+/// The most a zip without Zip64 records can address, both in entries and in bytes.
+const ZIP_MAX_ENTRIES: usize = u16::MAX as usize;
+const ZIP_MAX_OFFSET: u64 = u32::MAX as u64;
+
+/// Whether the archive may outgrow the classic zip limits. Small archives are written without
+/// Zip64 records, which some older tools still can't read. The bound allows for each entry's
+/// headers and for deflate growing incompressible data slightly.
+fn needs_zip64(items: &[PresignedExplodedItem]) -> bool {
+    // One more for a possible ERRORS.txt.
+    if items.len() + 1 >= ZIP_MAX_ENTRIES {
+        return true;
+    }
+
+    let bound: u64 = items
+        .iter()
+        .map(|item| {
+            let size = item.size.max(0) as u64;
+            size + size / 100 + 128 + 2 * item.virtual_path.len() as u64
+        })
+        .sum();
+
+    bound + 22 > ZIP_MAX_OFFSET
+}
+
+/// Zip keeps DOS times: no time zone, two second steps and only the years 1980 to 2107. Files
+/// are stamped in UTC and clamped into that range.
+fn dos_datetime(created_at: DateTime<FixedOffset>) -> ZipDateTime {
+    let earliest = Utc.with_ymd_and_hms(1980, 1, 1, 0, 0, 0).unwrap();
+    let latest = Utc.with_ymd_and_hms(2107, 12, 31, 23, 59, 58).unwrap();
+
+    ZipDateTime::from_chrono(&created_at.with_timezone(&Utc).clamp(earliest, latest))
+}
+
+fn compression(compress: bool, file_type: &str) -> Compression {
+    if compress && compressible(file_type) {
+        Compression::Deflate
+    } else {
+        Compression::Stored
+    }
+}
+
+/// Text and uncompressed formats. Media, archives and office documents are compressed already
+/// and only cost time to deflate again.
+fn compressible(file_type: &str) -> bool {
+    let essence = file_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+    essence.starts_with("text/")
+        || essence.ends_with("+json")
+        || essence.ends_with("+xml")
+        || matches!(
+            essence.as_str(),
+            "application/json"
+                | "application/xml"
+                | "application/javascript"
+                | "application/x-javascript"
+                | "application/sql"
+                | "application/rtf"
+                | "application/x-sh"
+                | "application/yaml"
+                | "application/x-yaml"
+                | "application/toml"
+                | "application/x-tar"
+                | "application/wasm"
+                | "application/postscript"
+                | "image/bmp"
+                | "image/tiff"
+                | "audio/wav"
+                | "audio/x-wav"
+        )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use futures_executor::block_on;
+
+    /// A stored item. `storage` says what `fake_fetch` does with it: `missing` fails, `short`
+    /// sends half its bytes, `long` more than it has, anything else exactly its size.
+    pub(crate) fn item(path: &str, size: i64, storage: &str) -> PresignedExplodedItem {
+        PresignedExplodedItem {
+            id: path.to_string(),
+            file_name: path.rsplit('/').next().unwrap_or_default().to_string(),
+            virtual_path: path.to_string(),
+            presign_url: storage.to_string(),
+            size,
+            file_type: "application/octet-stream".to_string(),
+            created_at: DateTime::parse_from_rfc3339("2026-05-01T12:00:00Z").unwrap(),
+        }
+    }
+
+    /// Stands in for storage, sending each file in two chunks.
+    pub(crate) async fn fake_fetch(item: &PresignedExplodedItem) -> std::result::Result<ItemBody, String> {
+        let size = item.size.max(0) as usize;
+        let len = match item.presign_url.as_str() {
+            "missing" => return Err("storage answered 404".to_string()),
+            "short" => size / 2,
+            "long" => size + 100,
+            _ => size,
+        };
+
+        let data = vec![b'x'; len];
+        let (first, second) = data.split_at(len / 2);
+        let chunks = vec![Ok(first.to_vec()), Ok(second.to_vec())];
+
+        Ok(Box::pin(futures_util::stream::iter(chunks)))
+    }
+
+    fn assert_exact_size(items: Vec<PresignedExplodedItem>) {
+        for zip64 in [false, true] {
+            let expected = stored_archive_size(&items, zip64);
+            let mut archive = Vec::new();
+
+            block_on(write_archive(&mut archive, items.clone(), fake_fetch, false, zip64, Some(expected))).unwrap();
+
+            assert_eq!(archive.len() as u64, expected, "zip64: {}", zip64);
+        }
+    }
+
+    #[test]
+    fn stored_archive_matches_its_size() {
+        assert_exact_size(vec![
+            item("notes.txt", 12, "ok"),
+            item("photos/holiday.jpg", 70_000, "ok"),
+            item("empty", 0, "ok"),
+            item("photos/übersicht.png", 300, "ok"),
+        ]);
+    }
+
+    #[test]
+    fn missing_file_is_replaced_by_the_error_list() {
+        assert_exact_size(vec![
+            item("notes.txt", 12, "ok"),
+            item("videos/big.mp4", 5_000, "missing"),
+        ]);
+    }
+
+    #[test]
+    fn small_gap_goes_into_the_comment() {
+        assert_exact_size(vec![
+            item("notes.txt", 12, "ok"),
+            item("a", 1, "missing"),
+            item("b.txt", 40, "short"),
+        ]);
+    }
+
+    #[test]
+    fn files_are_cut_to_their_recorded_size() {
+        assert_exact_size(vec![item("grown.log", 500, "long"), item("shrunk.log", 500, "short")]);
+    }
+
+    #[test]
+    fn small_archives_need_no_zip64() {
+        assert!(!needs_zip64(&[item("notes.txt", 12, "ok")]));
+        assert!(needs_zip64(&[item("huge.bin", u32::MAX as i64, "ok")]));
+    }
+
+    #[test]
+    fn too_many_entries_need_zip64() {
+        let items = (0..ZIP_MAX_ENTRIES).map(|index| item(&index.to_string(), 1, "ok")).collect::<Vec<_>>();
+
+        assert!(needs_zip64(&items));
+    }
+
+    #[test]
+    fn dos_times_are_clamped() {
+        let before = DateTime::parse_from_rfc3339("1970-01-01T00:00:00Z").unwrap();
+        let after = DateTime::parse_from_rfc3339("2200-01-01T00:00:00Z").unwrap();
+
+        assert_eq!(dos_datetime(before).year(), 1980);
+        assert_eq!(dos_datetime(after).year(), 2107);
+    }
+
+    #[test]
+    fn stored_entries_grow_by_the_zip64_extras() {
+        let classic = stored_entry_size("notes.txt", 12, false);
+        let zip64 = stored_entry_size("notes.txt", 12, true);
+
+        assert_eq!(classic, LOCAL_HEADER + DATA_DESCRIPTOR + CENTRAL_HEADER + 18 + 12);
+        assert_eq!(zip64 - classic, ZIP64_LOCAL_EXTRA + ZIP64_CENTRAL_EXTRA);
+    }
+}
@@ -1,7 +1,8 @@
 use crate::authentication::authentication::AuthenticatedUser;
+use common::types::error::{ApiError, ErrorCode, REQUEST_ID_HEADER};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use worker::{Env, Fetch, Headers, Method, Request, RequestInit};
+use worker::{Env, Fetch, Headers, Method, Request, RequestInit, Response};
 
 #[derive(Debug, Clone)]
 pub struct Configuration {
@@ -13,6 +14,8 @@ pub struct Configuration {
     pub share_secret: String,
     pub origin_secret: String,
     pub auth_server_uri: String,
+    /// Sent along with every internal request, see `REQUEST_ID_HEADER`.
+    pub request_id: String,
 }
 
 pub struct InternalResponse<R> {
@@ -21,8 +24,8 @@ pub struct InternalResponse<R> {
 }
 
 impl Configuration {
-    pub fn gather_configuration(env: Env) -> Configuration {
-        let config = Configuration {
+    pub fn gather_configuration(env: Env, request_id: String) -> Configuration {
+        Configuration {
             access_key: env.var("ACCESS_KEY").unwrap().to_string(),
             secret_key: env.var("SECRET_KEY").unwrap().to_string(),
             endpoint: env.var("ENDPOINT").unwrap().to_string(),
@@ -31,9 +34,8 @@ impl Configuration {
             share_secret: env.var("SHARE_SECRET").unwrap().to_string(),
             origin_secret: env.var("ORIGIN_SECRET").unwrap().to_string(),
             auth_server_uri: env.var("AUTH_SERVER_URI").unwrap().to_string(),
-        };
-
-        config
+            request_id,
+        }
     }
 
     pub async fn make_internal_request<T: Serialize, R: DeserializeOwned>(
@@ -47,15 +49,24 @@ impl Configuration {
         headers.set("Content-Type", "application/json")?;
         headers.set("x-user-id", user.id.as_str())?;
         headers.set("X-Origin-Secret", &self.origin_secret)?;
+        headers.set(REQUEST_ID_HEADER, &self.request_id)?;
 
         let cookie_value = format!("session={}", user.session_token);
         headers.set("Cookie", &cookie_value)?;
 
+        // Passed on for the audit log, the authentication service only sees the worker.
+        if let Some(client_ip) = &user.client_ip {
+            headers.set("X-Client-IP", client_ip)?;
+        }
+        if let Some(user_agent) = &user.user_agent {
+            headers.set("X-Client-User-Agent", user_agent)?;
+        }
+
         let url = format!("{}{}", self.auth_server_uri, path);
 
         let request = Request::new_with_init(
             &url,
-            &RequestInit::new()
+            RequestInit::new()
                 .with_body(Some(serde_json::to_string(payload)?.into()))
                 .with_headers(headers)
                 .with_method(method),
@@ -65,17 +76,7 @@ impl Configuration {
         let status = response.status_code();
         let text = response.text().await?;
 
-        let json_body: R = if text.is_empty() {
-            serde_json::from_str("null").map_err(|e| {
-                worker::Error::from(format!(
-                    "Raw body was empty. Error deserializing empty JSON: {}",
-                    e
-                ))
-            })?
-        } else {
-            serde_json::from_str(&text)
-                .map_err(|e| worker::Error::from(format!("Raw body was: {}. Error: {}", text, e)))?
-        };
+        let json_body: R = read_body(status, &text, "null")?;
 
         Ok((status, json_body))
     }
@@ -90,10 +91,20 @@ impl Configuration {
         let headers = Headers::new();
         headers.set("Content-Type", "application/json")?;
         headers.set("X-Origin-Secret", &self.origin_secret)?;
+        headers.set(REQUEST_ID_HEADER, &self.request_id)?;
+
+        if let Some(h) = incoming_headers
+            && let Ok(Some(cookie_str)) = h.get("Cookie")
+        {
+            headers.set("Cookie", &cookie_str)?;
+        }
 
         if let Some(h) = incoming_headers {
-            if let Ok(Some(cookie_str)) = h.get("Cookie") {
-                headers.set("Cookie", &cookie_str)?;
+            if let Ok(Some(client_ip)) = h.get("CF-Connecting-IP") {
+                headers.set("X-Client-IP", &client_ip)?;
+            }
+            if let Ok(Some(user_agent)) = h.get("User-Agent") {
+                headers.set("X-Client-User-Agent", &user_agent)?;
             }
         }
 
@@ -101,7 +112,7 @@ impl Configuration {
 
         let request = Request::new_with_init(
             &url,
-            &RequestInit::new()
+            RequestInit::new()
                 .with_body(Some(serde_json::to_string(payload)?.into()))
                 .with_headers(headers)
                 .with_method(method),
@@ -112,22 +123,57 @@ impl Configuration {
         let response_headers = response.headers().clone();
         let text = response.text().await?;
 
-        let json_body: R = if text.is_empty() {
-            serde_json::from_str("{}").map_err(|e| {
-                worker::Error::from(format!(
-                    "Raw body was empty. Error deserializing empty JSON: {}",
-                    e
-                ))
-            })?
-        } else {
-            serde_json::from_str(&text).map_err(|e| {
-                worker::Error::from(format!(
-                    "Raw body was: {}. Error deserializing JSON: {}",
-                    text, e
-                ))
-            })?
-        };
+        let json_body: R = read_body(status, &text, "{}")?;
 
         Ok((status, json_body, response_headers))
     }
+
+    /// Opens a long-lived GET against the authentication service and hands back the response
+    /// unread, for streams such as the change events.
+    pub async fn open_internal_stream(
+        &self,
+        path: &str,
+        user: &AuthenticatedUser,
+        last_event_id: Option<String>,
+    ) -> Result<Response, worker::Error> {
+        let headers = Headers::new();
+        headers.set("Accept", "text/event-stream")?;
+        headers.set("x-user-id", user.id.as_str())?;
+        headers.set("X-Origin-Secret", &self.origin_secret)?;
+        headers.set(REQUEST_ID_HEADER, &self.request_id)?;
+        headers.set("Cookie", &format!("session={}", user.session_token))?;
+
+        if let Some(last_event_id) = last_event_id {
+            headers.set("Last-Event-ID", &last_event_id)?;
+        }
+
+        let url = format!("{}{}", self.auth_server_uri, path);
+
+        let request = Request::new_with_init(
+            &url,
+            RequestInit::new()
+                .with_headers(headers)
+                .with_method(Method::Get),
+        )?;
+
+        Fetch::Request(request).send().await
+    }
+}
+
+/// Reads the body of an internal response, `empty` standing in for no body at all. Error
+/// responses that aren't what the caller expects, plain text from a proxy in between say, are
+/// read as a bare `ApiError` for their status, so callers can still pass the status on.
+fn read_body<R: DeserializeOwned>(status: u16, text: &str, empty: &str) -> Result<R, worker::Error> {
+    let text = if text.is_empty() { empty } else { text };
+
+    match serde_json::from_str(text) {
+        Ok(body) => Ok(body),
+        Err(_) if status >= 400 => {
+            let api_error = ApiError::from_code(ErrorCode::from_status(status));
+            serde_json::from_value(serde_json::to_value(api_error)?).map_err(|e| {
+                worker::Error::from(format!("Answered {} with: {}. Error: {}", status, text, e))
+            })
+        }
+        Err(e) => Err(worker::Error::from(format!("Raw body was: {}. Error: {}", text, e))),
+    }
 }
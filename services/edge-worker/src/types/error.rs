@@ -1,4 +1,6 @@
-use worker::Response;
+use common::types::error::{ApiError, ErrorCode};
+use serde_json::Value;
+use worker::{console_error, Response, ResponseBuilder};
 
 pub enum AuthError {
     MissingToken,
@@ -8,11 +10,18 @@ pub enum AuthError {
 
 impl From<AuthError> for Response {
     fn from(err: AuthError) -> Self {
-        match err {
-            AuthError::MissingToken => Response::error("Unauthorized: Missing Token", 401).unwrap(),
-            AuthError::InvalidToken => Response::error("Unauthorized: Invalid Token", 401).unwrap(),
-            AuthError::EnvError(_) => Response::error("Internal Server Error", 500).unwrap(),
-        }
+        let (code, message) = match err {
+            AuthError::MissingToken => (ErrorCode::Unauthorized, "Missing token"),
+            AuthError::InvalidToken => (ErrorCode::Unauthorized, "Invalid token"),
+            AuthError::EnvError(err) => {
+                console_error!("Failed to authenticate: {}", err);
+                (ErrorCode::Internal, ErrorCode::Internal.default_message())
+            }
+        };
+
+        // An empty error still gets its body from `envelope` on the way out.
+        error_response(code, message)
+            .unwrap_or_else(|_| ResponseBuilder::new().with_status(code.status()).empty())
     }
 }
 
@@ -20,4 +29,69 @@ impl From<worker::Error> for AuthError {
     fn from(err: worker::Error) -> Self {
         AuthError::EnvError(err)
     }
-}
\ No newline at end of file
+}
+
+/// An error response in the shared `ApiError` envelope.
+pub fn error_response(code: ErrorCode, message: &str) -> worker::Result<Response> {
+    Ok(Response::from_json(&ApiError::new(code, message))?.with_status(code.status()))
+}
+
+/// Brings an error response into the `ApiError` envelope and stamps it with the request's id,
+/// the same way the authentication service does. Plain text from `Response::error` becomes the
+/// message of a client error, server errors only carry the default message and whatever they
+/// said is logged. JSON objects of another shape pass through untouched.
+pub async fn envelope(mut response: Response, request_id: &str) -> worker::Result<Response> {
+    let status = response.status_code();
+
+    if status < 400 {
+        return Ok(response);
+    }
+
+    // `Response::error` sets no type, storage errors come as XML and say nothing useful.
+    let is_text = response
+        .headers()
+        .get("Content-Type")?
+        .is_none_or(|content_type| content_type.starts_with("text/plain"));
+
+    let text = response.text().await?;
+
+    if status >= 500 && !text.is_empty() {
+        console_error!("Failed with {} ({}): {}", status, request_id, text);
+    }
+
+    let envelope = match serde_json::from_str::<Value>(&text) {
+        Ok(Value::Object(fields)) => serde_json::from_value::<ApiError>(Value::Object(fields)).ok(),
+        Ok(Value::String(message)) => Some(bare_error(status, &message)),
+        _ if is_text => Some(bare_error(status, &text)),
+        _ => Some(bare_error(status, "")),
+    };
+
+    response.headers_mut().delete("Content-Length")?;
+    let (builder, _) = response.into_parts();
+
+    match envelope {
+        Some(api_error) => builder.from_json(&ApiError {
+            request_id: Some(request_id.to_string()),
+            ..api_error
+        }),
+        None => Ok(builder.fixed(text.into_bytes())),
+    }
+}
+
+/// An envelope for an error that came without one, keeping a client error's own words.
+fn bare_error(status: u16, message: &str) -> ApiError {
+    let code = ErrorCode::from_status(status);
+
+    match message.trim() {
+        message if status < 500 && !message.is_empty() => ApiError::new(code, message),
+        _ => ApiError::from_code(code),
+    }
+}
+
+/// Ids are passed on to the authentication service in `REQUEST_ID_HEADER`, keep them to what it
+/// accepts.
+pub fn request_id(cf_ray: Option<String>) -> String {
+    cf_ray
+        .filter(|id| !id.is_empty() && id.len() <= 128 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
@@ -1,25 +1,55 @@
 import { ShareDownloadRequest } from "@/lib/types/generated/ShareDownloadRequest";
 import { ShareDownloadResponse } from "@/lib/types/generated/ShareDownloadResponse";
+import { ShareFileRequest } from "@/lib/types/generated/ShareFileRequest";
+import { ShareZipRequest } from "@/lib/types/generated/ShareZipRequest";
 
 const EDGE_URL = process.env.NEXT_PUBLIC_EDGE_URL || "http://localhost:8787";
 
 export async function GET(request: Request) {
   const { searchParams } = new URL(request.url);
   const token = searchParams.get("t");
+  const fileId = searchParams.get("f");
 
   if (!token) return new Response("Unauthorized", { status: 401 });
 
-  const req: ShareDownloadRequest = { token };
-  const presignRes = await fetch(`${EDGE_URL}/download/share/create`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify(req),
-  });
+  // A single file picked from a collection or shared folder.
+  const presignRes = fileId
+    ? await fetch(`${EDGE_URL}/download/share/file`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ token, file_id: fileId } satisfies ShareFileRequest),
+      })
+    : await fetch(`${EDGE_URL}/download/share/create`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ token } satisfies ShareDownloadRequest),
+      });
 
   if (!presignRes.ok)
     return new Response("Error fetching metadata", { status: 500 });
 
   const res: ShareDownloadResponse = await presignRes.json();
+
+  // A shared folder or collection comes down as a single zip of everything in it.
+  if (res.is_directory || res.is_collection) {
+    const zipReq: ShareZipRequest = { token };
+    const zipRes = await fetch(`${EDGE_URL}/download/share/zip`, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(zipReq),
+    });
+
+    if (!zipRes.ok)
+      return new Response("Error creating archive", { status: 500 });
+
+    return new Response(zipRes.body, {
+      headers: {
+        "Content-Type": "application/zip",
+        "Content-Disposition": `attachment; filename="${res.file_name || "archive"}.zip"`,
+        "Cache-Control": "no-store",
+      },
+    });
+  }
   const fileRes = await fetch(res.presigned_url);
 
   const contentDisposition = fileRes.headers.get("Content-Disposition");
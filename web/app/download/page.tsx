@@ -1,10 +1,12 @@
 import { ShareDownloadRequest } from "@/lib/types/generated/ShareDownloadRequest";
 import { ShareDownloadResponse } from "@/lib/types/generated/ShareDownloadResponse";
-import { pretifyFileSize } from "@/lib/util/file";
+import { ShareItemsRequest } from "@/lib/types/generated/ShareItemsRequest";
+import { ShareItemsResponse } from "@/lib/types/generated/ShareItemsResponse";
+import { isPreviewable, pretifyFileSize } from "@/lib/util/file";
 import type { Metadata } from "next";
 import styles from "./page.module.scss";
 import Button from "@/components/general/Button";
-import { Download } from "lucide-react";
+import { Download, Eye } from "lucide-react";
 import AutoDownload from "@/components/general/AutoDownload";
 import NotFound from "../not-found";
 import LocalDate from "@/components/general/LocalDate";
@@ -18,7 +20,8 @@ interface SharePageProps {
 }
 
 async function getShare(token: string): Promise<ShareDownloadResponse | null> {
-  const req: ShareDownloadRequest = { token };
+  // Rendering the page must not use up a link with a download limit.
+  const req: ShareDownloadRequest = { token, metadata_only: true };
 
   const res = await fetch(`${EDGE_URL}/download/share/create`, {
     method: "POST",
@@ -31,6 +34,20 @@ async function getShare(token: string): Promise<ShareDownloadResponse | null> {
   return res.json();
 }
 
+async function getItems(token: string): Promise<ShareItemsResponse | null> {
+  const req: ShareItemsRequest = { token };
+
+  const res = await fetch(`${EDGE_URL}/download/share/items`, {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(req),
+    cache: "no-store",
+  });
+
+  if (!res.ok) return null;
+  return res.json();
+}
+
 export async function generateMetadata({
   searchParams,
 }: SharePageProps): Promise<Metadata> {
@@ -42,6 +59,8 @@ export async function generateMetadata({
 
   const ogImageUrl = `/share/opengraph-image?t=${encodeURIComponent(t)}`;
   const isVideo = share.file_type?.startsWith("video/");
+  // Links with a limit come back without a URL, and those can't be streamed either.
+  const streamUrl = share.presigned_url && `${EDGE_URL}/download/share/stream/${encodeURIComponent(t)}`;
 
   return {
     metadataBase: new URL("https://ldg.sh"),
@@ -51,8 +70,8 @@ export async function generateMetadata({
       siteName: "Ledger",
       type: isVideo ? "video.other" : "website",
       images: [{ url: ogImageUrl, width: 1200, height: 600 }],
-      ...(isVideo && {
-        videos: [{ url: share.presigned_url, type: share.file_type }],
+      ...(isVideo && streamUrl && {
+        videos: [{ url: streamUrl, type: share.file_type }],
       }),
     },
     twitter: { card: "summary_large_image" },
@@ -67,21 +86,39 @@ export default async function SharePage({ searchParams }: SharePageProps) {
   if (!share) return <NotFound />;
 
   const downloadUrl = `/api/share/download?t=${encodeURIComponent(t)}`;
-
-  const rows: Array<[string, ReactNode]> = [
-    ["File Name", share.file_name || "Unknown"],
-    ["File Size", pretifyFileSize(share.file_size) || "Unknown"],
-    ["File Type", share.file_type || "Unknown"],
-    ["Created", <LocalDate timestamp={share.created_at} type="full" />],
-    ["Owner", share.owner || "Unknown"],
-  ];
+  const previewUrl =
+    !share.is_collection && share.presigned_url && isPreviewable(share.file_type)
+      ? `${EDGE_URL}/download/share/stream/${encodeURIComponent(t)}`
+      : null;
+
+  // Collections are listed so each file can be fetched on its own, the zip is one click away.
+  const collection = share.is_collection ? await getItems(t) : null;
+  if (share.is_collection && !collection) return <NotFound />;
+
+  const rows: Array<[string, ReactNode]> = collection
+    ? [
+        ["Collection", collection.title],
+        ["Files", collection.items.length.toString()],
+        ["Total Size", pretifyFileSize(collection.total_size) || "Unknown"],
+        ["Created", <LocalDate timestamp={share.created_at} type="full" />],
+        ["Owner", share.owner || "Unknown"],
+      ]
+    : [
+        ["File Name", share.file_name || "Unknown"],
+        ["File Size", pretifyFileSize(share.file_size) || "Unknown"],
+        ["File Type", share.file_type || "Unknown"],
+        ["Created", <LocalDate timestamp={share.created_at} type="full" />],
+        ["Owner", share.owner || "Unknown"],
+      ];
 
   return (
     <main className={styles.page}>
-      <AutoDownload url={downloadUrl} />
+      {!collection && <AutoDownload url={downloadUrl} />}
       <div className={styles.content}>
         <div className={styles.header}>
-          <h1 className={styles.title}>Your download will begin shortly...</h1>
+          <h1 className={styles.title}>
+            {collection ? `${collection.items.length} files shared with you` : "Your download will begin shortly..."}
+          </h1>
           <p className={styles.subtitle}>
             Uploaded by <strong>{share.owner || "Unknown"}</strong> on{" "}
             <strong><LocalDate timestamp={share.created_at} type="date" /></strong> at{" "}
@@ -100,15 +137,45 @@ export default async function SharePage({ searchParams }: SharePageProps) {
           ))}
         </dl>
 
+        {collection && (
+          <>
+            <div className={styles.perforation} aria-hidden="true" />
+
+            <dl className={styles.table}>
+              {collection.items.map((item) => (
+                <div className={styles.row} key={item.id}>
+                  <dt className={styles.label} title={item.file_name}>
+                    <a
+                      className={styles.footerLink}
+                      href={`${downloadUrl}&f=${encodeURIComponent(item.id)}`}
+                    >
+                      {item.file_name}
+                    </a>
+                  </dt>
+                  <dd className={styles.value}>{pretifyFileSize(item.file_size)}</dd>
+                </div>
+              ))}
+            </dl>
+          </>
+        )}
+
         <div className={styles.perforation} aria-hidden="true" />
 
         <div className={styles.buttonContainer}>
           <Button
             icon={<Download size={14} strokeWidth={2.5} />}
-          label="Retry Download"
+          label={collection ? "Download All (.zip)" : "Retry Download"}
           variant="primary"
           href={downloadUrl}
           />
+          {previewUrl && (
+            <Button
+              icon={<Eye size={14} strokeWidth={2.5} />}
+              label="Open in Browser"
+              variant="secondary"
+              href={previewUrl}
+            />
+          )}
         </div>
       </div>
       <div className={styles.footer}>
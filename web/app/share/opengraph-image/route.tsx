@@ -12,7 +12,7 @@ export async function GET(request: Request) {
   const token = searchParams.get("t");
   if (!token) return new Response("Unauthorized", { status: 401 });
 
-  const req: ShareDownloadRequest = { token };
+  const req: ShareDownloadRequest = { token, metadata_only: true };
   const presignRes = await fetch(`${EDGE_URL}/download/share/create`, {
     method: "POST",
     headers: { "Content-Type": "application/json" },
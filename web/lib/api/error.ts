@@ -0,0 +1,34 @@
+import { ApiError } from "../types/generated/ApiError";
+import { ErrorCode } from "../types/generated/ErrorCode";
+
+/** A failed request, carrying what the `ApiError` envelope said about it. */
+export class RequestError extends Error {
+  code: ErrorCode | null;
+  requestId: string | null;
+
+  constructor(message: string, code: ErrorCode | null, requestId: string | null) {
+    super(message);
+    this.name = "RequestError";
+    this.code = code;
+    this.requestId = requestId;
+  }
+}
+
+/**
+ * Reads the error envelope off a failed response. `fallback` is used when the body isn't one,
+ * which only happens when something between us and the edge answered.
+ */
+export async function requestError(res: Response, fallback: string) {
+  const requestId = res.headers.get("X-Request-Id");
+
+  try {
+    const error: ApiError = await res.json();
+    if (typeof error.message === "string" && error.code) {
+      return new RequestError(error.message, error.code, error.request_id ?? requestId);
+    }
+  } catch {
+    // Not JSON, fall through.
+  }
+
+  return new RequestError(fallback, null, requestId);
+}
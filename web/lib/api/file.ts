@@ -12,7 +12,24 @@ import { MoveFilesRequest } from "../types/generated/MoveFilesRequest";
 import { RenameFileRequest } from "../types/generated/RenameFileRequest";
 import { ShareRequest } from "../types/generated/ShareRequest";
 import { ShareResponse } from "../types/generated/ShareResponse";
+import { ListSharesResponse } from "../types/generated/ListSharesResponse";
+import { ShareRevokeRequest } from "../types/generated/ShareRevokeRequest";
+import { ShareCollectionRequest } from "../types/generated/ShareCollectionRequest";
+import { ShareAccessRequest } from "../types/generated/ShareAccessRequest";
+import { ShareAccessResponse } from "../types/generated/ShareAccessResponse";
+import { CreateGrantRequest } from "../types/generated/CreateGrantRequest";
+import { DeleteGrantRequest } from "../types/generated/DeleteGrantRequest";
+import { GrantRole } from "../types/generated/GrantRole";
+import { ListGrantsRequest } from "../types/generated/ListGrantsRequest";
+import { ListGrantsResponse } from "../types/generated/ListGrantsResponse";
+import { SharedWithMeResponse } from "../types/generated/SharedWithMeResponse";
+import { CreateFileRequestRequest } from "../types/generated/CreateFileRequestRequest";
+import { FileRequestElement } from "../types/generated/FileRequestElement";
+import { FileRequestTokenRequest } from "../types/generated/FileRequestTokenRequest";
+import { FileRequestUploadsResponse } from "../types/generated/FileRequestUploadsResponse";
+import { ListFileRequestsResponse } from "../types/generated/ListFileRequestsResponse";
 import { authenticatedFetch } from "./apiClient";
+import { requestError } from "./error";
 
 export async function listFiles(
   directoryPath: string,
@@ -34,7 +51,7 @@ export async function listFiles(
     body: JSON.stringify(request),
   });
 
-  if (!res.ok) throw new Error("Failed to fetch file list");
+  if (!res.ok) throw await requestError(res, "Failed to fetch file list");
   const json: ListFilesResponse = await res.json();
 
   const files = json.files;
@@ -87,12 +104,9 @@ export async function createUpload(
     body: JSON.stringify(request),
   });
 
-  const body = await res.text();
+  if (!res.ok) throw await requestError(res, "Failed to create upload");
 
-  const json: InitUploadResponse = JSON.parse(body);
-
-  if (!res.ok)
-    throw new Error("Failed to create upload: " + JSON.stringify(res));
+  const json: InitUploadResponse = await res.json();
 
   return json;
 }
@@ -156,7 +170,7 @@ export async function completeUpload(
   });
 
   if (!response.ok) {
-    throw new Error(`Failed to complete upload: ${response.statusText}`);
+    throw await requestError(response, "Failed to complete upload");
   }
 }
 
@@ -220,9 +234,9 @@ export async function copyFiles(fileIds: string[], destinationPath: string) {
     body: JSON.stringify(request),
   });
 
-  const json: CopyFilesResponse = await res.json();
+  if (!res.ok) throw await requestError(res, "Failed to copy file");
 
-  if (!res.ok) throw new Error("Failed to copy file");
+  const json: CopyFilesResponse = await res.json();
 
   return json.file_ids;
 }
@@ -247,10 +261,161 @@ export async function getShareLink(
     body: JSON.stringify(request),
   });
 
-  const json: ShareResponse = await res.json();
+  if (!res.ok) throw await requestError(res, "Failed to create share token");
 
-  if (!res.ok) throw new Error("Failed to create share token");
+  const json: ShareResponse = await res.json();
 
   const baseUrl = window.location.origin;
   return `${baseUrl}/download?t=${json.token}`;
 }
+
+export async function shareCollection(request: ShareCollectionRequest) {
+  const res = await authenticatedFetch(`/file/share/collection`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to share the files");
+
+  const json: ShareResponse = await res.json();
+  return json;
+}
+
+export async function listShares() {
+  const res = await authenticatedFetch(`/file/share/list`, {
+    method: "POST",
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to list shares");
+
+  const json: ListSharesResponse = await res.json();
+  return json.shares;
+}
+
+/** Revokes a single link, or every link when no token is given. */
+export async function revokeShares(token?: string) {
+  const request: ShareRevokeRequest = token ? { token } : {};
+
+  const res = await authenticatedFetch(`/file/share/revoke`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  return res.ok;
+}
+
+export async function getShareAccess(token: string, offset: number = 0, limit: number = 50) {
+  const request: ShareAccessRequest = { token, offset, limit };
+
+  const res = await authenticatedFetch(`/file/share/access`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to load share access history");
+
+  const json: ShareAccessResponse = await res.json();
+  return json;
+}
+
+export async function listSharedWithMe() {
+  const res = await authenticatedFetch(`/file/shared`, {
+    method: "POST",
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to list shared files");
+
+  const json: SharedWithMeResponse = await res.json();
+  return json.files;
+}
+
+export async function grantAccess(fileId: string, email: string, role: GrantRole) {
+  const request: CreateGrantRequest = {
+    file_id: fileId,
+    email,
+    role,
+  };
+
+  const res = await authenticatedFetch(`/grant/create`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  return res.ok;
+}
+
+export async function listGrants(fileId: string) {
+  const request: ListGrantsRequest = { file_id: fileId };
+
+  const res = await authenticatedFetch(`/grant/list`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to list grants");
+
+  const json: ListGrantsResponse = await res.json();
+  return json.grants;
+}
+
+export async function revokeGrant(fileId: string, granteeId: string) {
+  const request: DeleteGrantRequest = {
+    file_id: fileId,
+    grantee_id: granteeId,
+  };
+
+  const res = await authenticatedFetch(`/grant/delete`, {
+    method: "DELETE",
+    body: JSON.stringify(request),
+  });
+
+  return res.ok;
+}
+
+export async function createFileRequest(request: CreateFileRequestRequest) {
+  const res = await authenticatedFetch(`/request/create`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to create file request");
+
+  const json: FileRequestElement = await res.json();
+  return json;
+}
+
+export async function listFileRequests() {
+  const res = await authenticatedFetch(`/request/list`, {
+    method: "POST",
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to list file requests");
+
+  const json: ListFileRequestsResponse = await res.json();
+  return json.requests;
+}
+
+export async function deleteFileRequest(token: string) {
+  const request: FileRequestTokenRequest = { token };
+
+  const res = await authenticatedFetch(`/request/delete`, {
+    method: "DELETE",
+    body: JSON.stringify(request),
+  });
+
+  return res.ok;
+}
+
+export async function listFileRequestUploads(token: string) {
+  const request: FileRequestTokenRequest = { token };
+
+  const res = await authenticatedFetch(`/request/uploads`, {
+    method: "POST",
+    body: JSON.stringify(request),
+  });
+
+  if (!res.ok) throw await requestError(res, "Failed to list file request uploads");
+
+  const json: FileRequestUploadsResponse = await res.json();
+  return json.uploads;
+}
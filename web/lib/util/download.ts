@@ -1,10 +1,12 @@
 "use client";
 
 import { authenticatedFetch } from "../api/apiClient";
+import { ArchiveFormat } from "../types/generated/ArchiveFormat";
 
 export const handleClientDownload = async (
   fileIds: string[],
   fileName?: string,
+  archiveFormat: ArchiveFormat = "zip",
 ) => {
   if (typeof window === "undefined") return;
 
@@ -32,7 +34,7 @@ export const handleClientDownload = async (
     const res = await authenticatedFetch(`/file/zip`, {
       method: "POST",
       headers: { "Content-Type": "application/json" },
-      body: JSON.stringify({ item_ids: fileIds }),
+      body: JSON.stringify({ item_ids: fileIds, archive_format: archiveFormat }),
     });
 
     if (!res.ok || !res.body) {
@@ -43,7 +45,7 @@ export const handleClientDownload = async (
     const totalSize = Number(res.headers.get("x-archive-size")) || 0;
 
     const fileStream = streamSaver.createWriteStream(
-      fileName || "ledger-archive-" + new Date().toISOString() + ".zip",
+      fileName || "ledger-archive-" + new Date().toISOString() + "." + archiveFormat,
       {
         size: totalSize,
         writableStrategy: undefined,
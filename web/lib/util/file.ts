@@ -12,3 +12,17 @@ export function pretifyFileSize(sizeInBytes: number): string {
     return `${sizeInGB} GB`;
   }
 }
+
+/** Mirrors the edge: only types a browser can show without running anything are opened inline. */
+export function isPreviewable(fileType?: string): boolean {
+  const essence = (fileType || "").split(";")[0].trim().toLowerCase();
+
+  return (
+    essence !== "image/svg+xml" &&
+    (essence.startsWith("image/") ||
+      essence.startsWith("video/") ||
+      essence.startsWith("audio/") ||
+      essence === "application/pdf" ||
+      essence === "text/plain")
+  );
+}